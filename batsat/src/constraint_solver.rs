@@ -0,0 +1,229 @@
+//! # High-Level Constraint Solving
+//!
+//! A solver that owns its variable manager and encodes non-clausal
+//! constraints internally, so that constraints can be stated and solved
+//! without keeping a variable manager, encodings and the solver in sync.
+
+use rustsat::{
+    encodings::{card, pb},
+    instances::{BasicVarManager, Cnf, ManageVars},
+    solvers::{Solve, SolveIncremental, SolverResult},
+    types::{
+        constraints::{CardConstraint, PBConstraint},
+        Clause, Lit, RsHashMap, TernaryVal, Var,
+    },
+};
+
+use crate::BatsatBasicSolver;
+
+/// A [`BatsatBasicSolver`] together with a [`BasicVarManager`]. All variables
+/// in added constraints are marked as used, auxiliary variables of encodings
+/// are allocated after them.
+///
+/// ```
+/// # use rustsat::{clause, lit, solvers::SolverResult, types::Lit};
+/// # use rustsat_batsat::ConstraintSolver;
+/// let mut solver = ConstraintSolver::default();
+/// solver.add_clause(clause![lit![0], lit![1]]).unwrap();
+/// solver.add_pb_ub([(lit![0], 3), (lit![1], 2)], 1).unwrap();
+/// assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+/// ```
+#[derive(Default)]
+pub struct ConstraintSolver {
+    solver: BatsatBasicSolver,
+    var_manager: BasicVarManager,
+    /// The selector literals of the clause groups, indexed by [`GroupId`]
+    groups: Vec<Lit>,
+    /// The clause groups by their selector literals
+    group_ids: RsHashMap<Lit, GroupId>,
+}
+
+/// Identifier of a clause group added with [`ConstraintSolver::add_group`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GroupId(usize);
+
+impl ConstraintSolver {
+    /// Creates a new empty constraint solver
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets a new variable that is not used in any constraint yet
+    pub fn new_var(&mut self) -> Var {
+        self.var_manager.new_var()
+    }
+
+    /// Gets a positive literal of a new variable
+    pub fn new_lit(&mut self) -> Lit {
+        self.var_manager.new_lit()
+    }
+
+    /// Adds a clause
+    ///
+    /// # Errors
+    ///
+    /// If the solver returns an error.
+    pub fn add_clause(&mut self, clause: Clause) -> anyhow::Result<()> {
+        self.mark_used(clause.iter());
+        self.solver.add_clause(clause)
+    }
+
+    /// Adds the pseudo-boolean constraint `sum of terms <= bound`
+    ///
+    /// # Errors
+    ///
+    /// If a weight or the bound is too large or if the solver returns an error.
+    pub fn add_pb_ub<I>(&mut self, terms: I, bound: usize) -> anyhow::Result<()>
+    where
+        I: IntoIterator<Item = (Lit, usize)>,
+    {
+        let terms = terms
+            .into_iter()
+            .map(|(lit, weight)| Ok((lit, isize::try_from(weight)?)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        self.add_pb(PBConstraint::new_ub(terms, isize::try_from(bound)?))
+    }
+
+    /// Adds a pseudo-boolean constraint, encoded with the default encoding
+    ///
+    /// # Errors
+    ///
+    /// If the solver returns an error.
+    pub fn add_pb(&mut self, constr: PBConstraint) -> anyhow::Result<()> {
+        self.mark_used(constr.iter().map(|(lit, _)| lit));
+        let mut cnf = Cnf::new();
+        pb::default_encode_pb_constraint(constr, &mut cnf, &mut self.var_manager)?;
+        self.solver.add_cnf(cnf)
+    }
+
+    /// Adds a cardinality constraint, encoded with the default encoding
+    ///
+    /// # Errors
+    ///
+    /// If the solver returns an error.
+    pub fn add_card(&mut self, constr: CardConstraint) -> anyhow::Result<()> {
+        self.mark_used(constr.iter());
+        let mut cnf = Cnf::new();
+        card::default_encode_cardinality_constraint(constr, &mut cnf, &mut self.var_manager)?;
+        self.solver.add_cnf(cnf)
+    }
+
+    /// Adds a group of clauses that can be enabled and disabled as a whole,
+    /// e.g., for extracting minimal unsatisfiable subsets. Each clause is
+    /// extended by the negation of a fresh selector literal for the group.
+    /// All groups are enabled in [`Self::solve`] and [`Self::solve_with`],
+    /// only some of them in [`Self::solve_groups`].
+    ///
+    /// # Errors
+    ///
+    /// If the solver returns an error.
+    pub fn add_group(&mut self, clauses: Cnf) -> anyhow::Result<GroupId> {
+        self.mark_used(clauses.iter().flat_map(|cl| cl.iter()));
+        let selector = self.var_manager.new_lit();
+        self.solver.add_cnf(
+            clauses
+                .into_iter()
+                .map(|mut cl| {
+                    cl.add(!selector);
+                    cl
+                })
+                .collect(),
+        )?;
+        let id = GroupId(self.groups.len());
+        self.groups.push(selector);
+        self.group_ids.insert(selector, id);
+        Ok(id)
+    }
+
+    /// Solves the added constraints with all clause groups enabled
+    ///
+    /// # Errors
+    ///
+    /// If the solver returns an error.
+    pub fn solve(&mut self) -> anyhow::Result<SolverResult> {
+        if self.groups.is_empty() {
+            return self.solver.solve();
+        }
+        self.solve_with(&[])
+    }
+
+    /// Solves the added constraints under assumptions with all clause groups
+    /// enabled
+    ///
+    /// # Errors
+    ///
+    /// If the solver returns an error.
+    pub fn solve_with(&mut self, assumps: &[Lit]) -> anyhow::Result<SolverResult> {
+        self.mark_used(assumps.iter());
+        let mut assumps = assumps.to_vec();
+        assumps.extend(&self.groups);
+        self.solver.solve_assumps(&assumps)
+    }
+
+    /// Solves the added constraints with only the given clause groups enabled.
+    /// Group identifiers are only meaningful for the solver that returned
+    /// them, passing one of another solver enables an unrelated group.
+    ///
+    /// # Errors
+    ///
+    /// If the solver returns an error.
+    ///
+    /// # Panics
+    ///
+    /// If a group identifier was not returned by [`Self::add_group`] of this
+    /// solver and is out of range.
+    pub fn solve_groups(&mut self, groups: &[GroupId]) -> anyhow::Result<SolverResult> {
+        let assumps: Vec<Lit> = groups
+            .iter()
+            .map(|&GroupId(idx)| self.groups[idx])
+            .collect();
+        self.solver.solve_assumps(&assumps)
+    }
+
+    /// Gets the clause groups in the core of the last unsatisfiable solve
+    /// call, sorted by their identifiers. The other assumptions in the core
+    /// are ignored.
+    ///
+    /// # Errors
+    ///
+    /// If the solver is not in the unsatisfiable state.
+    pub fn core_groups(&mut self) -> anyhow::Result<Vec<GroupId>> {
+        let core = self.solver.core()?;
+        let mut groups: Vec<GroupId> = core
+            .into_iter()
+            .filter_map(|lit| self.group_ids.get(&!lit).copied())
+            .collect();
+        groups.sort_unstable();
+        groups.dedup();
+        Ok(groups)
+    }
+
+    /// Gets the value of a literal in the last found solution
+    ///
+    /// # Errors
+    ///
+    /// If the solver is not in the satisfied state.
+    pub fn lit_val(&self, lit: Lit) -> anyhow::Result<TernaryVal> {
+        self.solver.lit_val(lit)
+    }
+
+    /// Gets a reference to the underlying solver
+    #[must_use]
+    pub fn solver(&self) -> &BatsatBasicSolver {
+        &self.solver
+    }
+
+    /// Gets a reference to the variable manager
+    #[must_use]
+    pub fn var_manager(&self) -> &BasicVarManager {
+        &self.var_manager
+    }
+
+    /// Marks the variables of literals as used in the variable manager
+    fn mark_used<'a, I: Iterator<Item = &'a Lit>>(&mut self, lits: I) {
+        lits.for_each(|lit| {
+            self.var_manager.mark_used(lit.var());
+        });
+    }
+}
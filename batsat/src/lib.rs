@@ -2,24 +2,180 @@
 //!
 //! Interface to the [BatSat](https://github.com/c-cube/batsat) incremental SAT-Solver
 
-use batsat::{intmap::AsIndex, lbool, BasicSolver, SolverInterface};
+use batsat::{intmap::AsIndex, lbool, Callbacks, ClauseKind, SolverInterface};
 use rustsat::{
-    solvers::{Solve, SolveIncremental, SolverResult},
+    solvers::{LimitConflicts, Solve, SolveIncremental, SolverResult},
     types::{Clause, Lit, TernaryVal},
 };
 use thiserror::Error;
 
+mod constraint_solver;
+pub use constraint_solver::{ConstraintSolver, GroupId};
+
+#[cfg(feature = "proof")]
+use std::io::{self, Write};
+
 #[derive(Error, Clone, Copy, PartialEq, Eq, Debug)]
 #[error("BatSat returned an invalid value: {error}")]
 pub struct InvalidApiReturn {
     error: &'static str,
 }
 
-pub struct BatsatBasicSolver(BasicSolver);
+/// Callbacks enforcing a conflict budget and tracing a DRAT proof. Since
+/// BatSat does not expose its internal conflict budget, conflicts are counted
+/// via the learnt clauses.
+#[derive(Default)]
+struct BudgetCallbacks {
+    budget: Option<u64>,
+    n_conflicts: u64,
+    #[cfg(feature = "proof")]
+    proof: Option<DratProof>,
+}
+
+impl Callbacks for BudgetCallbacks {
+    fn on_start(&mut self) {
+        self.n_conflicts = 0;
+    }
+
+    #[cfg_attr(not(feature = "proof"), allow(unused_variables))]
+    fn on_new_clause(&mut self, c: &[batsat::Lit], src: ClauseKind) {
+        if src == ClauseKind::Learnt {
+            self.n_conflicts += 1;
+        }
+        #[cfg(feature = "proof")]
+        if let Some(proof) = &mut self.proof {
+            proof.add_clause(c);
+        }
+    }
+
+    #[cfg(feature = "proof")]
+    fn on_delete_clause(&mut self, c: &[batsat::Lit]) {
+        if let Some(proof) = &mut self.proof {
+            proof.delete_clause(c);
+        }
+    }
+
+    fn stop(&self) -> bool {
+        self.budget
+            .map_or(false, |budget| self.n_conflicts >= budget)
+    }
+}
+
+/// A DRAT proof written while solving. Since the callbacks can not return
+/// errors, the first write error is stored and returned after solving.
+#[cfg(feature = "proof")]
+struct DratProof {
+    writer: Box<dyn Write>,
+    error: Option<io::Error>,
+}
+
+#[cfg(feature = "proof")]
+impl DratProof {
+    fn add_clause(&mut self, c: &[batsat::Lit]) {
+        self.write_line("", c);
+    }
+
+    fn delete_clause(&mut self, c: &[batsat::Lit]) {
+        self.write_line("d ", c);
+    }
+
+    fn write_line(&mut self, prefix: &str, c: &[batsat::Lit]) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(err) = Self::write_lits(&mut self.writer, prefix, c) {
+            self.error = Some(err);
+        }
+    }
+
+    fn write_lits(writer: &mut dyn Write, prefix: &str, c: &[batsat::Lit]) -> io::Result<()> {
+        write!(writer, "{}", prefix)?;
+        for l in c {
+            // BatSat variable indices are shifted by one, i.e., they are the
+            // DIMACS variable indices
+            let sign = if l.sign() { "" } else { "-" };
+            write!(writer, "{}{} ", sign, l.var().idx())?;
+        }
+        writeln!(writer, "0")
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+        self.writer.flush()
+    }
+}
+
+/// Converts a RustSAT literal to the corresponding BatSat literal. This is the
+/// canonical conversion used by [`BatsatBasicSolver`]: BatSat variables are
+/// indexed like DIMACS variables, i.e., `var![i]` maps to BatSat variable
+/// `i + 1`, and BatSat's sign is `true` for positive literals.
+///
+/// The variable is not created in any solver, use the solver interface to add
+/// clauses over new variables.
+#[must_use]
+pub fn to_batsat_lit(lit: Lit) -> batsat::Lit {
+    batsat::Lit::new(batsat::Var::from_index(lit.vidx() + 1), lit.is_pos())
+}
+
+/// Converts a BatSat literal to the corresponding RustSAT literal. This is the
+/// inverse of [`to_batsat_lit`].
+///
+/// # Panics
+///
+/// If the literal is over BatSat variable `0`, which has no RustSAT
+/// counterpart.
+#[must_use]
+pub fn from_batsat_lit(lit: batsat::Lit) -> Lit {
+    Lit::new(lit.var().idx() - 1, !lit.sign())
+}
+
+/// The basic BatSat solver without theories
+///
+/// The solver does not implement [`rustsat::solvers::PhaseLit`], since BatSat
+/// only allows for setting the preferred polarity of a variable when the
+/// variable is created, not for changing it later on. [`Solve::reset`] uses
+/// the default implementation, since BatSat can not clear a solver in place.
+pub struct BatsatBasicSolver(batsat::Solver<BudgetCallbacks>);
 
 impl Default for BatsatBasicSolver {
     fn default() -> BatsatBasicSolver {
-        BatsatBasicSolver(BasicSolver::default())
+        BatsatBasicSolver(batsat::Solver::default())
+    }
+}
+
+impl BatsatBasicSolver {
+    /// Converts the result of a solve call. An undefined result is only
+    /// expected if the conflict limit was reached.
+    fn convert_result(&self, res: lbool) -> anyhow::Result<SolverResult> {
+        match res {
+            x if x == lbool::TRUE => Ok(SolverResult::Sat),
+            x if x == lbool::FALSE => Ok(SolverResult::Unsat),
+            x if x == lbool::UNDEF && self.0.cb().budget.is_some() => Ok(SolverResult::Interrupted),
+            x if x == lbool::UNDEF => Err(InvalidApiReturn {
+                error: "BatSat Solver is in an UNSAT state".into(),
+            }
+            .into()),
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[cfg(feature = "proof")]
+impl BatsatBasicSolver {
+    /// Completes the proof after a call to solve. If the formula was found
+    /// unsatisfiable independent of the assumptions, the empty clause is added
+    /// since BatSat does not report learning it.
+    fn finish_proof(&mut self, res: lbool) -> io::Result<()> {
+        let derived_empty = res == lbool::FALSE && self.0.unsat_core().is_empty();
+        let Some(proof) = &mut self.0.cb_mut().proof else {
+            return Ok(());
+        };
+        if derived_empty {
+            proof.add_clause(&[]);
+        }
+        proof.flush()
     }
 }
 
@@ -45,21 +201,18 @@ impl Solve for BatsatBasicSolver {
     }
 
     fn solve(&mut self) -> anyhow::Result<SolverResult> {
-        match self.0.solve_limited(&[]) {
-            x if x == lbool::TRUE => Ok(SolverResult::Sat),
-            x if x == lbool::FALSE => Ok(SolverResult::Unsat),
-            x if x == lbool::UNDEF => Err(InvalidApiReturn {
-                error: "BatSat Solver is in an UNSAT state".into(),
-            }
-            .into()),
-            _ => unreachable!(),
-        }
+        let res = self.0.solve_limited(&[]);
+        #[cfg(feature = "proof")]
+        self.finish_proof(res)?;
+        self.convert_result(res)
     }
 
-    fn lit_val(&self, lit: Lit) -> anyhow::Result<TernaryVal> {
-        let l = batsat::Lit::new(batsat::Var::from_index(lit.vidx() + 1), lit.is_pos());
+    fn try_solve_assumps(&mut self, assumps: &[Lit]) -> anyhow::Result<SolverResult> {
+        self.solve_assumps(assumps)
+    }
 
-        match self.0.value_lit(l) {
+    fn lit_val(&self, lit: Lit) -> anyhow::Result<TernaryVal> {
+        match self.0.value_lit(to_batsat_lit(lit)) {
             x if x == lbool::TRUE => Ok(TernaryVal::True),
             x if x == lbool::FALSE => Ok(TernaryVal::False),
             x if x == lbool::UNDEF => Ok(TernaryVal::DontCare),
@@ -90,6 +243,16 @@ impl Solve for BatsatBasicSolver {
     }
 }
 
+/// The limit applies to each following solve call separately, until it is
+/// removed. Solve calls that reach the limit return
+/// [`SolverResult::Interrupted`].
+impl LimitConflicts for BatsatBasicSolver {
+    fn limit_conflicts(&mut self, limit: Option<u32>) -> anyhow::Result<()> {
+        self.0.cb_mut().budget = limit.map(u64::from);
+        Ok(())
+    }
+}
+
 impl SolveIncremental for BatsatBasicSolver {
     fn solve_assumps(&mut self, assumps: &[Lit]) -> anyhow::Result<SolverResult> {
         let a = assumps
@@ -102,15 +265,10 @@ impl SolveIncremental for BatsatBasicSolver {
             })
             .collect::<Vec<_>>();
 
-        match self.0.solve_limited(&a) {
-            x if x == lbool::TRUE => Ok(SolverResult::Sat),
-            x if x == lbool::FALSE => Ok(SolverResult::Unsat),
-            x if x == lbool::UNDEF => Err(InvalidApiReturn {
-                error: "BatSat Solver is in an UNSAT state".into(),
-            }
-            .into()),
-            _ => unreachable!(),
-        }
+        let res = self.0.solve_limited(&a);
+        #[cfg(feature = "proof")]
+        self.finish_proof(res)?;
+        self.convert_result(res)
     }
 
     fn core(&mut self) -> anyhow::Result<Vec<Lit>> {
@@ -118,7 +276,19 @@ impl SolveIncremental for BatsatBasicSolver {
             .0
             .unsat_core()
             .iter()
-            .map(|l| Lit::new(l.var().idx() - 1, !l.sign()))
+            .map(|&l| from_batsat_lit(l))
             .collect::<Vec<_>>())
     }
+
+    /// Sets a writer that BatSat traces a DRAT proof to, based on the learnt
+    /// and deleted clauses. The writer should be set before the first call to
+    /// solve, since clauses learnt earlier are not included in the proof.
+    #[cfg(feature = "proof")]
+    fn set_drat_proof<W: Write + 'static>(&mut self, writer: W) -> anyhow::Result<()> {
+        self.0.cb_mut().proof = Some(DratProof {
+            writer: Box::new(writer),
+            error: None,
+        });
+        Ok(())
+    }
 }
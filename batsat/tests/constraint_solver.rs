@@ -0,0 +1,71 @@
+use rustsat::{
+    clause,
+    instances::Cnf,
+    lit,
+    solvers::SolverResult,
+    types::{constraints::CardConstraint, TernaryVal},
+};
+use rustsat_batsat::ConstraintSolver;
+
+#[test]
+fn clause_and_pb() {
+    let mut solver = ConstraintSolver::new();
+    solver.add_clause(clause![lit![0], lit![1]]).unwrap();
+    solver
+        .add_pb_ub([(lit![0], 3), (lit![1], 2), (lit![2], 1)], 2)
+        .unwrap();
+    assert_eq!(solver.solve().unwrap(), SolverResult::Sat);
+    assert_eq!(solver.lit_val(lit![0]).unwrap(), TernaryVal::False);
+    assert_eq!(solver.lit_val(lit![1]).unwrap(), TernaryVal::True);
+    assert_eq!(solver.lit_val(lit![2]).unwrap(), TernaryVal::False);
+    assert_eq!(solver.solve_with(&[lit![2]]).unwrap(), SolverResult::Unsat);
+}
+
+#[test]
+fn card_and_new_vars() {
+    let mut solver = ConstraintSolver::new();
+    solver
+        .add_card(CardConstraint::new_lb([lit![0], lit![1], lit![2]], 2))
+        .unwrap();
+    // New variables do not collide with encoding variables
+    let fresh = solver.new_lit();
+    assert!(fresh.var() > rustsat::var![2]);
+    solver.add_clause(clause![!fresh, !lit![0]]).unwrap();
+    solver.add_clause(clause![!fresh, !lit![1]]).unwrap();
+    assert_eq!(solver.solve_with(&[fresh]).unwrap(), SolverResult::Unsat);
+    assert_eq!(solver.solve_with(&[!fresh]).unwrap(), SolverResult::Sat);
+    let n_true = (0..3)
+        .filter(|&idx| solver.lit_val(lit![idx]).unwrap() == TernaryVal::True)
+        .count();
+    assert!(n_true >= 2);
+}
+
+#[test]
+fn clause_groups_core() {
+    let mut solver = ConstraintSolver::new();
+    let pos = solver
+        .add_group(Cnf::from_iter([
+            clause![lit![0]],
+            clause![lit![1], lit![2]],
+        ]))
+        .unwrap();
+    let neg = solver
+        .add_group(Cnf::from_iter([
+            clause![!lit![0], !lit![1]],
+            clause![!lit![0], lit![1]],
+        ]))
+        .unwrap();
+    let other = solver
+        .add_group(Cnf::from_iter([clause![lit![2]]]))
+        .unwrap();
+    assert_eq!(
+        solver.solve_groups(&[pos, other]).unwrap(),
+        SolverResult::Sat
+    );
+    assert_eq!(
+        solver.solve_groups(&[neg, other]).unwrap(),
+        SolverResult::Sat
+    );
+    assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+    assert_eq!(solver.core_groups().unwrap(), vec![pos, neg]);
+}
@@ -1 +1,92 @@
 rustsat_solvertests::incremental_tests!(rustsat_batsat::BatsatBasicSolver);
+
+#[test]
+fn add_clause_matches_add_cnf() {
+    use rustsat::{
+        clause,
+        instances::Cnf,
+        lit,
+        solvers::{Solve, SolveIncremental},
+    };
+
+    let clauses = [
+        clause![lit![0], lit![1]],
+        clause![!lit![0], lit![2]],
+        clause![!lit![1], !lit![2]],
+    ];
+    let mut by_clause = rustsat_batsat::BatsatBasicSolver::default();
+    let mut by_cnf = rustsat_batsat::BatsatBasicSolver::default();
+    let assumps = [[!lit![2]], [lit![2]], [lit![1]]];
+    for cl in clauses {
+        by_clause.add_clause(cl.clone()).unwrap();
+        let mut cnf = Cnf::new();
+        cnf.add_clause(cl);
+        by_cnf.add_cnf(cnf).unwrap();
+        for a in &assumps {
+            assert_eq!(
+                by_clause.solve_assumps(a).unwrap(),
+                by_cnf.solve_assumps(a).unwrap()
+            );
+        }
+    }
+    by_clause.add_unit(lit![2]).unwrap();
+    by_cnf.add_cnf(Cnf::from_iter([clause![lit![2]]])).unwrap();
+    for a in &assumps {
+        assert_eq!(
+            by_clause.solve_assumps(a).unwrap(),
+            by_cnf.solve_assumps(a).unwrap()
+        );
+    }
+}
+
+#[test]
+fn core_implication() {
+    use rustsat::{
+        instances::Cnf,
+        lit,
+        solvers::{Solve, SolveIncremental, SolverResult},
+    };
+
+    let mut cnf = Cnf::new();
+    cnf.add_lit_impl_lit(lit![0], lit![1]);
+    let mut solver = rustsat_batsat::BatsatBasicSolver::default();
+    solver.add_cnf(cnf).unwrap();
+    let assumps = [lit![0], !lit![1]];
+    assert_eq!(solver.solve_assumps(&assumps).unwrap(), SolverResult::Unsat);
+    // The core is a clause consisting of negated assumptions
+    let mut core = solver.core().unwrap();
+    core.sort_unstable();
+    assert_eq!(core, vec![!lit![0], lit![1]]);
+    let neg_core: Vec<_> = core.iter().map(|&l| !l).collect();
+    assert_eq!(
+        solver.solve_assumps(&neg_core).unwrap(),
+        SolverResult::Unsat
+    );
+    // Assumptions not involved in the conflict are not part of the core
+    assert_eq!(
+        solver.solve_assumps(&[lit![2], lit![0], !lit![1]]).unwrap(),
+        SolverResult::Unsat
+    );
+    let mut core = solver.core().unwrap();
+    core.sort_unstable();
+    assert_eq!(core, vec![!lit![0], lit![1]]);
+}
+
+#[test]
+fn try_solve_assumps() {
+    use rustsat::{
+        clause, lit,
+        solvers::{Solve, SolverResult},
+    };
+
+    let mut solver = rustsat_batsat::BatsatBasicSolver::default();
+    solver.add_clause(clause![lit![0], lit![1]]).unwrap();
+    assert_eq!(
+        solver.try_solve_assumps(&[!lit![0], !lit![1]]).unwrap(),
+        SolverResult::Unsat
+    );
+    assert_eq!(
+        solver.try_solve_assumps(&[!lit![0]]).unwrap(),
+        SolverResult::Sat
+    );
+}
@@ -1,3 +1,153 @@
 mod base {
     rustsat_solvertests::base_tests!(rustsat_batsat::BatsatBasicSolver, false, true);
 }
+
+#[test]
+fn limit_conflicts_interrupted() {
+    use rustsat::{
+        instances::SatInstance,
+        solvers::{LimitConflicts, Solve, SolverResult},
+        types::Lit,
+    };
+
+    // Pigeon hole principle with 9 pigeons and 8 holes
+    let (n_pigeons, n_holes) = (9, 8);
+    let pos = |p: u32, h: u32| Lit::positive(p * n_holes + h);
+    let mut inst: SatInstance = SatInstance::new();
+    for p in 0..n_pigeons {
+        inst.add_clause((0..n_holes).map(|h| pos(p, h)).collect());
+    }
+    for h in 0..n_holes {
+        for p1 in 0..n_pigeons {
+            for p2 in p1 + 1..n_pigeons {
+                inst.add_binary(!pos(p1, h), !pos(p2, h));
+            }
+        }
+    }
+    let mut solver = rustsat_batsat::BatsatBasicSolver::default();
+    solver.add_cnf(inst.into_cnf().0).unwrap();
+    solver.limit_conflicts(Some(10)).unwrap();
+    assert_eq!(solver.solve().unwrap(), SolverResult::Interrupted);
+}
+
+#[test]
+fn solution_satisfies_clauses() {
+    use rustsat::{
+        instances::{BasicVarManager, ManageVars, SatInstance},
+        solvers::{Solve, SolverResult},
+        types::TernaryVal,
+    };
+
+    let inst: SatInstance<BasicVarManager> =
+        SatInstance::from_dimacs_path("./data/AProVE11-12.cnf").unwrap();
+    let (cnf, vm) = inst.into_cnf();
+    let max_var = vm.max_var().unwrap();
+    let mut solver = rustsat_batsat::BatsatBasicSolver::default();
+    solver.add_cnf_ref(&cnf).unwrap();
+    assert_eq!(solver.solve().unwrap(), SolverResult::Sat);
+    let sol = solver.solution(max_var).unwrap();
+    assert!(cnf.iter().all(|cl| cl.is_sat(&sol)));
+    // Variables unknown to the solver are unassigned
+    let unknown = max_var + 1;
+    assert_eq!(
+        solver.lit_val(unknown.pos_lit()).unwrap(),
+        TernaryVal::DontCare
+    );
+}
+
+#[test]
+fn reset_independent() {
+    use rustsat::{
+        lit,
+        solvers::{Solve, SolverResult},
+        types::TernaryVal,
+    };
+
+    let mut solver = rustsat_batsat::BatsatBasicSolver::default();
+    solver.add_unit(lit![0]).unwrap();
+    solver.add_unit(!lit![0]).unwrap();
+    assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+    solver.reset();
+    solver.add_binary(!lit![0], lit![1]).unwrap();
+    solver.add_unit(!lit![1]).unwrap();
+    assert_eq!(solver.solve().unwrap(), SolverResult::Sat);
+    assert_eq!(solver.lit_val(lit![0]).unwrap(), TernaryVal::False);
+}
+
+#[cfg(feature = "proof")]
+mod proof {
+    use std::{cell::RefCell, io, rc::Rc};
+
+    use rustsat::{
+        instances::SatInstance,
+        solvers::{Solve, SolveIncremental, SolverResult},
+    };
+
+    /// Writer into a buffer that can still be read after handing it to the
+    /// solver
+    #[derive(Default, Clone)]
+    struct SharedBuf(Rc<RefCell<Vec<u8>>>);
+
+    impl io::Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn unsat_proof() -> String {
+        let inst: SatInstance =
+            SatInstance::from_dimacs_path("./data/minisat-segfault.cnf").unwrap();
+        let mut solver = rustsat_batsat::BatsatBasicSolver::default();
+        let buf = SharedBuf::default();
+        solver.set_drat_proof(buf.clone()).unwrap();
+        solver.add_cnf(inst.into_cnf().0).unwrap();
+        assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+        let proof = buf.0.borrow().clone();
+        String::from_utf8(proof).unwrap()
+    }
+
+    #[test]
+    fn drat_derives_empty_clause() {
+        let proof = unsat_proof();
+        assert!(proof.lines().count() > 1);
+        // Literals are in DIMACS format and every clause is terminated by `0`
+        assert!(proof.lines().all(|line| {
+            let mut lits = line.trim_start_matches("d ").split(' ').rev();
+            lits.next() == Some("0") && lits.all(|l| matches!(l.parse::<i32>(), Ok(l) if l != 0))
+        }));
+        assert_eq!(proof.lines().last(), Some("0"));
+    }
+
+    /// Checks the proof with `drat-trim`, which needs to be on the path
+    #[test]
+    #[ignore]
+    fn drat_trim_verified() {
+        let proof_path = std::env::temp_dir().join("rustsat-batsat-segfault.drat");
+        std::fs::write(&proof_path, unsat_proof()).unwrap();
+        let out = std::process::Command::new("drat-trim")
+            .arg("./data/minisat-segfault.cnf")
+            .arg(&proof_path)
+            .output()
+            .expect("drat-trim should be on the path");
+        assert!(String::from_utf8_lossy(&out.stdout).contains("s VERIFIED"));
+    }
+}
+
+#[test]
+fn lit_conversion_round_trip() {
+    use rustsat::lit;
+    use rustsat_batsat::{from_batsat_lit, to_batsat_lit};
+
+    for l in [lit![0], !lit![0], lit![1], !lit![5], lit![42], !lit![1000]] {
+        let bl = to_batsat_lit(l);
+        // DIMACS indexing and sign convention
+        assert_eq!(bl.var().idx(), l.vidx32() + 1);
+        assert_eq!(bl.sign(), l.is_pos());
+        assert_eq!(from_batsat_lit(bl), l);
+        assert_eq!(to_batsat_lit(!l), !bl);
+    }
+}
@@ -41,6 +41,18 @@ typedef enum MaybeError {
    * Attempting to decrease precision
    */
   PrecisionDecreased,
+  /**
+   * No input literals were added to the encoding
+   */
+  EmptyEncoding,
+  /**
+   * Adding input literals would overflow the sum of the weights
+   */
+  WeightOverflow,
+  /**
+   * Collecting the clauses of the encoding ran out of memory
+   */
+  OutOfMemory,
 } MaybeError;
 
 /**
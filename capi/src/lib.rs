@@ -35,6 +35,12 @@ pub mod encodings {
         PrecisionNotPow2,
         /// Attempting to decrease precision
         PrecisionDecreased,
+        /// No input literals were added to the encoding
+        EmptyEncoding,
+        /// Adding input literals would overflow the sum of the weights
+        WeightOverflow,
+        /// Collecting the clauses of the encoding ran out of memory
+        OutOfMemory,
     }
 
     impl From<encodings::Error> for MaybeError {
@@ -42,6 +48,9 @@ pub mod encodings {
             match value {
                 encodings::Error::NotEncoded => MaybeError::NotEncoded,
                 encodings::Error::Unsat => MaybeError::Unsat,
+                encodings::Error::EmptyEncoding => MaybeError::EmptyEncoding,
+                encodings::Error::WeightOverflow => MaybeError::WeightOverflow,
+                encodings::Error::OutOfMemory(_) => MaybeError::OutOfMemory,
             }
         }
     }
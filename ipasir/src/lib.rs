@@ -168,6 +168,10 @@ impl Solve for IpasirSolver<'_, '_> {
         }
     }
 
+    fn try_solve_assumps(&mut self, assumps: &[Lit]) -> anyhow::Result<SolverResult> {
+        self.solve_assumps(assumps)
+    }
+
     fn lit_val(&self, lit: Lit) -> anyhow::Result<TernaryVal> {
         if self.state != InternalSolverState::Sat {
             return Err(StateError {
@@ -18,3 +18,17 @@ mod unsat {
         slv
     });
 }
+
+#[test]
+fn try_solve_assumps_not_incremental() {
+    use rustsat::{
+        clause, lit,
+        solvers::{NotIncremental, Solve, SolverResult},
+    };
+
+    let mut solver = rustsat_kissat::Kissat::default();
+    solver.add_clause(clause![lit![0], lit![1]]).unwrap();
+    let err = solver.try_solve_assumps(&[!lit![0]]).unwrap_err();
+    assert!(err.is::<NotIncremental>());
+    assert_eq!(solver.try_solve_assumps(&[]).unwrap(), SolverResult::Sat);
+}
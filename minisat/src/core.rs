@@ -142,6 +142,10 @@ impl Solve for Minisat {
         }
     }
 
+    fn try_solve_assumps(&mut self, assumps: &[Lit]) -> anyhow::Result<SolverResult> {
+        self.solve_assumps(assumps)
+    }
+
     fn lit_val(&self, lit: Lit) -> anyhow::Result<TernaryVal> {
         if self.state != InternalSolverState::Sat {
             return Err(StateError {
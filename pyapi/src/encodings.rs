@@ -30,6 +30,13 @@ fn convert_error(err: Error) -> PyErr {
             pyo3::exceptions::PyRuntimeError::new_err("not encoded to enforce bound")
         }
         Error::Unsat => pyo3::exceptions::PyValueError::new_err("encoding is unsat"),
+        Error::EmptyEncoding => {
+            pyo3::exceptions::PyValueError::new_err("encoding has no input literals")
+        }
+        Error::WeightOverflow => {
+            pyo3::exceptions::PyOverflowError::new_err("sum of input weights overflows")
+        }
+        Error::OutOfMemory(err) => pyo3::exceptions::PyMemoryError::new_err(format!("{}", err)),
     }
 }
 
@@ -1,14 +1,23 @@
 //! # Encodings for Common Constraint Types to CNF
 //!
-//! CNF encodings for cardinality and pseudo-boolean constraints.
+//! CNF encodings for cardinality and pseudo-boolean constraints, as well as
+//! some related building blocks such as lexicographic orderings.
 
 use thiserror::Error;
 
-use crate::types::{Clause, Lit};
+use crate::{
+    instances::Cnf,
+    types::{Clause, Lit, RsHashSet},
+};
 
+pub mod absdiff;
 pub mod am1;
 pub mod atomics;
 pub mod card;
+pub mod graph;
+pub mod hamming;
+pub mod lex;
+pub mod monotone;
 pub mod pb;
 
 /// Trait for collecting clauses. Mainly used when generating encodings and implemented by
@@ -30,6 +39,119 @@ pub trait CollectClauses {
     }
 }
 
+/// Clause collector that passes every clause on to a callback instead of
+/// storing it. This allows for streaming the clauses of an encoding to any
+/// destination, e.g., a writer or a solver API that does not implement
+/// [`CollectClauses`], without materializing the full encoding in memory.
+///
+/// ```
+/// # use rustsat::{
+/// #     encodings::{pb::{BoundUpper, GeneralizedTotalizer}, CallbackCollector},
+/// #     instances::{BasicVarManager, ManageVars},
+/// #     lit, var,
+/// # };
+/// let mut var_manager = BasicVarManager::default();
+/// var_manager.increase_next_free(var![3]);
+/// let mut enc = GeneralizedTotalizer::from_iter([(lit![0], 2), (lit![1], 3), (lit![2], 1)]);
+/// let mut max_len = 0;
+/// let mut collector = CallbackCollector::new(|cl| max_len = std::cmp::max(max_len, cl.len()));
+/// enc.encode_ub(0..=4, &mut collector, &mut var_manager).unwrap();
+/// assert_eq!(max_len, 3);
+/// ```
+pub struct CallbackCollector<F>
+where
+    F: FnMut(Clause),
+{
+    callback: F,
+    n_clauses: usize,
+}
+
+impl<F> CallbackCollector<F>
+where
+    F: FnMut(Clause),
+{
+    /// Creates a new collector passing clauses to the given callback
+    pub fn new(callback: F) -> Self {
+        Self {
+            callback,
+            n_clauses: 0,
+        }
+    }
+}
+
+impl<F> CollectClauses for CallbackCollector<F>
+where
+    F: FnMut(Clause),
+{
+    fn n_clauses(&self) -> usize {
+        self.n_clauses
+    }
+
+    fn extend_clauses<T>(&mut self, cl_iter: T) -> Result<(), crate::OutOfMemory>
+    where
+        T: IntoIterator<Item = Clause>,
+    {
+        cl_iter.into_iter().for_each(|cl| {
+            self.n_clauses += 1;
+            (self.callback)(cl)
+        });
+        Ok(())
+    }
+}
+
+/// Clause collector that accumulates clauses from multiple encodings into a
+/// [`Cnf`] while dropping clauses that were already collected. Clauses are
+/// considered duplicates if they contain the same literals, independent of
+/// their order.
+#[derive(Default)]
+pub struct CnfDeduper {
+    cnf: Cnf,
+    seen: RsHashSet<Vec<Lit>>,
+    n_duplicates: usize,
+}
+
+impl CnfDeduper {
+    /// Creates a new empty deduplicating collector
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the number of duplicate clauses that were dropped
+    #[must_use]
+    pub fn n_duplicates(&self) -> usize {
+        self.n_duplicates
+    }
+
+    /// Returns the deduplicated CNF and the number of dropped duplicates
+    #[must_use]
+    pub fn finish(self) -> (Cnf, usize) {
+        (self.cnf, self.n_duplicates)
+    }
+}
+
+impl CollectClauses for CnfDeduper {
+    fn n_clauses(&self) -> usize {
+        self.cnf.len()
+    }
+
+    fn extend_clauses<T>(&mut self, cl_iter: T) -> Result<(), crate::OutOfMemory>
+    where
+        T: IntoIterator<Item = Clause>,
+    {
+        for cl in cl_iter {
+            let mut key = cl.lits().to_vec();
+            key.sort_unstable();
+            if self.seen.insert(key) {
+                self.cnf.add_clause(cl);
+            } else {
+                self.n_duplicates += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Errors from encodings
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum Error {
@@ -39,6 +161,15 @@ pub enum Error {
     /// The requested encoding is unsatisfiable
     #[error("encoding is unsat")]
     Unsat,
+    /// No input literals were added to the encoding before enforcing a bound
+    #[error("encoding has no input literals")]
+    EmptyEncoding,
+    /// Adding input literals would overflow the sum of the weights
+    #[error("sum of input weights overflows")]
+    WeightOverflow,
+    /// Collecting the clauses of the encoding ran out of memory
+    #[error("out of memory: {0}")]
+    OutOfMemory(#[from] crate::OutOfMemory),
 }
 
 /// Trait for encodings that track statistics.
@@ -48,6 +179,12 @@ pub trait EncodeStats {
 
     /// Gets the number of auxiliary variables in the encoding
     fn n_vars(&self) -> u32;
+
+    /// Gets the depth of the encoding, i.e., the longest path from an output
+    /// to an input. Encodings without a tree-like structure return `0`.
+    fn depth(&self) -> usize {
+        0
+    }
 }
 
 #[path = "encodings/nodedb.rs"]
@@ -87,4 +224,12 @@ pub trait IterWeightedInputs {
         Self: 'a;
     /// Gets an iterator over copies of the input literals
     fn iter(&self) -> Self::Iter<'_>;
+    /// Gets the number of distinct input literals
+    fn n_lits(&self) -> usize {
+        self.iter().count()
+    }
+    /// Gets the number of distinct weights of the input literals
+    fn n_distinct_weights(&self) -> usize {
+        self.iter().map(|(_, w)| w).collect::<RsHashSet<_>>().len()
+    }
 }
@@ -0,0 +1,108 @@
+//! # Absolute Difference Objectives
+//!
+//! Encodings for objectives of the form `|sum_A - sum_B|` over two weighted
+//! sums of literals. The difference `sum_A - sum_B` is shifted to a
+//! non-negative weighted sum over the literals of `A` and the _negated_
+//! literals of `B`, which is then bounded from above by a pseudo-boolean
+//! encoding. Doing this for both `A - B` and `B - A` yields order-encoded
+//! outputs of the positive parts of both differences, of which at most one
+//! can be non-zero. The sum of both positive parts is the absolute difference.
+
+use super::{
+    atomics,
+    pb::{BoundUpper, DefUpperBounding},
+    CollectClauses,
+};
+use crate::{instances::ManageVars, types::Lit};
+
+/// Encodes the absolute difference of two weighted sums with the default
+/// upper bounding pseudo-boolean encoding. See [`abs_diff`].
+///
+/// # Errors
+///
+/// If the collector runs out of memory, returns [`crate::OutOfMemory`].
+pub fn default_abs_diff<Col: CollectClauses>(
+    a: &[(Lit, usize)],
+    b: &[(Lit, usize)],
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<Vec<(Lit, usize)>, crate::OutOfMemory> {
+    abs_diff::<DefUpperBounding, Col>(a, b, collector, var_manager)
+}
+
+/// Encodes the absolute difference `|sum_A - sum_B|` of two weighted sums with
+/// an upper bounding pseudo-boolean encoding of choice and returns objective
+/// terms representing it. The encoding only enforces that the weighted sum of
+/// the returned terms is _at least_ the absolute difference, which is exact
+/// when the terms are minimized.
+///
+/// One auxiliary variable is introduced per distinct value the difference can
+/// exceed, its weight is the distance to the next such value.
+///
+/// # Errors
+///
+/// If the collector runs out of memory, returns [`crate::OutOfMemory`].
+///
+/// # Panics
+///
+/// If the pseudo-boolean encoding does not allow enforcing bounds within the
+/// range it was encoded for.
+pub fn abs_diff<PBE, Col>(
+    a: &[(Lit, usize)],
+    b: &[(Lit, usize)],
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<Vec<(Lit, usize)>, crate::OutOfMemory>
+where
+    PBE: BoundUpper + FromIterator<(Lit, usize)>,
+    Col: CollectClauses,
+{
+    let mut terms = vec![];
+    positive_diff::<PBE, Col>(a, b, &mut terms, collector, var_manager)?;
+    positive_diff::<PBE, Col>(b, a, &mut terms, collector, var_manager)?;
+    Ok(terms)
+}
+
+/// Adds objective terms for the positive part of `sum_pos - sum_neg`
+fn positive_diff<PBE, Col>(
+    pos: &[(Lit, usize)],
+    neg: &[(Lit, usize)],
+    terms: &mut Vec<(Lit, usize)>,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<(), crate::OutOfMemory>
+where
+    PBE: BoundUpper + FromIterator<(Lit, usize)>,
+    Col: CollectClauses,
+{
+    let pos_sum: usize = pos.iter().map(|(_, w)| w).sum();
+    if pos_sum == 0 {
+        return Ok(());
+    }
+    let neg_sum: usize = neg.iter().map(|(_, w)| w).sum();
+    // `sum_pos - sum_neg > d` iff `sum_pos + sum(!neg) > d + neg_sum`
+    let mut enc = PBE::from_iter(pos.iter().copied().chain(neg.iter().map(|&(l, w)| (!l, w))));
+    let bounds = neg_sum..neg_sum + pos_sum;
+    enc.encode_ub(bounds.clone(), collector, var_manager)?;
+    let mut prev: Option<Vec<Lit>> = None;
+    for ub in bounds {
+        let mut assumps = enc
+            .enforce_ub(ub)
+            .expect("bound should be enforceable after encoding");
+        assumps.sort_unstable();
+        if prev.as_ref() == Some(&assumps) {
+            // Difference can not take the value `ub - neg_sum`
+            terms.last_mut().unwrap().1 += 1;
+            continue;
+        }
+        let exceeded = var_manager.new_lit();
+        collector.extend_clauses(
+            assumps
+                .iter()
+                .map(|&assump| atomics::lit_impl_lit(!assump, exceeded)),
+        )?;
+        terms.push((exceeded, 1));
+        prev = Some(assumps);
+    }
+    Ok(())
+}
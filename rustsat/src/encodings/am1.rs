@@ -21,7 +21,10 @@
 //! ```
 
 use super::CollectClauses;
-use crate::instances::ManageVars;
+use crate::{
+    instances::ManageVars,
+    types::{Clause, Lit, LitIter},
+};
 
 mod pairwise;
 pub use pairwise::Pairwise;
@@ -47,3 +50,35 @@ pub type Def = Pairwise;
 pub fn new_default_am1() -> impl Encode {
     Def::default()
 }
+
+/// A default encoder for exactly-1 constraints. This uses a [`Def`] at-most-1
+/// encoding. See [`encode_exactly_one`].
+pub fn default_encode_exactly_one<LI: LitIter, Col: CollectClauses>(
+    lits: LI,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<(), crate::OutOfMemory> {
+    encode_exactly_one::<Def, LI, Col>(lits, collector, var_manager)
+}
+
+/// An encoder for exactly-1 constraints with an at-most-1 encoding of choice.
+/// The constraint is encoded as the at-most-1 encoding and a single clause
+/// over all input literals. For a single input literal, only a unit clause is
+/// produced and for no input literals, an empty clause.
+pub fn encode_exactly_one<AM1, LI, Col>(
+    lits: LI,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<(), crate::OutOfMemory>
+where
+    AM1: Encode + FromIterator<Lit>,
+    LI: LitIter,
+    Col: CollectClauses,
+{
+    let lits: Vec<Lit> = lits.into_iter().collect();
+    collector.add_clause(Clause::from(&lits[..]))?;
+    if lits.len() > 1 {
+        AM1::from_iter(lits).encode(collector, var_manager)?;
+    }
+    Ok(())
+}
@@ -55,7 +55,7 @@ pub fn clause_impl_clause<'all>(
     })
 }
 
-/// Implication of form `(a1 | a2 | ... | an) -> (b1 & b2 & ... | bm)`
+/// Implication of form `(a1 | a2 | ... | an) -> (b1 & b2 & ... & bm)`
 pub fn clause_impl_cube<'all>(
     a: &'all [Lit],
     b: &'all [Lit],
@@ -72,3 +72,58 @@ pub fn cube_impl_cube<'all>(a: &'all [Lit], b: &'all [Lit]) -> impl Iterator<Ite
         cl
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        instances::Cnf,
+        lit,
+        types::{Assignment, TernaryVal},
+        var,
+    };
+
+    /// Checks that the CNF is satisfied by exactly the assignments to the
+    /// first four variables for which `expected` holds
+    fn check(cnf: &Cnf, expected: impl Fn(&[bool]) -> bool) {
+        for bits in 0..16_u32 {
+            let vals: Vec<bool> = (0..4).map(|idx| bits & (1 << idx) != 0).collect();
+            let mut assign = Assignment::default();
+            for (idx, &val) in vals.iter().enumerate() {
+                assign.assign_var(var![idx as u32], TernaryVal::from(val));
+            }
+            assert_eq!(
+                cnf.iter().all(|cl| cl.is_sat(&assign)),
+                expected(&vals),
+                "{vals:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn cube_impl_clause() {
+        let (a, b) = ([lit![0], lit![1]], [lit![2], lit![3]]);
+        let cnf: Cnf = [super::cube_impl_clause(&a, &b)].into_iter().collect();
+        check(&cnf, |v| !(v[0] && v[1]) || v[2] || v[3]);
+    }
+
+    #[test]
+    fn clause_impl_clause() {
+        let (a, b) = ([lit![0], lit![1]], [lit![2], lit![3]]);
+        let cnf: Cnf = super::clause_impl_clause(&a, &b).collect();
+        check(&cnf, |v| !(v[0] || v[1]) || v[2] || v[3]);
+    }
+
+    #[test]
+    fn clause_impl_cube() {
+        let (a, b) = ([lit![0], lit![1]], [lit![2], lit![3]]);
+        let cnf: Cnf = super::clause_impl_cube(&a, &b).collect();
+        check(&cnf, |v| !(v[0] || v[1]) || (v[2] && v[3]));
+    }
+
+    #[test]
+    fn cube_impl_cube() {
+        let (a, b) = ([lit![0], lit![1]], [lit![2], lit![3]]);
+        let cnf: Cnf = super::cube_impl_cube(&a, &b).collect();
+        check(&cnf, |v| !(v[0] && v[1]) || (v[2] && v[3]));
+    }
+}
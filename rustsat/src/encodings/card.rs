@@ -31,13 +31,13 @@ use std::{
     ops::{Bound, Range, RangeBounds},
 };
 
-use super::{CollectClauses, Error};
+use super::{atomics, CollectClauses, EncodeStats, Error};
 use crate::{
     clause,
     instances::ManageVars,
     types::{
         constraints::{CardConstraint, CardEQConstr, CardLBConstr, CardUBConstr},
-        Clause, Lit,
+        Clause, Lit, LitIter,
     },
 };
 
@@ -76,6 +76,15 @@ pub trait BoundUpper: Encode {
     /// adequately and nothing has been called afterwards, otherwise
     /// [`Error::NotEncoded`] will be returned.
     fn enforce_ub(&self, ub: usize) -> Result<Vec<Lit>, Error>;
+    /// Returns assumptions/units for enforcing a strict upper bound (`sum of lits
+    /// < ub`), i.e., [`BoundUpper::enforce_ub`] with `ub - 1`. If `ub` is zero,
+    /// [`Error::Unsat`] is returned.
+    fn enforce_ub_strict(&self, ub: usize) -> Result<Vec<Lit>, Error> {
+        match ub.checked_sub(1) {
+            Some(ub) => self.enforce_ub(ub),
+            None => Err(Error::Unsat),
+        }
+    }
     /// Encodes an upper bound cardinality constraint to CNF
     ///
     /// # Errors
@@ -126,6 +135,14 @@ pub trait BoundLower: Encode {
     /// the number of literals in the encoding, [`Error::Unsat`] is
     /// returned.
     fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error>;
+    /// Returns assumptions/units for enforcing a strict lower bound (`sum of lits
+    /// > lb`), i.e., [`BoundLower::enforce_lb`] with `lb + 1`.
+    fn enforce_lb_strict(&self, lb: usize) -> Result<Vec<Lit>, Error> {
+        match lb.checked_add(1) {
+            Some(lb) => self.enforce_lb(lb),
+            None => Err(Error::Unsat),
+        }
+    }
     /// Encodes a lower bound cardinality constraint to CNF
     ///
     /// # Errors
@@ -347,6 +364,129 @@ pub fn new_default_inc_both() -> impl BoundBoth {
     DefIncBothBounding::default()
 }
 
+/// Upper bounding cardinality encoding that is selected at runtime, e.g., from
+/// a command line argument. Since the encoding traits are not object safe,
+/// this dispatches to the selected encoding instead of being a trait object.
+/// Use [`make`] to construct an encoding by name.
+pub enum DynUpperBounding {
+    /// A [`Totalizer`]
+    Totalizer(Totalizer),
+    /// A [`DbTotalizer`]
+    DbTotalizer(DbTotalizer),
+}
+
+impl DynUpperBounding {
+    /// The names accepted by [`make`]
+    pub const NAMES: [&'static str; 2] = ["totalizer", "dbtotalizer"];
+}
+
+/// Constructs an empty upper bounding cardinality encoding from its name.
+/// Accepted names are listed in [`DynUpperBounding::NAMES`]. Returns [`None`]
+/// if the name is unknown.
+#[must_use]
+pub fn make(name: &str) -> Option<DynUpperBounding> {
+    match name {
+        "totalizer" => Some(DynUpperBounding::Totalizer(Totalizer::default())),
+        "dbtotalizer" => Some(DynUpperBounding::DbTotalizer(DbTotalizer::default())),
+        _ => None,
+    }
+}
+
+impl Encode for DynUpperBounding {
+    fn n_lits(&self) -> usize {
+        match self {
+            DynUpperBounding::Totalizer(enc) => enc.n_lits(),
+            DynUpperBounding::DbTotalizer(enc) => enc.n_lits(),
+        }
+    }
+}
+
+impl BoundUpper for DynUpperBounding {
+    fn encode_ub<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        match self {
+            DynUpperBounding::Totalizer(enc) => enc.encode_ub(range, collector, var_manager),
+            DynUpperBounding::DbTotalizer(enc) => enc.encode_ub(range, collector, var_manager),
+        }
+    }
+
+    fn enforce_ub(&self, ub: usize) -> Result<Vec<Lit>, Error> {
+        match self {
+            DynUpperBounding::Totalizer(enc) => enc.enforce_ub(ub),
+            DynUpperBounding::DbTotalizer(enc) => enc.enforce_ub(ub),
+        }
+    }
+}
+
+impl EncodeIncremental for DynUpperBounding {
+    fn reserve(&mut self, var_manager: &mut dyn ManageVars) {
+        match self {
+            DynUpperBounding::Totalizer(enc) => enc.reserve(var_manager),
+            DynUpperBounding::DbTotalizer(enc) => enc.reserve(var_manager),
+        }
+    }
+}
+
+impl BoundUpperIncremental for DynUpperBounding {
+    fn encode_ub_change<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        match self {
+            DynUpperBounding::Totalizer(enc) => enc.encode_ub_change(range, collector, var_manager),
+            DynUpperBounding::DbTotalizer(enc) => {
+                enc.encode_ub_change(range, collector, var_manager)
+            }
+        }
+    }
+}
+
+impl EncodeStats for DynUpperBounding {
+    fn n_clauses(&self) -> usize {
+        match self {
+            DynUpperBounding::Totalizer(enc) => enc.n_clauses(),
+            DynUpperBounding::DbTotalizer(enc) => enc.n_clauses(),
+        }
+    }
+
+    fn n_vars(&self) -> u32 {
+        match self {
+            DynUpperBounding::Totalizer(enc) => enc.n_vars(),
+            DynUpperBounding::DbTotalizer(enc) => enc.n_vars(),
+        }
+    }
+
+    fn depth(&self) -> usize {
+        match self {
+            DynUpperBounding::Totalizer(enc) => enc.depth(),
+            DynUpperBounding::DbTotalizer(enc) => enc.depth(),
+        }
+    }
+}
+
+impl Extend<Lit> for DynUpperBounding {
+    fn extend<T: IntoIterator<Item = Lit>>(&mut self, iter: T) {
+        match self {
+            DynUpperBounding::Totalizer(enc) => enc.extend(iter),
+            DynUpperBounding::DbTotalizer(enc) => enc.extend(iter),
+        }
+    }
+}
+
 /// A default encoder for any cardinality constraint. This uses a
 /// [`DefBothBounding`] to encode non-trivial constraints.
 pub fn default_encode_cardinality_constraint<Col: CollectClauses>(
@@ -386,6 +526,52 @@ pub fn encode_cardinality_constraint<CE: BoundBoth + FromIterator<Lit>, Col: Col
     }
 }
 
+/// Encodes an upper bound cardinality constraint that only holds if the
+/// condition literal is true, i.e., `cond -> (sum of lits <= ub)`, with the
+/// default upper bounding encoding. See [`encode_implied_ub`].
+pub fn default_encode_implied_ub<LI: LitIter, Col: CollectClauses>(
+    cond: Lit,
+    lits: LI,
+    ub: usize,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<(), crate::OutOfMemory> {
+    encode_implied_ub::<DefUpperBounding, LI, Col>(cond, lits, ub, collector, var_manager)
+}
+
+/// Encodes an upper bound cardinality constraint that only holds if the
+/// condition literal is true, i.e., `cond -> (sum of lits <= ub)`, with an
+/// encoding of choice. The encoding is built for exactly the bound `ub` and
+/// the assumptions enforcing the bound are made conditional on `cond`. If
+/// `cond` is false, the input literals are not restricted. If `ub` is at least
+/// the number of input literals, no clauses are produced.
+pub fn encode_implied_ub<CE, LI, Col>(
+    cond: Lit,
+    lits: LI,
+    ub: usize,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<(), crate::OutOfMemory>
+where
+    CE: BoundUpper + FromIterator<Lit>,
+    LI: LitIter,
+    Col: CollectClauses,
+{
+    let mut enc = CE::from_iter(lits);
+    if ub >= enc.n_lits() {
+        return Ok(());
+    }
+    enc.encode_ub(ub..ub + 1, collector, var_manager)?;
+    let assumps = enc
+        .enforce_ub(ub)
+        .expect("bound should be encoded since it was just encoded");
+    collector.extend_clauses(
+        assumps
+            .into_iter()
+            .map(|assump| atomics::lit_impl_lit(cond, assump)),
+    )
+}
+
 fn prepare_ub_range<Enc: Encode, R: RangeBounds<usize>>(enc: &Enc, range: R) -> Range<usize> {
     (match range.start_bound() {
         Bound::Included(b) => *b,
@@ -177,6 +177,10 @@ impl EncodeStats for DbTotalizer {
     fn n_vars(&self) -> u32 {
         self.n_vars
     }
+
+    fn depth(&self) -> usize {
+        self.depth()
+    }
 }
 
 impl From<Vec<Lit>> for DbTotalizer {
@@ -234,6 +234,10 @@ where
     fn n_vars(&self) -> u32 {
         self.card_enc.n_vars()
     }
+
+    fn depth(&self) -> usize {
+        self.card_enc.depth()
+    }
 }
 
 type InvertedIter<ICE> = std::iter::Map<ICE, fn(Lit) -> Lit>;
@@ -428,4 +432,8 @@ where
     fn n_vars(&self) -> u32 {
         self.ub_enc.n_vars() + self.lb_enc.n_vars()
     }
+
+    fn depth(&self) -> usize {
+        std::cmp::max(self.ub_enc.depth(), self.lb_enc.depth())
+    }
 }
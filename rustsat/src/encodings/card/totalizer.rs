@@ -90,6 +90,24 @@ impl Totalizer {
         }
     }
 
+    /// Encodes the full range of upper bounds `0..=n` in one shot. Afterwards,
+    /// [`BoundUpper::enforce_ub`] can be called for any bound without
+    /// re-encoding, e.g., for binary search, as long as no literals are added.
+    ///
+    /// # Errors
+    ///
+    /// If the collector runs out of memory, returns [`crate::OutOfMemory`].
+    pub fn encode_ub_full<Col>(
+        &mut self,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        self.encode_ub(0..=self.in_lits.len(), collector, var_manager)
+    }
+
     /// Fully builds the tree, then returns it
     #[cfg(feature = "internals")]
     pub fn tree(mut self) -> Option<Node> {
@@ -298,6 +316,10 @@ impl EncodeStats for Totalizer {
     fn n_vars(&self) -> u32 {
         self.n_vars
     }
+
+    fn depth(&self) -> usize {
+        self.depth()
+    }
 }
 
 impl From<Vec<Lit>> for Totalizer {
@@ -771,13 +793,21 @@ impl Node {
                 if out_lits.len() < idx_range.end {
                     out_lits.resize(idx_range.end, None);
                 };
+                let n_missing = out_lits
+                    .iter()
+                    .take(idx_range.end)
+                    .skip(idx_range.start)
+                    .filter(|olit| olit.is_none())
+                    .count();
+                let mut next_var = var_manager.new_vars(n_missing).start;
                 for olit in out_lits
                     .iter_mut()
                     .take(idx_range.end)
                     .skip(idx_range.start)
                 {
                     if olit.is_none() {
-                        *olit = Some(var_manager.new_var().pos_lit());
+                        *olit = Some(next_var.pos_lit());
+                        next_var += 1;
                     }
                 }
                 debug_assert!(out_lits.len() <= *max_val);
@@ -1145,4 +1175,22 @@ mod tests {
         assert_eq!(cnf1.len(), tot1.n_clauses());
         assert_eq!(cnf2.len(), tot2.n_clauses());
     }
+
+    #[test]
+    fn strict_bounds() {
+        let mut tot = Totalizer::default();
+        tot.extend(vec![lit![0], lit![1], lit![2], lit![3]]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        tot.encode_both(.., &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        for b in 1..=4 {
+            assert_eq!(tot.enforce_ub_strict(b), tot.enforce_ub(b - 1));
+        }
+        for b in 0..4 {
+            assert_eq!(tot.enforce_lb_strict(b), tot.enforce_lb(b + 1));
+        }
+        assert_eq!(tot.enforce_ub_strict(0), Err(Error::Unsat));
+        assert_eq!(tot.enforce_lb_strict(4), Err(Error::Unsat));
+    }
 }
@@ -0,0 +1,184 @@
+//! # Dependency Tracking Between Encodings
+//!
+//! When building layered encodings, literals produced by one encoding (e.g.,
+//! a literal reifying that a constraint holds) are fed as inputs to another
+//! encoding. A [`ConstraintGraph`] records the input literals and the
+//! auxiliary variables of each encoding so that accidentally using a variable
+//! as both an input and an auxiliary variable can be detected.
+
+use std::ops::Range;
+
+use thiserror::Error;
+
+use crate::{
+    instances::ManageVars,
+    types::{Lit, Var},
+    var,
+};
+
+/// Errors detected when validating a [`ConstraintGraph`]
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum GraphError {
+    /// An input variable of an encoding is an auxiliary variable of the same
+    /// or a later encoding
+    #[error(
+        "input variable {var} of encoding {node} is an auxiliary variable of encoding {aux_of}"
+    )]
+    AuxAsInput {
+        /// The offending variable
+        var: Var,
+        /// The encoding that uses the variable as an input
+        node: usize,
+        /// The encoding that introduced the variable as an auxiliary variable
+        aux_of: usize,
+    },
+}
+
+/// A single encoding in a [`ConstraintGraph`]
+struct Node {
+    /// The input literals of the encoding
+    inputs: Vec<Lit>,
+    /// The indices of the auxiliary variables introduced by the encoding
+    aux: Range<u32>,
+}
+
+/// Graph tracking the dependencies between encodings. Each encoding is added
+/// with its input literals and built via [`ConstraintGraph::encode`], which
+/// records the auxiliary variables the encoding introduces. This assumes that
+/// the variable manager hands out variables with consecutive indices, as
+/// [`crate::instances::BasicVarManager`] does.
+///
+/// An encoding may use auxiliary variables of encodings added _before_ it as
+/// inputs. These uses are the edges of the graph, see
+/// [`ConstraintGraph::dependencies`].
+#[derive(Default)]
+pub struct ConstraintGraph {
+    nodes: Vec<Node>,
+}
+
+impl ConstraintGraph {
+    /// Builds an encoding over the given input literals by calling `encode`
+    /// with the variable manager. Returns the index of the new encoding in the
+    /// graph and the return value of `encode`.
+    pub fn encode<LI, F, R>(
+        &mut self,
+        inputs: LI,
+        var_manager: &mut dyn ManageVars,
+        encode: F,
+    ) -> (usize, R)
+    where
+        LI: IntoIterator<Item = Lit>,
+        F: FnOnce(&mut dyn ManageVars) -> R,
+    {
+        let before = var_manager.n_used();
+        let res = encode(var_manager);
+        self.nodes.push(Node {
+            inputs: inputs.into_iter().collect(),
+            aux: before..var_manager.n_used(),
+        });
+        (self.nodes.len() - 1, res)
+    }
+
+    /// Gets the number of encodings in the graph
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Checks whether the graph contains no encodings
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Gets the encoding that introduced a variable as an auxiliary variable
+    fn aux_owner(&self, var: Var) -> Option<usize> {
+        self.nodes
+            .iter()
+            .position(|node| node.aux.contains(&var.idx32()))
+    }
+
+    /// Gets the encodings whose auxiliary variables are used as inputs of the
+    /// given encoding
+    ///
+    /// # Panics
+    ///
+    /// If `node` is not an index of an encoding in the graph.
+    #[must_use]
+    pub fn dependencies(&self, node: usize) -> Vec<usize> {
+        let mut deps: Vec<usize> = self.nodes[node]
+            .inputs
+            .iter()
+            .filter_map(|lit| self.aux_owner(lit.var()))
+            .collect();
+        deps.sort_unstable();
+        deps.dedup();
+        deps
+    }
+
+    /// Validates the graph, i.e., checks that no encoding uses one of its own
+    /// auxiliary variables or one of an encoding added after it as an input.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GraphError::AuxAsInput`] for the first violation found.
+    pub fn validate(&self) -> Result<(), GraphError> {
+        for (idx, node) in self.nodes.iter().enumerate() {
+            for lit in &node.inputs {
+                if let Some(aux_of) = self.aux_owner(lit.var()) {
+                    if aux_of >= idx {
+                        return Err(GraphError::AuxAsInput {
+                            var: lit.var(),
+                            node: idx,
+                            aux_of,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Gets the auxiliary variables introduced by an encoding
+    ///
+    /// # Panics
+    ///
+    /// If `node` is not an index of an encoding in the graph.
+    pub fn aux_vars(&self, node: usize) -> impl Iterator<Item = Var> {
+        self.nodes[node].aux.clone().map(|idx| var![idx])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConstraintGraph, GraphError};
+    use crate::{
+        encodings::card::{BoundUpper, Totalizer},
+        instances::{BasicVarManager, Cnf, ManageVars},
+        lit, var,
+    };
+
+    #[test]
+    fn input_used_as_aux() {
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![2]);
+        let mut graph = ConstraintGraph::default();
+        let mut cnf = Cnf::new();
+        // Variable 2 is not known to the variable manager yet and will be
+        // handed out as an auxiliary variable
+        let inputs = [lit![0], lit![1], lit![2]];
+        let mut tot = Totalizer::from(inputs.to_vec());
+        let (node, res) = graph.encode(inputs, &mut var_manager, |vm| {
+            tot.encode_ub(.., &mut cnf, vm)
+        });
+        res.unwrap();
+        assert_eq!(
+            graph.validate(),
+            Err(GraphError::AuxAsInput {
+                var: var![2],
+                node,
+                aux_of: node
+            })
+        );
+    }
+}
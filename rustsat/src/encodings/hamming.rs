@@ -0,0 +1,96 @@
+//! # Hamming Distance Encodings
+//!
+//! Encodings for constraining the Hamming distance between models and a
+//! reference assignment, as used for finding diverse solutions. Since the
+//! reference values are constants, the literal expressing that a variable
+//! differs from the reference (`x_i XOR ref_i`) is simply either the positive
+//! or the negative literal of the variable and no auxiliary variables are
+//! needed for it.
+
+use super::{
+    card::{BoundLower, DefLowerBounding},
+    CollectClauses,
+};
+use crate::{
+    instances::ManageVars,
+    types::{Assignment, Lit, TernaryVal, Var},
+};
+
+/// Gets the literals that are true if and only if the respective variable
+/// differs from its value in the reference assignment. Variables that are not
+/// assigned in the reference are treated as assigned to false.
+#[must_use]
+pub fn diff_lits(reference: &Assignment, vars: &[Var]) -> Vec<Lit> {
+    vars.iter()
+        .map(|&var| match reference.var_value(var) {
+            TernaryVal::True => var.neg_lit(),
+            TernaryVal::False | TernaryVal::DontCare => var.pos_lit(),
+        })
+        .collect()
+}
+
+/// Encodes that models differ from the reference assignment in at least `d`
+/// of the given variables with the default lower bounding cardinality
+/// encoding. See [`at_least`].
+///
+/// # Errors
+///
+/// If the collector runs out of memory, returns [`crate::OutOfMemory`].
+pub fn default_at_least<Col: CollectClauses>(
+    reference: &Assignment,
+    vars: &[Var],
+    d: usize,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<DefLowerBounding, crate::OutOfMemory> {
+    at_least::<DefLowerBounding, Col>(reference, vars, d, collector, var_manager)
+}
+
+/// Encodes that models differ from the reference assignment in at least `d`
+/// of the given variables with a lower bounding cardinality encoding of
+/// choice over the [`diff_lits`]. The encoding is built for exactly the bound
+/// `d` and returned, the distance is only enforced when assuming the
+/// assumptions from [`BoundLower::enforce_lb`] with bound `d`. Since the
+/// encoding is returned, its lower bound can later be changed.
+///
+/// # Errors
+///
+/// If the collector runs out of memory, returns [`crate::OutOfMemory`].
+pub fn at_least<CE, Col>(
+    reference: &Assignment,
+    vars: &[Var],
+    d: usize,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<CE, crate::OutOfMemory>
+where
+    CE: BoundLower + FromIterator<Lit>,
+    Col: CollectClauses,
+{
+    let mut enc = CE::from_iter(diff_lits(reference, vars));
+    enc.encode_lb(d..=d, collector, var_manager)?;
+    Ok(enc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diff_lits;
+    use crate::{
+        lit,
+        types::{Assignment, TernaryVal},
+        var,
+    };
+
+    #[test]
+    fn diff_lits_polarity() {
+        let reference = Assignment::from(vec![
+            TernaryVal::True,
+            TernaryVal::False,
+            TernaryVal::DontCare,
+        ]);
+        assert_eq!(
+            diff_lits(&reference, &[var![0], var![1], var![2], var![3]]),
+            vec![!lit![0], lit![1], lit![2], lit![3]]
+        );
+    }
+}
@@ -0,0 +1,145 @@
+//! # Lexicographic Ordering Encodings
+//!
+//! Encodings for constraining the lexicographic order of two bit-vectors, as
+//! commonly used for symmetry breaking and tie-breaking. Bit-vectors are given
+//! as slices of literals with the most significant bit first. If the two
+//! vectors are of different length, the shorter one is padded with zeros at
+//! the most significant end.
+
+use super::CollectClauses;
+use crate::{
+    instances::ManageVars,
+    types::{Clause, Lit},
+};
+
+/// Encodes that the bit-vector `a` is lexicographically smaller or equal to the
+/// bit-vector `b`. Both vectors are interpreted most significant bit first and
+/// the shorter vector is zero-padded. The encoding introduces one auxiliary
+/// variable per bit position but the last one, each of which is implied by
+/// the prefix up to that position being equal.
+///
+/// # Errors
+///
+/// If the collector runs out of memory, returns [`crate::OutOfMemory`].
+pub fn lex_leq<Col>(
+    a: &[Lit],
+    b: &[Lit],
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<(), crate::OutOfMemory>
+where
+    Col: CollectClauses,
+{
+    let len = std::cmp::max(a.len(), b.len());
+    // `None` stands for a padded constant false bit
+    let bit = |vec: &[Lit], idx: usize| {
+        if idx + vec.len() < len {
+            None
+        } else {
+            Some(vec[idx + vec.len() - len])
+        }
+    };
+    // `None` stands for the constant true prefix equality of the first bit
+    let mut prefix_eq: Option<Lit> = None;
+    for idx in 0..len {
+        let (a_bit, b_bit) = (bit(a, idx), bit(b, idx));
+        let with_prefix = |lits: &[Option<Lit>]| -> Clause {
+            let mut cl: Clause = lits.iter().flatten().copied().collect();
+            if let Some(eq) = prefix_eq {
+                cl.add(!eq);
+            }
+            cl
+        };
+        // Equal prefix implies `a_bit -> b_bit`
+        if let Some(a_bit) = a_bit {
+            collector.add_clause(with_prefix(&[Some(!a_bit), b_bit]))?;
+        }
+        if idx + 1 == len {
+            break;
+        }
+        // Equal prefix and equal bits imply the extended prefix is equal
+        let next_eq = var_manager.new_lit();
+        if let Some(a_bit) = a_bit {
+            collector.add_clause(with_prefix(&[Some(!a_bit), Some(next_eq)]))?;
+        }
+        collector.add_clause(with_prefix(&[b_bit, Some(next_eq)]))?;
+        prefix_eq = Some(next_eq);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lex_leq;
+    use crate::{
+        instances::{BasicVarManager, Cnf, ManageVars},
+        lit,
+        types::{Assignment, Lit, TernaryVal},
+        var,
+    };
+
+    /// Checks by enumeration whether the CNF is satisfiable with the given
+    /// fixed input assignment
+    fn sat_with(cnf: &Cnf, fixed: &[(Lit, bool)], n_vars: u32) -> bool {
+        (0..1_u64 << n_vars).any(|bits| {
+            let mut assign = Assignment::default();
+            for idx in 0..n_vars {
+                assign.assign_var(var![idx], TernaryVal::from(bits & (1 << idx) != 0));
+            }
+            fixed
+                .iter()
+                .all(|&(l, val)| assign.lit_value(l) == TernaryVal::from(val))
+                && cnf.iter().all(|cl| cl.is_sat(&assign))
+        })
+    }
+
+    fn value(bits: &[bool]) -> usize {
+        bits.iter().fold(0, |val, &b| 2 * val + usize::from(b))
+    }
+
+    fn check(len_a: u32, len_b: u32) {
+        let a: Vec<Lit> = (0..len_a).map(|idx| lit![idx]).collect();
+        let b: Vec<Lit> = (len_a..len_a + len_b).map(|idx| lit![idx]).collect();
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![len_a + len_b]);
+        let mut cnf = Cnf::new();
+        lex_leq(&a, &b, &mut cnf, &mut var_manager).unwrap();
+        let n_vars = var_manager.n_used();
+        for in_bits in 0..1_u32 << (len_a + len_b) {
+            let vals: Vec<bool> = (0..len_a + len_b)
+                .map(|idx| in_bits & (1 << idx) != 0)
+                .collect();
+            let (a_vals, b_vals) = vals.split_at(len_a as usize);
+            let fixed: Vec<(Lit, bool)> = a
+                .iter()
+                .chain(b.iter())
+                .copied()
+                .zip(vals.iter().copied())
+                .collect();
+            assert_eq!(
+                sat_with(&cnf, &fixed, n_vars),
+                value(a_vals) <= value(b_vals),
+                "a = {a_vals:?}, b = {b_vals:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn leq_equal_length() {
+        check(1, 1);
+        check(3, 3);
+    }
+
+    #[test]
+    fn leq_unequal_length() {
+        check(2, 3);
+        check(3, 1);
+    }
+
+    #[test]
+    fn leq_empty() {
+        check(0, 0);
+        check(0, 2);
+        check(2, 0);
+    }
+}
@@ -0,0 +1,93 @@
+//! # Monotone Sequence Encodings
+//!
+//! Encodings for sequences of literals that are monotone non-increasing, i.e.,
+//! where a true literal is only preceded by true literals, as they appear,
+//! e.g., in staircase scheduling models. The number of true literals in such
+//! a sequence is the length of its true prefix, which allows bounding it
+//! without the auxiliary variables of a general cardinality encoding.
+
+use super::{atomics, CollectClauses};
+use crate::types::{Clause, Lit};
+
+/// Encodes that the sequence of literals is monotone non-increasing and that
+/// at most `k` of the literals are true. Monotonicity is encoded by the
+/// implications `lits[i+1] -> lits[i]`, the cardinality by fixing `lits[k]`
+/// to false. The encoding consists of at most `lits.len()` clauses and does
+/// not introduce auxiliary variables.
+///
+/// # Errors
+///
+/// If the collector runs out of memory, returns [`crate::OutOfMemory`].
+pub fn monotone_with_count<Col>(
+    lits: &[Lit],
+    k: usize,
+    collector: &mut Col,
+) -> Result<(), crate::OutOfMemory>
+where
+    Col: CollectClauses,
+{
+    collector.extend_clauses(
+        lits.windows(2)
+            .map(|win| atomics::lit_impl_lit(win[1], win[0])),
+    )?;
+    if k < lits.len() {
+        collector.add_clause(Clause::from([!lits[k]]))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::monotone_with_count;
+    use crate::{
+        encodings::card::{BoundUpper, Totalizer},
+        instances::{BasicVarManager, Cnf, ManageVars},
+        lit,
+        types::{Assignment, Lit, TernaryVal},
+        var,
+    };
+
+    fn check(n: u32, k: usize) {
+        let lits: Vec<Lit> = (0..n).map(|idx| lit![idx]).collect();
+        let mut cnf = Cnf::new();
+        monotone_with_count(&lits, k, &mut cnf).unwrap();
+        assert!(cnf.len() <= lits.len());
+        for bits in 0..1_u32 << n {
+            let mut assign = Assignment::default();
+            for idx in 0..n {
+                assign.assign_var(var![idx], TernaryVal::from(bits & (1 << idx) != 0));
+            }
+            let count = bits.count_ones() as usize;
+            // Monotone non-increasing iff the true literals form a prefix
+            let monotone = bits == (1 << count) - 1;
+            assert_eq!(
+                cnf.iter().all(|cl| cl.is_sat(&assign)),
+                monotone && count <= k,
+                "bits = {bits:b}"
+            );
+        }
+    }
+
+    #[test]
+    fn monotone_count() {
+        check(1, 0);
+        check(5, 0);
+        check(5, 2);
+        check(5, 5);
+        check(5, 7);
+    }
+
+    #[test]
+    fn smaller_than_totalizer() {
+        let lits: Vec<Lit> = (0..10).map(|idx| lit![idx]).collect();
+        let mut cnf = Cnf::new();
+        monotone_with_count(&lits, 4, &mut cnf).unwrap();
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![10]);
+        let mut tot_cnf = Cnf::new();
+        let mut tot = Totalizer::from(lits);
+        tot.encode_ub(4..=4, &mut tot_cnf, &mut var_manager)
+            .unwrap();
+        assert!(cnf.len() < tot_cnf.len());
+    }
+}
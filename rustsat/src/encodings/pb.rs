@@ -40,20 +40,27 @@ use std::{
 use super::{card, CollectClauses, Error};
 use crate::{
     clause,
-    instances::ManageVars,
+    instances::{Cnf, ManageVars},
     types::{
         constraints::{PBConstraint, PBEQConstr, PBLBConstr, PBUBConstr},
-        Clause, Lit,
+        Clause, IWLitIter, Lit,
     },
 };
 
 pub mod gte;
-pub use gte::GeneralizedTotalizer;
+pub use gte::{GeneralizedTotalizer, TreeStrategy};
 
 pub mod simulators;
 /// Inverted generalized totalizer that can be used for lower bounding PB expressions
 pub type InvertedGeneralizedTotalizer = simulators::Inverted<GeneralizedTotalizer>;
 /// Double generalized totalizer that can be used for upper and lower bounding PB expressions
+///
+/// The two sub-encodings do not share any structure, but both sides are built
+/// lazily for the bound ranges that are actually needed. A
+/// [`GeneralizedTotalizer`] itself also supports both bounds with a single
+/// tree by fully encoding it with [`GeneralizedTotalizer::encode_ge`], which
+/// roughly halves the number of variables if large ranges of both bounds are
+/// needed.
 pub type DoubleGeneralizedTotalizer =
     simulators::Double<GeneralizedTotalizer, InvertedGeneralizedTotalizer>;
 
@@ -63,9 +70,25 @@ pub use dpw::DynamicPolyWatchdog;
 pub mod dbgte;
 pub use dbgte::DbGte;
 
+pub mod swc;
+pub use swc::SequentialWeightCounter;
+
+pub mod chunked;
+/// Generalized totalizer that encodes large constraints in chunks of bounded
+/// weight, see [`chunked::Chunked`]
+pub type ChunkedGeneralizedTotalizer = chunked::Chunked<GeneralizedTotalizer>;
+
+pub mod adder;
+pub use adder::AdderPb;
+
+pub mod auto;
+pub use auto::PbEncoder;
+
 /// Trait for all pseudo-boolean encodings of form `weighted sum of lits <> rhs`
 pub trait Encode {
-    /// Get the sum of weights in the encoding
+    /// Get the sum of weights in the encoding. This includes the weights of
+    /// input literals that are not yet encoded and is therefore an upper bound
+    /// on any upper bound that needs to be enforced.
     fn weight_sum(&self) -> usize;
     /// Gets the next higher value possible to be achieved by the weighted sum.
     /// Might simply return `val + 1` if no stronger value can be inferred.
@@ -100,6 +123,15 @@ pub trait BoundUpper: Encode {
     /// adequately and nothing has been called afterwards, otherwise
     /// [`Error::NotEncoded`] will be returned.
     fn enforce_ub(&self, ub: usize) -> Result<Vec<Lit>, Error>;
+    /// Returns assumptions/units for enforcing a strict upper bound (`weighted sum of lits
+    /// < ub`), i.e., [`BoundUpper::enforce_ub`] with `ub - 1`. If `ub` is zero,
+    /// [`Error::Unsat`] is returned.
+    fn enforce_ub_strict(&self, ub: usize) -> Result<Vec<Lit>, Error> {
+        match ub.checked_sub(1) {
+            Some(ub) => self.enforce_ub(ub),
+            None => Err(Error::Unsat),
+        }
+    }
     /// Encodes an upper bound pseudo-boolean constraint to CNF
     ///
     /// # Errors
@@ -122,12 +154,12 @@ pub trait BoundUpper: Encode {
         };
         let mut enc = Self::from_iter(lits);
         enc.encode_ub(ub..ub + 1, collector, var_manager)?;
-        collector.extend_clauses(
-            enc.enforce_ub(ub)
-                .unwrap()
-                .into_iter()
-                .map(|unit| clause![unit]),
-        )?;
+        let units = match enc.enforce_ub(ub) {
+            // The empty sum satisfies any non-negative bound
+            Err(Error::EmptyEncoding) => return Ok(()),
+            res => res.unwrap(),
+        };
+        collector.extend_clauses(units.into_iter().map(|unit| clause![unit]))?;
         Ok(())
     }
     /// Gets the next smaller upper bound value that can be _easily_ encoded. This
@@ -161,6 +193,14 @@ pub trait BoundLower: Encode {
     /// the weighted sum of literals in the encoding, [`Error::Unsat`]
     /// is returned.
     fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error>;
+    /// Returns assumptions/units for enforcing a strict lower bound (`weighted sum of lits
+    /// > lb`), i.e., [`BoundLower::enforce_lb`] with `lb + 1`.
+    fn enforce_lb_strict(&self, lb: usize) -> Result<Vec<Lit>, Error> {
+        match lb.checked_add(1) {
+            Some(lb) => self.enforce_lb(lb),
+            None => Err(Error::Unsat),
+        }
+    }
     /// Encodes a lower bound pseudo-boolean constraint to CNF
     ///
     /// # Errors
@@ -183,12 +223,12 @@ pub trait BoundLower: Encode {
         };
         let mut enc = Self::from_iter(lits);
         enc.encode_lb(lb..lb + 1, collector, var_manager)?;
-        collector.extend_clauses(
-            enc.enforce_lb(lb)
-                .unwrap()
-                .into_iter()
-                .map(|unit| clause![unit]),
-        )?;
+        let units = match enc.enforce_lb(lb) {
+            Err(Error::EmptyEncoding) if lb == 0 => return Ok(()),
+            Err(Error::EmptyEncoding) => anyhow::bail!(Error::Unsat),
+            res => res.unwrap(),
+        };
+        collector.extend_clauses(units.into_iter().map(|unit| clause![unit]))?;
         Ok(())
     }
     /// Gets the next greater lower bound value that can be _easily_ encoded. This
@@ -230,6 +270,34 @@ pub trait BoundBoth: BoundUpper + BoundLower {
         assumps.extend(self.enforce_lb(b)?);
         Ok(assumps)
     }
+    /// Channels a one-hot encoding of the value of the weighted sum to the
+    /// encoding, i.e., returns clauses under which `onehot[v]` is true if and
+    /// only if the weighted sum is `v`. The clauses require exactly one of the
+    /// one-hot literals to be true and `onehot[v]` to imply
+    /// [`BoundBoth::enforce_eq`] for `v`. Since both bounds are needed, this
+    /// requires an encoding of both directions, e.g., a
+    /// [`DoubleGeneralizedTotalizer`] rather than a [`GeneralizedTotalizer`].
+    /// One-hot literals for values larger than the weight sum are forced to
+    /// false, values not covered by `onehot` are excluded.
+    ///
+    /// # Errors
+    ///
+    /// If the encoding is not built for enforcing any of the values covered by
+    /// `onehot`, [`Error::NotEncoded`] is returned.
+    fn channel_onehot(&self, onehot: &[Lit]) -> Result<Cnf, Error> {
+        let mut cnf = Cnf::new();
+        cnf.add_clause(onehot.iter().copied().collect());
+        for (val, &hot) in onehot.iter().enumerate() {
+            match self.enforce_eq(val) {
+                Ok(assumps) => assumps
+                    .into_iter()
+                    .for_each(|a| cnf.add_lit_impl_lit(hot, a)),
+                Err(Error::Unsat) => cnf.add_unit(!hot),
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(cnf)
+    }
     /// Encodes an equality pseudo-boolean constraint to CNF
     ///
     /// # Errors
@@ -252,12 +320,12 @@ pub trait BoundBoth: BoundUpper + BoundLower {
         };
         let mut enc = Self::from_iter(lits);
         enc.encode_both(b..b + 1, collector, var_manager)?;
-        collector.extend_clauses(
-            enc.enforce_eq(b)
-                .unwrap()
-                .into_iter()
-                .map(|unit| clause![unit]),
-        )?;
+        let units = match enc.enforce_eq(b) {
+            Err(Error::EmptyEncoding) if b == 0 => return Ok(()),
+            Err(Error::EmptyEncoding) => anyhow::bail!(Error::Unsat),
+            res => res.unwrap(),
+        };
+        collector.extend_clauses(units.into_iter().map(|unit| clause![unit]))?;
         Ok(())
     }
     /// Encodes any pseudo-boolean constraint to CNF
@@ -286,6 +354,69 @@ pub trait BoundBoth: BoundUpper + BoundLower {
 /// and lower bounding
 impl<PBE> BoundBoth for PBE where PBE: BoundUpper + BoundLower {}
 
+/// The direction of a bound on a weighted sum of literals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundDir {
+    /// An upper bound, i.e., `weighted sum of lits <= b`
+    Upper,
+    /// A lower bound, i.e., `weighted sum of lits >= b`
+    Lower,
+}
+
+/// Trait for pseudo-boolean encodings that are bounded in a direction chosen at
+/// runtime. This allows writing algorithms, e.g., for minimization and
+/// maximization, that are generic over the bound direction. Only encodings
+/// supporting both directions implement this trait.
+pub trait BoundDirected: Encode {
+    /// Lazily builds the pseudo-boolean encoding to enable bounds of direction
+    /// `dir` within a given range, see [`BoundUpper::encode_ub`] and
+    /// [`BoundLower::encode_lb`].
+    fn encode_bound<Col, R>(
+        &mut self,
+        dir: BoundDir,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>;
+    /// Returns assumptions/units for enforcing a bound of direction `dir`, see
+    /// [`BoundUpper::enforce_ub`] and [`BoundLower::enforce_lb`].
+    fn enforce_bound(&self, dir: BoundDir, bound: usize) -> Result<Vec<Lit>, Error>;
+}
+
+/// Default implementation of [`BoundDirected`] for every encoding that does
+/// upper and lower bounding
+impl<PBE> BoundDirected for PBE
+where
+    PBE: BoundUpper + BoundLower,
+{
+    fn encode_bound<Col, R>(
+        &mut self,
+        dir: BoundDir,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        match dir {
+            BoundDir::Upper => self.encode_ub(range, collector, var_manager),
+            BoundDir::Lower => self.encode_lb(range, collector, var_manager),
+        }
+    }
+
+    fn enforce_bound(&self, dir: BoundDir, bound: usize) -> Result<Vec<Lit>, Error> {
+        match dir {
+            BoundDir::Upper => self.enforce_ub(bound),
+            BoundDir::Lower => self.enforce_lb(bound),
+        }
+    }
+}
+
 /// Trait for all pseudo-boolean encodings of form `sum of lits <> rhs`
 pub trait EncodeIncremental: Encode {
     /// Reserves all variables this encoding might need
@@ -444,6 +575,82 @@ pub fn encode_pb_constraint<PBE: BoundBoth + FromIterator<(Lit, usize)>, Col: Co
     }
 }
 
+/// Extends a pseudo-boolean encoding with literals with signed weights. A term
+/// `-w * l` with a negative weight is added as `w * !l`, which shifts the
+/// weighted sum by `w`. The accumulated shift is returned as an offset, i.e.,
+/// the signed weighted sum of the added literals equals the weighted sum in
+/// the encoding minus the offset. A signed upper bound `ub` therefore needs to
+/// be enforced as `ub + offset` (and equally for lower bounds).
+///
+/// ```
+/// # use rustsat::{encodings::pb::{self, Encode, GeneralizedTotalizer}, lit};
+/// let mut enc = GeneralizedTotalizer::default();
+/// // 2 x0 - 3 x1 <= 0 is equivalent to 2 x0 + 3 !x1 <= 3
+/// let offset = pb::extend_signed(&mut enc, [(lit![0], 2), (lit![1], -3)]);
+/// assert_eq!(offset, 3);
+/// assert_eq!(enc.weight_sum(), 5);
+/// ```
+pub fn extend_signed<PBE, LI>(enc: &mut PBE, lits: LI) -> usize
+where
+    PBE: Extend<(Lit, usize)>,
+    LI: IWLitIter,
+{
+    let mut offset = 0;
+    enc.extend(lits.into_iter().filter_map(|(l, w)| {
+        if w == 0 {
+            return None;
+        }
+        let abs_w = w.unsigned_abs();
+        if w < 0 {
+            offset += abs_w;
+        }
+        Some((l.flip_if(w < 0), abs_w))
+    }));
+    offset
+}
+
+/// Encodes that at least one of several upper bounded pseudo-boolean
+/// constraints holds. Each pair of an encoding and an upper bound is reified
+/// by a fresh selector literal that implies `weighted sum <= ub`. The returned
+/// clause over the selectors needs to be added to enforce the disjunction, it
+/// can also be used as an assumption-based switch by adding it with an
+/// additional literal.
+///
+/// Encodings without input literals always hold, their selectors are not
+/// constrained.
+///
+/// # Errors
+///
+/// If the collector runs out of memory, returns [`crate::OutOfMemory`].
+///
+/// # Panics
+///
+/// If an encoding does not allow enforcing the bound it was encoded for.
+pub fn disjunction<'enc, PBE, Col, I>(
+    constrs: I,
+    collector: &mut Col,
+    var_manager: &mut dyn ManageVars,
+) -> Result<Clause, crate::OutOfMemory>
+where
+    PBE: BoundUpper + 'enc,
+    Col: CollectClauses,
+    I: IntoIterator<Item = (&'enc mut PBE, usize)>,
+{
+    let mut selectors = Clause::new();
+    for (enc, ub) in constrs {
+        enc.encode_ub(ub..=ub, collector, var_manager)?;
+        let assumps = match enc.enforce_ub(ub) {
+            Ok(assumps) => assumps,
+            Err(Error::EmptyEncoding) => vec![],
+            Err(err) => panic!("unexpected error enforcing encoded bound: {err}"),
+        };
+        let sel = var_manager.new_lit();
+        collector.extend_clauses(assumps.into_iter().map(|a| clause![!sel, a]))?;
+        selectors.add(sel);
+    }
+    Ok(selectors)
+}
+
 fn prepare_ub_range<Enc: Encode, R: RangeBounds<usize>>(enc: &Enc, range: R) -> Range<usize> {
     (match range.start_bound() {
         Bound::Included(b) => *b,
@@ -0,0 +1,337 @@
+//! # Adder Network
+//!
+//! Implementation of the adder network encoding \[1\]. The weighted sum of the
+//! input literals is computed in binary by a network of full and half adders,
+//! whose output is compared against the bound. The encoding produces
+//! `O(n log w)` clauses and is therefore much smaller than the
+//! [`super::GeneralizedTotalizer`] for constraints with many inputs, but
+//! propagates poorly.
+//!
+//! ## References
+//!
+//! - \[1\] Joost P. Warners: _A linear-time transformation of linear
+//!   inequalities into conjunctive normal form_, Information Processing
+//!   Letters 1998.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    ops::RangeBounds,
+};
+
+use super::{
+    gte::{copy_key_val, GteIter},
+    BoundUpper, Encode,
+};
+use crate::{
+    clause,
+    encodings::{CollectClauses, EncodeStats, Error, IterWeightedInputs},
+    instances::ManageVars,
+    types::{Clause, Lit, RsHashMap},
+};
+
+/// Implementation of the adder network encoding \[1\]. Every input literal is
+/// added to a bucket for each bit that is set in its weight. The buckets are
+/// reduced from the least significant bit upwards by full and half adders,
+/// where the sum stays in the bucket and the carry moves to the next bucket,
+/// until each bucket holds at most one literal, the output bit. An upper bound
+/// is enforced by a comparator on the output bits that is activated by a
+/// single assumption.
+///
+/// [`BoundUpper::encode_ub`] encodes a separate comparator, with one variable
+/// and up to one clause per output bit, for _every_ bound in the requested
+/// range. The range should therefore be narrow, e.g., only the bounds that
+/// will actually be enforced, rather than `..` for large weight sums.
+///
+/// Only the implications from the inputs to the outputs are encoded, since
+/// this is all that is needed for upper bounding. Extending the encoding with
+/// new input literals invalidates it, i.e., the next call to
+/// [`BoundUpper::encode_ub`] rebuilds the adder network.
+///
+/// # References
+///
+/// - \[1\] Joost P. Warners: _A linear-time transformation of linear
+///   inequalities into conjunctive normal form_, Information Processing
+///   Letters 1998.
+#[derive(Default, Clone)]
+pub struct AdderPb {
+    /// Input literals and weights for the encoding
+    in_lits: RsHashMap<Lit, usize>,
+    /// Sum of all input weight
+    weight_sum: usize,
+    /// The output bits of the adder network, least significant first. Bits
+    /// that are constantly false are [`None`].
+    out_bits: Vec<Option<Lit>>,
+    /// The literals activating the comparator for each encoded upper bound
+    comparators: BTreeMap<usize, Lit>,
+    /// The number of variables in the encoding
+    n_vars: u32,
+    /// The number of clauses in the encoding
+    n_clauses: usize,
+}
+
+impl AdderPb {
+    /// Builds the adder network over the input literals
+    fn encode_adders<Col>(
+        &mut self,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let n_bits = (usize::BITS - self.weight_sum.leading_zeros()) as usize;
+        // Sort inputs for a deterministic encoding
+        let mut lits: Vec<(Lit, usize)> = self
+            .in_lits
+            .iter()
+            .filter_map(|(&l, &w)| if w > 0 { Some((l, w)) } else { None })
+            .collect();
+        lits.sort_unstable();
+        let mut buckets: Vec<VecDeque<Lit>> = vec![VecDeque::new(); n_bits];
+        for (lit, weight) in lits {
+            for (bit, bucket) in buckets.iter_mut().enumerate() {
+                if weight & (1 << bit) != 0 {
+                    bucket.push_back(lit);
+                }
+            }
+        }
+        self.out_bits.clear();
+        let mut bit = 0;
+        while bit < buckets.len() {
+            while buckets[bit].len() >= 2 {
+                let a = buckets[bit].pop_front().unwrap();
+                let b = buckets[bit].pop_front().unwrap();
+                let sum = var_manager.new_lit();
+                let carry = var_manager.new_lit();
+                if let Some(c) = buckets[bit].pop_front() {
+                    collector.extend_clauses(full_adder(a, b, c, sum, carry))?;
+                } else {
+                    collector.extend_clauses(half_adder(a, b, sum, carry))?;
+                }
+                buckets[bit].push_back(sum);
+                if bit + 1 == buckets.len() {
+                    buckets.push(VecDeque::new());
+                }
+                buckets[bit + 1].push_back(carry);
+            }
+            self.out_bits.push(buckets[bit].pop_front());
+            bit += 1;
+        }
+        Ok(())
+    }
+
+    /// Encodes the comparator for `output bits <= ub`, activated by a fresh
+    /// literal
+    fn encode_comparator<Col>(
+        &self,
+        ub: usize,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<Lit, crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let active = var_manager.new_lit();
+        // The value exceeds `ub` iff for some bit that is not set in `ub`, the
+        // output bit and all higher output bits that are set in `ub` are true
+        let clauses = self.out_bits.iter().enumerate().filter_map(|(bit, &out)| {
+            if ub & (1 << bit) != 0 {
+                return None;
+            }
+            let mut cl = clause![!active, !out?];
+            for (higher, &higher_out) in self.out_bits.iter().enumerate().skip(bit + 1) {
+                if ub & (1 << higher) != 0 {
+                    // A constantly false output bit satisfies the clause
+                    cl.add(!higher_out?);
+                }
+            }
+            Some(cl)
+        });
+        collector.extend_clauses(clauses)?;
+        Ok(active)
+    }
+}
+
+/// Clauses for a full adder where `sum` and `carry` are implied by the
+/// corresponding value of `a + b + c`
+fn full_adder(a: Lit, b: Lit, c: Lit, sum: Lit, carry: Lit) -> [Clause; 7] {
+    [
+        clause![!a, b, c, sum],
+        clause![a, !b, c, sum],
+        clause![a, b, !c, sum],
+        clause![!a, !b, !c, sum],
+        clause![!a, !b, carry],
+        clause![!a, !c, carry],
+        clause![!b, !c, carry],
+    ]
+}
+
+/// Clauses for a half adder where `sum` and `carry` are implied by the
+/// corresponding value of `a + b`
+fn half_adder(a: Lit, b: Lit, sum: Lit, carry: Lit) -> [Clause; 3] {
+    [
+        clause![!a, b, sum],
+        clause![a, !b, sum],
+        clause![!a, !b, carry],
+    ]
+}
+
+impl Encode for AdderPb {
+    fn weight_sum(&self) -> usize {
+        self.weight_sum
+    }
+}
+
+impl IterWeightedInputs for AdderPb {
+    type Iter<'a> = GteIter<'a>;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.in_lits.iter().map(copy_key_val)
+    }
+}
+
+impl BoundUpper for AdderPb {
+    fn encode_ub<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_ub_range(self, range);
+        if range.is_empty() {
+            return Ok(());
+        }
+        let n_vars_before = var_manager.n_used();
+        let n_clauses_before = collector.n_clauses();
+        if self.out_bits.is_empty() {
+            self.encode_adders(collector, var_manager)?;
+        }
+        for ub in range {
+            if self.comparators.contains_key(&ub) {
+                continue;
+            }
+            let active = self.encode_comparator(ub, collector, var_manager)?;
+            self.comparators.insert(ub, active);
+        }
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.n_vars += var_manager.n_used() - n_vars_before;
+        Ok(())
+    }
+
+    fn enforce_ub(&self, ub: usize) -> Result<Vec<Lit>, Error> {
+        if ub >= self.weight_sum {
+            return Ok(vec![]);
+        }
+        match self.comparators.get(&ub) {
+            Some(&active) => Ok(vec![active]),
+            None => Err(Error::NotEncoded),
+        }
+    }
+}
+
+impl EncodeStats for AdderPb {
+    fn n_clauses(&self) -> usize {
+        self.n_clauses
+    }
+
+    fn n_vars(&self) -> u32 {
+        self.n_vars
+    }
+}
+
+impl From<RsHashMap<Lit, usize>> for AdderPb {
+    fn from(lits: RsHashMap<Lit, usize>) -> Self {
+        Self {
+            weight_sum: lits.iter().fold(0, |sum, (_, w)| sum + w),
+            in_lits: lits,
+            ..Default::default()
+        }
+    }
+}
+
+impl FromIterator<(Lit, usize)> for AdderPb {
+    fn from_iter<T: IntoIterator<Item = (Lit, usize)>>(iter: T) -> Self {
+        let lits: RsHashMap<Lit, usize> = RsHashMap::from_iter(iter);
+        Self::from(lits)
+    }
+}
+
+impl Extend<(Lit, usize)> for AdderPb {
+    fn extend<T: IntoIterator<Item = (Lit, usize)>>(&mut self, iter: T) {
+        // Extending invalidates the encoding
+        self.out_bits.clear();
+        self.comparators.clear();
+        iter.into_iter().for_each(|(l, w)| {
+            self.weight_sum += w;
+            match self.in_lits.get_mut(&l) {
+                Some(old_w) => *old_w += w,
+                None => {
+                    self.in_lits.insert(l, w);
+                }
+            };
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AdderPb;
+    use crate::{
+        encodings::{pb::BoundUpper, EncodeStats, Error},
+        instances::{BasicVarManager, Cnf, ManageVars},
+        lit,
+        types::Lit,
+        var,
+    };
+
+    #[test]
+    fn ub_functions() {
+        let mut adder = AdderPb::from_iter([(lit![0], 1), (lit![1], 2), (lit![2], 1)]);
+        assert_eq!(adder.enforce_ub(2), Err(Error::NotEncoded));
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        let mut cnf = Cnf::new();
+        adder.encode_ub(1..3, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(adder.n_clauses(), cnf.len());
+        assert_eq!(adder.n_vars(), var_manager.n_used() - 3);
+        assert_eq!(adder.enforce_ub(0), Err(Error::NotEncoded));
+        let assumps: Vec<Lit> = adder.enforce_ub(2).unwrap();
+        assert_eq!(assumps.len(), 1);
+        assert_eq!(adder.enforce_ub(4), Ok(vec![]));
+    }
+
+    #[test]
+    fn ub_extend_invalidates() {
+        let mut adder = AdderPb::from_iter([(lit![0], 1), (lit![1], 2)]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        adder
+            .encode_ub(0..2, &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        assert!(adder.enforce_ub(1).is_ok());
+        adder.extend([(lit![2], 1)]);
+        assert_eq!(adder.enforce_ub(1), Err(Error::NotEncoded));
+    }
+
+    #[test]
+    fn fewer_clauses_than_gte() {
+        use crate::encodings::pb::GeneralizedTotalizer;
+        let lits: Vec<(Lit, usize)> = (0..64)
+            .map(|idx| (lit![idx], 1 + idx as usize % 5))
+            .collect();
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![64]);
+        let mut adder = AdderPb::from_iter(lits.iter().copied());
+        adder
+            .encode_ub(50..=50, &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        let mut gte = GeneralizedTotalizer::from_iter(lits);
+        gte.encode_ub(50..=50, &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        assert!(adder.n_clauses() < gte.n_clauses());
+    }
+}
@@ -0,0 +1,195 @@
+//! # Automatic Encoding Selection
+//!
+//! A pseudo-boolean encoding that picks one of the upper bounding encodings in
+//! this library based on the characteristics of the input literals, so that
+//! code using it does not need to re-implement the selection heuristic.
+
+use std::ops::RangeBounds;
+
+use super::{
+    BoundUpper, DynamicPolyWatchdog, Encode, GeneralizedTotalizer, SequentialWeightCounter,
+};
+use crate::{
+    encodings::{CollectClauses, EncodeStats, Error},
+    instances::ManageVars,
+    types::{Lit, RsHashMap, RsHashSet},
+};
+
+/// Inputs with at most this many distinct weights are encoded with the
+/// [`SequentialWeightCounter`]
+const FEW_DISTINCT_WEIGHTS: usize = 2;
+
+/// Inputs where the largest weight is more than this factor larger than the
+/// smallest weight are encoded with the [`DynamicPolyWatchdog`]
+const WIDE_WEIGHT_SPREAD: usize = 1 << 10;
+
+/// Upper bounding pseudo-boolean encoding that is selected based on the input
+/// literals with [`PbEncoder::auto`]. The encoding is not incremental, since
+/// not all of the variants are.
+pub enum PbEncoder {
+    /// The generalized totalizer, selected if no other encoding is preferable
+    Gte(GeneralizedTotalizer),
+    /// The sequential weight counter, selected for inputs with few distinct
+    /// weights
+    Swc(SequentialWeightCounter),
+    /// The dynamic polynomial watchdog, selected for inputs where the weights
+    /// span a wide range
+    Dpw(DynamicPolyWatchdog),
+}
+
+impl PbEncoder {
+    /// Selects an encoding for the given input literals. Inputs whose weights
+    /// span a wide range are encoded with the [`DynamicPolyWatchdog`], inputs
+    /// with few distinct weights with the [`SequentialWeightCounter`], and all
+    /// other inputs with the [`GeneralizedTotalizer`].
+    #[must_use]
+    pub fn auto(lits: RsHashMap<Lit, usize>) -> Self {
+        let min_weight = lits.values().copied().min().unwrap_or(0);
+        let max_weight = lits.values().copied().max().unwrap_or(0);
+        if min_weight > 0 && max_weight / min_weight > WIDE_WEIGHT_SPREAD {
+            return PbEncoder::Dpw(DynamicPolyWatchdog::from(lits));
+        }
+        let distinct: RsHashSet<usize> = lits.values().copied().collect();
+        if !distinct.is_empty() && distinct.len() <= FEW_DISTINCT_WEIGHTS {
+            return PbEncoder::Swc(SequentialWeightCounter::from(lits));
+        }
+        PbEncoder::Gte(GeneralizedTotalizer::from(lits))
+    }
+}
+
+impl Encode for PbEncoder {
+    fn weight_sum(&self) -> usize {
+        match self {
+            PbEncoder::Gte(enc) => enc.weight_sum(),
+            PbEncoder::Swc(enc) => enc.weight_sum(),
+            PbEncoder::Dpw(enc) => enc.weight_sum(),
+        }
+    }
+
+    fn next_higher(&self, val: usize) -> usize {
+        match self {
+            PbEncoder::Gte(enc) => enc.next_higher(val),
+            PbEncoder::Swc(enc) => enc.next_higher(val),
+            PbEncoder::Dpw(enc) => enc.next_higher(val),
+        }
+    }
+
+    fn next_lower(&self, val: usize) -> usize {
+        match self {
+            PbEncoder::Gte(enc) => enc.next_lower(val),
+            PbEncoder::Swc(enc) => enc.next_lower(val),
+            PbEncoder::Dpw(enc) => enc.next_lower(val),
+        }
+    }
+}
+
+impl BoundUpper for PbEncoder {
+    fn encode_ub<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        match self {
+            PbEncoder::Gte(enc) => enc.encode_ub(range, collector, var_manager),
+            PbEncoder::Swc(enc) => enc.encode_ub(range, collector, var_manager),
+            PbEncoder::Dpw(enc) => enc.encode_ub(range, collector, var_manager),
+        }
+    }
+
+    fn enforce_ub(&self, ub: usize) -> Result<Vec<Lit>, Error> {
+        match self {
+            PbEncoder::Gte(enc) => enc.enforce_ub(ub),
+            PbEncoder::Swc(enc) => enc.enforce_ub(ub),
+            PbEncoder::Dpw(enc) => enc.enforce_ub(ub),
+        }
+    }
+
+    fn coarse_ub(&self, ub: usize) -> usize {
+        match self {
+            PbEncoder::Gte(enc) => enc.coarse_ub(ub),
+            PbEncoder::Swc(enc) => enc.coarse_ub(ub),
+            PbEncoder::Dpw(enc) => enc.coarse_ub(ub),
+        }
+    }
+}
+
+impl EncodeStats for PbEncoder {
+    fn n_clauses(&self) -> usize {
+        match self {
+            PbEncoder::Gte(enc) => enc.n_clauses(),
+            PbEncoder::Swc(enc) => enc.n_clauses(),
+            PbEncoder::Dpw(enc) => enc.n_clauses(),
+        }
+    }
+
+    fn n_vars(&self) -> u32 {
+        match self {
+            PbEncoder::Gte(enc) => enc.n_vars(),
+            PbEncoder::Swc(enc) => enc.n_vars(),
+            PbEncoder::Dpw(enc) => enc.n_vars(),
+        }
+    }
+}
+
+impl From<RsHashMap<Lit, usize>> for PbEncoder {
+    fn from(lits: RsHashMap<Lit, usize>) -> Self {
+        Self::auto(lits)
+    }
+}
+
+impl FromIterator<(Lit, usize)> for PbEncoder {
+    fn from_iter<T: IntoIterator<Item = (Lit, usize)>>(iter: T) -> Self {
+        Self::auto(RsHashMap::from_iter(iter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PbEncoder;
+    use crate::{
+        encodings::pb::{BoundUpper, Encode},
+        instances::{BasicVarManager, Cnf, ManageVars},
+        lit,
+        types::Lit,
+        var,
+    };
+
+    fn auto(weights: &[usize]) -> PbEncoder {
+        weights
+            .iter()
+            .enumerate()
+            .map(|(idx, &w)| (lit![idx as u32], w))
+            .collect()
+    }
+
+    #[test]
+    fn selection() {
+        assert!(matches!(auto(&[3, 3, 3, 3]), PbEncoder::Swc(_)));
+        assert!(matches!(auto(&[1, 2, 1, 2, 1]), PbEncoder::Swc(_)));
+        assert!(matches!(auto(&[1, 5, 3, 7, 2]), PbEncoder::Gte(_)));
+        assert!(matches!(
+            auto(&[1, 3, 100_000, 2, 5_000]),
+            PbEncoder::Dpw(_)
+        ));
+        assert!(matches!(auto(&[]), PbEncoder::Gte(_)));
+    }
+
+    #[test]
+    fn dispatch() {
+        let mut enc = auto(&[1, 5, 3, 7, 2]);
+        assert_eq!(enc.weight_sum(), 18);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![5]);
+        enc.encode_ub(4..=4, &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        let assumps: Vec<Lit> = enc.enforce_ub(4).unwrap();
+        // Literals with weights larger than the bound are forced to false
+        assert!(assumps.contains(&!lit![1]));
+        assert!(assumps.contains(&!lit![3]));
+    }
+}
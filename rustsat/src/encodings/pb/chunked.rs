@@ -0,0 +1,281 @@
+//! # Chunked Pseudo-Boolean Encodings
+//!
+//! Generic code to split a large pseudo-boolean constraint into a hierarchy of
+//! smaller ones. The input literals are partitioned into chunks whose weights
+//! sum to at most a configurable maximum. Each chunk is encoded by its own
+//! sub-encoding, one chunk at a time. For every value that the partial sum of a
+//! chunk can exceed, an auxiliary literal is introduced that is implied by
+//! exceeding it. These literals, weighted by the distance to the next such
+//! value, are the inputs of a top-level encoding of the same type, which
+//! therefore bounds the sum of all chunks.
+
+use std::ops::RangeBounds;
+
+use super::{BoundUpper, Encode};
+use crate::{
+    encodings::{atomics, CollectClauses, EncodeStats, Error},
+    instances::ManageVars,
+    types::{Lit, RsHashMap},
+};
+
+/// Encoding type that splits the input literals into chunks with a weight sum
+/// of at most `max_chunk_weight`, each encoded by a separate encoding of type
+/// `PBE`, and bounds their partial sums with a top-level encoding of type
+/// `PBE`. Input literals whose weight is not smaller than `max_chunk_weight`
+/// are directly added to the top-level encoding.
+///
+/// Since every chunk is encoded over its full range when it is created, the
+/// size of the largest intermediate encoding only depends on
+/// `max_chunk_weight` and not on the size of the whole constraint.
+pub struct Chunked<PBE>
+where
+    PBE: Encode + 'static,
+{
+    /// The maximum weight sum of a chunk
+    max_chunk_weight: usize,
+    /// Input literals that are not yet assigned to a chunk
+    lit_buffer: RsHashMap<Lit, usize>,
+    /// The encodings of the chunks
+    chunks: Vec<PBE>,
+    /// The top-level encoding over the outputs of the chunks
+    top: PBE,
+    /// The sum of all input weights
+    weight_sum: usize,
+    /// The number of variables introduced for linking chunks to the top-level
+    /// encoding
+    n_vars: u32,
+    /// The number of clauses linking chunks to the top-level encoding
+    n_clauses: usize,
+}
+
+impl<PBE> Chunked<PBE>
+where
+    PBE: Encode + Default + 'static,
+{
+    /// Creates a new empty chunked encoding where chunks have a weight sum of
+    /// at most `max_chunk_weight`
+    #[must_use]
+    pub fn new(max_chunk_weight: usize) -> Self {
+        Self {
+            max_chunk_weight,
+            lit_buffer: RsHashMap::default(),
+            chunks: vec![],
+            top: PBE::default(),
+            weight_sum: 0,
+            n_vars: 0,
+            n_clauses: 0,
+        }
+    }
+}
+
+impl<PBE> Chunked<PBE>
+where
+    PBE: Encode + 'static,
+{
+    /// Gets the number of chunks that have been encoded so far
+    #[must_use]
+    pub fn n_chunks(&self) -> usize {
+        self.chunks.len()
+    }
+}
+
+impl<PBE> Chunked<PBE>
+where
+    PBE: BoundUpper + FromIterator<(Lit, usize)> + Extend<(Lit, usize)> + 'static,
+{
+    /// Partitions the buffered input literals into chunks, encodes them and
+    /// adds their outputs to the top-level encoding
+    fn encode_chunks<Col>(
+        &mut self,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let mut lits: Vec<(Lit, usize)> = self.lit_buffer.drain().collect();
+        // Sort for deterministic chunk boundaries
+        lits.sort_unstable();
+        let mut chunk = vec![];
+        let mut chunk_weight = 0;
+        for (lit, weight) in lits {
+            if weight >= self.max_chunk_weight {
+                self.top.extend([(lit, weight)]);
+                continue;
+            }
+            if chunk_weight + weight > self.max_chunk_weight {
+                self.encode_chunk(std::mem::take(&mut chunk), collector, var_manager)?;
+                chunk_weight = 0;
+            }
+            chunk.push((lit, weight));
+            chunk_weight += weight;
+        }
+        self.encode_chunk(chunk, collector, var_manager)
+    }
+
+    /// Encodes a single chunk and adds its outputs to the top-level encoding
+    fn encode_chunk<Col>(
+        &mut self,
+        chunk: Vec<(Lit, usize)>,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if chunk.len() <= 1 {
+            // No need for a sub-encoding
+            self.top.extend(chunk);
+            return Ok(());
+        }
+        let mut enc = PBE::from_iter(chunk);
+        enc.encode_ub(.., collector, var_manager)?;
+        let weight_sum = enc.weight_sum();
+        let mut terms: Vec<(Lit, usize)> = vec![];
+        let mut prev: Option<Vec<Lit>> = None;
+        let mut val = 0;
+        while val < weight_sum {
+            let next = enc.next_higher(val).min(weight_sum);
+            let mut assumps = enc.enforce_ub(val).expect("chunk should be fully encoded");
+            assumps.sort_unstable();
+            if prev.as_ref() == Some(&assumps) {
+                // Chunk sum can not take any value in `val..next`
+                terms.last_mut().unwrap().1 += next - val;
+            } else {
+                // `exceeded` is implied by the chunk sum being larger than `val`
+                let exceeded = var_manager.new_lit();
+                self.n_vars += 1;
+                collector.extend_clauses(
+                    assumps
+                        .iter()
+                        .map(|&assump| atomics::lit_impl_lit(!assump, exceeded)),
+                )?;
+                self.n_clauses += assumps.len();
+                terms.push((exceeded, next - val));
+                prev = Some(assumps);
+            }
+            val = next;
+        }
+        self.top.extend(terms);
+        self.chunks.push(enc);
+        Ok(())
+    }
+}
+
+impl<PBE> Extend<(Lit, usize)> for Chunked<PBE>
+where
+    PBE: Encode + 'static,
+{
+    fn extend<T: IntoIterator<Item = (Lit, usize)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|(l, w)| {
+            if w == 0 {
+                return;
+            }
+            self.weight_sum += w;
+            *self.lit_buffer.entry(l).or_insert(0) += w;
+        });
+    }
+}
+
+impl<PBE> Encode for Chunked<PBE>
+where
+    PBE: Encode + 'static,
+{
+    fn weight_sum(&self) -> usize {
+        self.weight_sum
+    }
+}
+
+impl<PBE> BoundUpper for Chunked<PBE>
+where
+    PBE: BoundUpper + FromIterator<(Lit, usize)> + Extend<(Lit, usize)> + 'static,
+{
+    fn encode_ub<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        if !self.lit_buffer.is_empty() {
+            self.encode_chunks(collector, var_manager)?;
+        }
+        self.top.encode_ub(range, collector, var_manager)
+    }
+
+    fn enforce_ub(&self, ub: usize) -> Result<Vec<Lit>, Error> {
+        if !self.lit_buffer.is_empty() {
+            return Err(Error::NotEncoded);
+        }
+        self.top.enforce_ub(ub)
+    }
+}
+
+impl<PBE> EncodeStats for Chunked<PBE>
+where
+    PBE: Encode + EncodeStats + 'static,
+{
+    fn n_clauses(&self) -> usize {
+        self.n_clauses
+            + self.top.n_clauses()
+            + self
+                .chunks
+                .iter()
+                .map(EncodeStats::n_clauses)
+                .sum::<usize>()
+    }
+
+    fn n_vars(&self) -> u32 {
+        self.n_vars + self.top.n_vars() + self.chunks.iter().map(EncodeStats::n_vars).sum::<u32>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Chunked;
+    use crate::{
+        encodings::{
+            pb::{BoundUpper, Encode, GeneralizedTotalizer},
+            EncodeStats,
+        },
+        instances::{BasicVarManager, Cnf, ManageVars},
+        lit,
+        types::Lit,
+        var,
+    };
+
+    #[test]
+    fn chunk_boundaries() {
+        let mut enc = Chunked::<GeneralizedTotalizer>::new(6);
+        // Weights 2, 3 | 4, 1 | 5 and 7 is added to the top-level directly
+        enc.extend([
+            (lit![0], 2),
+            (lit![1], 3),
+            (lit![2], 4),
+            (lit![3], 1),
+            (lit![4], 5),
+            (lit![5], 7),
+        ]);
+        assert_eq!(enc.weight_sum(), 22);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![6]);
+        let mut cnf = Cnf::new();
+        enc.encode_ub(0..=22, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(enc.n_chunks(), 2);
+        assert_eq!(cnf.len(), enc.n_clauses());
+        assert_eq!(var_manager.n_used() - 6, enc.n_vars());
+        let assumps: Vec<Lit> = enc.enforce_ub(6).unwrap();
+        assert!(assumps.contains(&!lit![5]));
+        assert!(enc.enforce_ub(22).unwrap().is_empty());
+    }
+
+    #[test]
+    fn not_encoded() {
+        let mut enc = Chunked::<GeneralizedTotalizer>::new(4);
+        enc.extend([(lit![0], 2), (lit![1], 3)]);
+        assert!(enc.enforce_ub(2).is_err());
+    }
+}
@@ -266,6 +266,10 @@ impl EncodeStats for DbGte {
     fn n_vars(&self) -> u32 {
         self.n_vars
     }
+
+    fn depth(&self) -> usize {
+        self.depth()
+    }
 }
 
 impl From<RsHashMap<Lit, usize>> for DbGte {
@@ -393,6 +393,10 @@ impl EncodeStats for DynamicPolyWatchdog {
     fn n_vars(&self) -> u32 {
         self.n_vars
     }
+
+    fn depth(&self) -> usize {
+        self.depth()
+    }
 }
 
 impl From<RsHashMap<Lit, usize>> for DynamicPolyWatchdog {
@@ -8,18 +8,28 @@
 //!
 //! - \[1\] Saurabh Joshi and Ruben Martins and Vasco Manquinho: _Generalized Totalizer Encoding for Pseudo-Boolean Constraints_, CP 2015.
 
-use super::{BoundUpper, BoundUpperIncremental, Encode, EncodeIncremental, Error};
+use super::{
+    BoundLower, BoundLowerIncremental, BoundUpper, BoundUpperIncremental, Encode,
+    EncodeIncremental, Error,
+};
 use crate::{
+    clause,
     encodings::{atomics, CollectClauses, EncodeStats, IterWeightedInputs},
-    instances::ManageVars,
-    types::{Lit, RsHashMap},
+    instances::{Cnf, ManageVars},
+    types::{Lit, RsHashMap, RsHashSet},
 };
 use std::{
     cmp,
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet, BinaryHeap},
+    io,
     ops::{Range, RangeBounds},
 };
 
+/// Number of leaves from which on subtrees are built on separate threads with
+/// the `parallel` feature
+#[cfg(feature = "parallel")]
+const PARALLEL_BUILD_THRESHOLD: usize = 1 << 12;
+
 /// Implementation of the binary adder tree generalized totalizer encoding
 /// \[1\]. The implementation is incremental. The implementation is recursive.
 /// This encoding only support upper bounding. Lower bounding can be achieved by
@@ -30,10 +40,13 @@ use std::{
 ///
 /// - \[1\] Saurabh Joshi and Ruben Martins and Vasco Manquinho: _Generalized
 ///   Totalizer Encoding for Pseudo-Boolean Constraints_, CP 2015.
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct GeneralizedTotalizer {
     /// Input literals and weights for the encoding
     in_lits: RsHashMap<Lit, usize>,
+    /// The number of input literals with each weight, kept up to date when
+    /// inputs are added
+    weight_counts: BTreeMap<usize, usize>,
     /// Input literals and weights not yet in the tree
     lit_buffer: RsHashMap<Lit, usize>,
     /// The root of the tree, if constructed
@@ -46,12 +59,193 @@ pub struct GeneralizedTotalizer {
     n_vars: u32,
     /// The number of clauses in the GTE
     n_clauses: usize,
+    /// Seed for breaking ties between equal weights when building the tree
+    seed: Option<u64>,
+    /// Whether to build a separate subtree for each group of literals with
+    /// equal weight
+    group_weights: bool,
+    /// Whether the output literals of the root are kept when the tree is
+    /// extended
+    stable_outputs: bool,
+    /// Whether the implications from the output literals to the input
+    /// literals are encoded for the current tree
+    ge_encoded: bool,
+    /// How input literals are partitioned into subtrees
+    strategy: TreeStrategy,
+    /// The last upper bound enforced with [`Self::tighten_ub`]
+    tightened_ub: Option<usize>,
+}
+
+/// Strategy for partitioning the input literals of a [`GeneralizedTotalizer`]
+/// into subtrees
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TreeStrategy {
+    /// Literals are sorted by weight and the tree is split in the middle,
+    /// yielding a tree of minimal depth
+    #[default]
+    Balanced,
+    /// The two subtrees with the lowest weight sum are repeatedly merged, like
+    /// in Huffman coding. For skewed weight distributions, this can reduce the
+    /// number of distinct intermediate sums and thereby the number of
+    /// clauses, at the cost of a deeper tree.
+    WeightHuffman,
 }
 
 impl GeneralizedTotalizer {
+    /// Sets a seed for breaking ties between input literals of equal weight
+    /// when building the tree. The same seed always produces the same
+    /// encoding, while different seeds can produce structurally different but
+    /// equisatisfiable encodings. Without a seed, ties are broken by the
+    /// iteration order of the input map. The seed only affects parts of the
+    /// tree that are built after setting it.
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Keeps the output literals of the root when the tree is extended with
+    /// input literals added after encoding. Without this, extending the tree
+    /// joins the old root and the new subtree at a new root with fresh output
+    /// literals. With this, the right child of the root is instead joined with
+    /// the new subtree and the root keeps its output literals, which are then
+    /// implied by the sum of _all_ input literals reaching the respective
+    /// value. Clauses referencing output literals,
+    /// e.g., assumptions from [`BoundUpper::enforce_ub`] that were added as
+    /// unit clauses, therefore stay valid and apply to the extended sum. Since
+    /// the extended sum might reach values that the old sum could not, the
+    /// bound is only fully enforced by the assumptions for the extended
+    /// encoding. This can not be combined with [`Self::encode_ge`].
+    ///
+    /// If the tree consists of a single input literal when it is extended,
+    /// the output of the root is the input literal itself and can not be
+    /// kept. The extended tree then gets a new root with fresh output
+    /// literals, as without this option.
+    #[must_use]
+    pub fn with_stable_outputs(mut self) -> Self {
+        self.stable_outputs = true;
+        self
+    }
+
+    /// Sets the strategy for partitioning input literals into subtrees. The
+    /// strategy only affects parts of the tree that are built after setting
+    /// it.
+    #[must_use]
+    pub fn with_tree_strategy(mut self, strategy: TreeStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Creates an encoding from terms with many repeated coefficients. Terms
+    /// with equal coefficients are grouped into their own subtree, in which
+    /// all output values are multiples of the coefficient, and the subtrees
+    /// are then merged. Repeated literals have their coefficients summed.
+    #[must_use]
+    pub fn from_compressed(terms: &[(Lit, usize)]) -> Self {
+        let mut gte = Self {
+            group_weights: true,
+            ..Default::default()
+        };
+        gte.extend(terms.iter().copied());
+        gte
+    }
+
+    /// Adds input literals like [`Extend::extend`], but checks that the sum of
+    /// the input weights does not overflow. Literals before the one causing the
+    /// overflow are still added.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::WeightOverflow`] if the sum of the input weights would
+    /// overflow `usize`.
+    pub fn try_extend<T: IntoIterator<Item = (Lit, usize)>>(
+        &mut self,
+        iter: T,
+    ) -> Result<(), Error> {
+        for (l, w) in iter {
+            // Since the weight of each literal is at most the weight sum, it
+            // can not overflow if the sum does not
+            self.weight_sum = self
+                .weight_sum
+                .checked_add(w)
+                .ok_or(Error::WeightOverflow)?;
+            self.add_input(l, w);
+        }
+        Ok(())
+    }
+
+    /// Adds a single input literal to the buffer and the input map, without
+    /// updating the weight sum
+    fn add_input(&mut self, l: Lit, w: usize) {
+        // Insert into buffer to be added to tree
+        match self.lit_buffer.get_mut(&l) {
+            Some(old_w) => *old_w += w,
+            None => {
+                self.lit_buffer.insert(l, w);
+            }
+        };
+        // Insert into map of input literals
+        match self.in_lits.get_mut(&l) {
+            Some(old_w) => {
+                let count = self.weight_counts.get_mut(old_w).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    self.weight_counts.remove(old_w);
+                }
+                *old_w += w;
+                *self.weight_counts.entry(*old_w).or_insert(0) += 1;
+            }
+            None => {
+                self.in_lits.insert(l, w);
+                *self.weight_counts.entry(w).or_insert(0) += 1;
+            }
+        };
+    }
+
+    /// Builds a tree with a separate subtree for each group of literals with
+    /// equal weight. `lits` must be sorted by weight.
+    fn build_grouped_tree(lits: &[(Lit, usize)]) -> Node {
+        debug_assert_ne!(lits.len(), 0);
+        let mut groups: Vec<Node> = vec![];
+        let mut start = 0;
+        for idx in 1..=lits.len() {
+            if idx == lits.len() || lits[idx].1 != lits[start].1 {
+                groups.push(GeneralizedTotalizer::build_tree(&lits[start..idx]));
+                start = idx;
+            }
+        }
+        // Merge subtrees in a balanced fashion
+        while groups.len() > 1 {
+            let mut merged = Vec::with_capacity((groups.len() + 1) / 2);
+            let mut iter = groups.into_iter();
+            while let Some(left) = iter.next() {
+                merged.push(match iter.next() {
+                    Some(right) => Node::new_internal(left, right),
+                    None => left,
+                });
+            }
+            groups = merged;
+        }
+        groups.pop().unwrap()
+    }
+
     /// Recursively builds the tree data structure. Uses weights out of
-    /// `lit_buffer` to initialize leafs.
+    /// `lit_buffer` to initialize leafs. With the `parallel` feature, large
+    /// trees are built by [`GeneralizedTotalizer::build_tree_parallel`] on at
+    /// most as many threads as the available parallelism.
     fn build_tree(lits: &[(Lit, usize)]) -> Node {
+        #[cfg(feature = "parallel")]
+        return GeneralizedTotalizer::build_tree_parallel(
+            lits,
+            PARALLEL_BUILD_THRESHOLD,
+            std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get),
+        );
+        #[cfg(not(feature = "parallel"))]
+        GeneralizedTotalizer::build_tree_sequential(lits)
+    }
+
+    /// Recursively builds the tree data structure on the current thread
+    fn build_tree_sequential(lits: &[(Lit, usize)]) -> Node {
         debug_assert_ne!(lits.len(), 0);
 
         if lits.len() == 1 {
@@ -59,12 +253,67 @@ impl GeneralizedTotalizer {
         };
 
         let split = lits.len() / 2;
-        let left = GeneralizedTotalizer::build_tree(&lits[..split]);
-        let right = GeneralizedTotalizer::build_tree(&lits[split..]);
+        let left = GeneralizedTotalizer::build_tree_sequential(&lits[..split]);
+        let right = GeneralizedTotalizer::build_tree_sequential(&lits[split..]);
 
         Node::new_internal(left, right)
     }
 
+    /// Recursively builds the tree data structure, building the right subtree
+    /// on a separate thread for subtrees with at least `threshold` leaves.
+    /// Uses at most `threads` threads in total, including the current one.
+    /// The split points are the same as in the sequential build, so the
+    /// resulting tree is identical.
+    #[cfg(feature = "parallel")]
+    fn build_tree_parallel(lits: &[(Lit, usize)], threshold: usize, threads: usize) -> Node {
+        if threads < 2 || lits.len() < threshold.max(2) {
+            return GeneralizedTotalizer::build_tree_sequential(lits);
+        }
+
+        let split = lits.len() / 2;
+        // The spawned thread gets half of the thread budget, the current
+        // thread keeps the rest
+        let right_threads = threads / 2;
+        let left_threads = threads - right_threads;
+        let (left, right) = std::thread::scope(|scope| {
+            let right = scope.spawn(|| {
+                GeneralizedTotalizer::build_tree_parallel(&lits[split..], threshold, right_threads)
+            });
+            let left =
+                GeneralizedTotalizer::build_tree_parallel(&lits[..split], threshold, left_threads);
+            (
+                left,
+                right.join().expect("thread building subtree panicked"),
+            )
+        });
+
+        Node::new_internal(left, right)
+    }
+
+    /// Builds a tree by repeatedly merging the two subtrees with the lowest
+    /// weight sum. Ties are broken by the order of `lits`.
+    fn build_huffman_tree(lits: &[(Lit, usize)]) -> Node {
+        debug_assert_ne!(lits.len(), 0);
+        let mut nodes: Vec<Option<Node>> = lits
+            .iter()
+            .map(|&(l, w)| Some(Node::new_leaf(l, w)))
+            .collect();
+        let mut queue: BinaryHeap<cmp::Reverse<(usize, usize)>> = nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| cmp::Reverse((node.as_ref().unwrap().max_val(), idx)))
+            .collect();
+        while queue.len() > 1 {
+            let cmp::Reverse((_, left)) = queue.pop().unwrap();
+            let cmp::Reverse((_, right)) = queue.pop().unwrap();
+            let node =
+                Node::new_internal(nodes[left].take().unwrap(), nodes[right].take().unwrap());
+            queue.push(cmp::Reverse((node.max_val(), nodes.len())));
+            nodes.push(Some(node));
+        }
+        nodes.pop().unwrap().unwrap()
+    }
+
     /// Extends the tree at the root node with added literals of maximum weight `max_weight`
     fn extend_tree(&mut self, max_weight: usize) {
         if !self.lit_buffer.is_empty() {
@@ -85,12 +334,31 @@ impl GeneralizedTotalizer {
                 .collect();
             if !new_lits.is_empty() {
                 // Add nodes in sorted fashion to minimize clauses
-                new_lits.sort_by_key(|(_, w)| *w);
-                let subtree = GeneralizedTotalizer::build_tree(&new_lits[..]);
+                match self.seed {
+                    Some(seed) => {
+                        new_lits.sort_unstable_by_key(|&(l, w)| (w, tie_breaker(seed, l)));
+                    }
+                    None => new_lits.sort_by_key(|(_, w)| *w),
+                }
+                let subtree = if self.group_weights {
+                    GeneralizedTotalizer::build_grouped_tree(&new_lits[..])
+                } else {
+                    match self.strategy {
+                        TreeStrategy::Balanced => GeneralizedTotalizer::build_tree(&new_lits[..]),
+                        TreeStrategy::WeightHuffman => {
+                            GeneralizedTotalizer::build_huffman_tree(&new_lits[..])
+                        }
+                    }
+                };
+                self.ge_encoded = false;
                 self.root = match self.root.take() {
                     None => Some(subtree),
                     Some(old_root) => {
-                        let new_root = Node::new_internal(old_root, subtree);
+                        let new_root = if self.stable_outputs {
+                            old_root.extend_keep_outputs(subtree)
+                        } else {
+                            Node::new_internal(old_root, subtree)
+                        };
                         Some(new_root)
                     }
                 };
@@ -99,11 +367,456 @@ impl GeneralizedTotalizer {
         }
     }
 
+    /// Gets the weight of all input literals, if all weights are equal
+    fn uniform_weight(&self) -> Option<usize> {
+        if self.weight_counts.len() != 1 {
+            return None;
+        }
+        let (&weight, _) = self.weight_counts.iter().next()?;
+        if weight > 0 {
+            Some(weight)
+        } else {
+            None
+        }
+    }
+
+    /// Gets the largest output value that needs to be encoded and assumed in
+    /// order to enforce a given upper bound. This guarantees that the next
+    /// achievable value above the bound is included. With uniform weights,
+    /// all achievable values are multiples of the weight and the next one is
+    /// known exactly.
+    fn max_required_val(&self, ub: usize) -> usize {
+        match self.uniform_weight() {
+            Some(weight) => (ub / weight + 1) * weight,
+            None => ub + self.max_leaf_weight,
+        }
+    }
+
+    /// Gets the number of input literals that are not yet in the tree. These
+    /// are added to the tree by the next encode call with a bound of at least
+    /// their weight.
+    #[must_use]
+    pub fn n_buffered(&self) -> usize {
+        self.lit_buffer.len()
+    }
+
+    /// Gets the number of input literals that are already in the tree. A
+    /// literal whose weight was increased after adding it to the tree counts
+    /// towards both this and [`GeneralizedTotalizer::n_buffered`].
+    #[must_use]
+    pub fn n_in_tree(&self) -> usize {
+        self.in_lits
+            .iter()
+            .filter(|(l, w)| self.lit_buffer.get(l) != Some(w))
+            .count()
+    }
+
+    /// Gets the assumptions that are newly required when tightening the upper
+    /// bound from `prev_ub` to `ub`, i.e., the assumptions from
+    /// [`BoundUpper::enforce_ub`] for `ub` that are not already part of the
+    /// assumptions for `prev_ub`. This is only valid for monotonic tightening,
+    /// i.e., `ub <= prev_ub` with the assumptions for `prev_ub` still being
+    /// assumed, since looser bounds would require dropping assumptions.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`BoundUpper::enforce_ub`] for `ub`.
+    pub fn enforce_ub_delta(&self, prev_ub: usize, ub: usize) -> Result<Vec<Lit>, Error> {
+        debug_assert!(ub <= prev_ub);
+        // Assumptions on inputs with weight and outputs with value above
+        // `prev_ub` are already part of the assumptions for `prev_ub`
+        self.enforce_ub_upto(ub, prev_ub)
+    }
+
+    /// Gets the assumptions from [`BoundUpper::enforce_ub`] for `ub`, but only
+    /// those on input literals with weight at most `max` and on output
+    /// literals with value at most `max`
+    fn enforce_ub_upto(&self, ub: usize, max: usize) -> Result<Vec<Lit>, Error> {
+        if self.in_lits.is_empty() {
+            return Err(Error::EmptyEncoding);
+        }
+        if ub >= self.weight_sum {
+            return Ok(vec![]);
+        }
+
+        // Assume literals that have higher weight than `ub`
+        let mut assumps = Vec::with_capacity(self.lit_buffer.len());
+        self.lit_buffer.iter().try_for_each(|(_, &w)| {
+            if w <= ub {
+                Err(Error::NotEncoded)
+            } else {
+                Ok(())
+            }
+        })?;
+        self.in_lits.iter().for_each(|(&l, &w)| {
+            if w > ub && w <= max {
+                assumps.push(!l);
+            }
+        });
+        // Enforce bound on internal tree
+        assumps.extend(match &self.root {
+            None => {
+                vec![]
+            }
+            Some(root_node) => match &root_node {
+                // Assumes that literal is already enforced from wrapper function if it's weight is more than `ub`
+                Node::Leaf { .. } => vec![],
+                Node::Internal {
+                    out_lits,
+                    enc_range,
+                    max_val,
+                    ..
+                } => {
+                    if ub >= *max_val {
+                        vec![]
+                    } else if enc_range.contains(&(ub + 1))
+                        && enc_range.contains(&cmp::min(*max_val, self.max_required_val(ub)))
+                    {
+                        out_lits
+                            .range(ub + 1..cmp::min(self.max_required_val(ub), max) + 1)
+                            .map(|(_, &l)| !l)
+                            .collect()
+                    } else {
+                        return Err(Error::NotEncoded);
+                    }
+                }
+            },
+        });
+        Ok(assumps)
+    }
+
+    /// Tightens the upper bound to `new_ub` for a solution improving search.
+    /// Incrementally encodes what is needed for enforcing `new_ub` in addition
+    /// to what is already encoded and returns the new clauses together with
+    /// the assumptions enforcing the bound. For loosening the bound, use
+    /// [`BoundUpperIncremental::encode_ub_change`] and
+    /// [`BoundUpper::enforce_ub`] instead.
+    ///
+    /// # Errors
+    ///
+    /// If the clauses can not be collected, returns [`Error::OutOfMemory`],
+    /// otherwise the same as [`BoundUpper::enforce_ub`].
+    ///
+    /// # Panics
+    ///
+    /// If `new_ub` is not below the bound of the previous call.
+    pub fn tighten_ub(
+        &mut self,
+        new_ub: usize,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(Cnf, Vec<Lit>), Error> {
+        if let Some(last_ub) = self.tightened_ub {
+            assert!(
+                new_ub < last_ub,
+                "upper bound {new_ub} does not tighten the previous bound {last_ub}"
+            );
+        }
+        let mut cnf = Cnf::new();
+        self.encode_ub_change(new_ub..=new_ub, &mut cnf, var_manager)?;
+        let assumps = self.enforce_ub(new_ub)?;
+        self.tightened_ub = Some(new_ub);
+        Ok((cnf, assumps))
+    }
+
+    /// Gets the assumptions from [`BoundUpper::enforce_ub`] for all bounds in
+    /// `ub..=ub + window`. Since enforcing `ub` implies all looser bounds, the
+    /// additional assumptions do not change the enforced bound but can help
+    /// the solver propagate output literals close to the bound. The window is
+    /// clamped to the encoded output literals.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`BoundUpper::enforce_ub`] for `ub`.
+    pub fn enforce_ub_window(&self, ub: usize, window: usize) -> Result<Vec<Lit>, Error> {
+        let mut assumps = self.enforce_ub(ub)?;
+        if ub >= self.weight_sum {
+            return Ok(assumps);
+        }
+        if let Some(Node::Internal {
+            out_lits,
+            enc_range,
+            ..
+        }) = &self.root
+        {
+            // Outputs above the ones enforcing `ub` up to the ones enforcing
+            // the last bound in the window
+            let last_ub = cmp::min(ub.saturating_add(window), self.weight_sum - 1);
+            let start = self.max_required_val(ub) + 1;
+            let end = cmp::min(self.max_required_val(last_ub) + 1, enc_range.end);
+            if start < end {
+                assumps.extend(out_lits.range(start..end).map(|(_, &l)| !l));
+            }
+        }
+        Ok(assumps)
+    }
+
+    /// Gets a minimal subset of the assumptions from [`BoundUpper::enforce_ub`]
+    /// that still enforces the bound. An input literal in the tree with a
+    /// weight larger than `ub` propagates the output literal of the root for
+    /// its weight, which is part of the assumptions. The assumption
+    /// negating the input literal is therefore redundant and dropped. Only
+    /// input literals that are not in the tree with their full weight are
+    /// assumed directly.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`BoundUpper::enforce_ub`].
+    pub fn enforce_ub_minimal(&self, ub: usize) -> Result<Vec<Lit>, Error> {
+        let mut assumps = self.enforce_ub(ub)?;
+        let Some(Node::Internal { out_lits, .. }) = &self.root else {
+            return Ok(assumps);
+        };
+        let assump_set: RsHashSet<Lit> = assumps.iter().copied().collect();
+        let redundant: RsHashSet<Lit> = assumps
+            .iter()
+            .filter(|&&assump| {
+                let lit = !assump;
+                match self.in_lits.get(&lit) {
+                    Some(weight) if !self.lit_buffer.contains_key(&lit) => out_lits
+                        .get(weight)
+                        .map_or(false, |&out| assump_set.contains(&!out)),
+                    _ => false,
+                }
+            })
+            .copied()
+            .collect();
+        assumps.retain(|assump| !redundant.contains(assump));
+        Ok(assumps)
+    }
+
+    /// Fully encodes the tree and additionally encodes the implications from
+    /// the output literals to the input literals, which are not needed for
+    /// upper bounding. With these, an output literal being true implies that
+    /// the weighted sum of the input literals is at least the value of the
+    /// output, which allows for enforcing lower bounds with
+    /// [`Self::enforce_ge`] on the same tree that is used for
+    /// [`BoundUpper::enforce_ub`].
+    ///
+    /// The tree is only fully encoded for the input literals added so far.
+    /// After extending the encoding, this needs to be called again, which
+    /// only encodes the clauses for the parts of the tree that were not
+    /// encoded before.
+    ///
+    /// # Errors
+    ///
+    /// If the clauses can not be collected, returns [`crate::OutOfMemory`].
+    ///
+    /// # Panics
+    ///
+    /// If the encoding was built with [`Self::with_stable_outputs`]. After
+    /// extending such an encoding, the implications from the kept output
+    /// literals of the root to the old sum would block the new input
+    /// literals.
+    pub fn encode_ge<Col>(
+        &mut self,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        assert!(
+            !self.stable_outputs,
+            "lower bounding is not supported with stable outputs"
+        );
+        let n_vars_before = var_manager.n_used();
+        let n_clauses_before = collector.n_clauses();
+        self.extend_tree(self.weight_sum);
+        if let Some(root) = &mut self.root {
+            let max_val = root.max_val();
+            root.rec_encode_change(1..max_val + 1, collector, var_manager)?;
+            root.rec_encode_ge(collector)?;
+        }
+        self.ge_encoded = true;
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.n_vars += var_manager.n_used() - n_vars_before;
+        Ok(())
+    }
+
+    /// Gets assumptions for enforcing the lower bound `weighted sum of input
+    /// literals >= lb`. This is the counterpart of [`BoundUpper::enforce_ub`]
+    /// on the same tree: while the upper bound is enforced by assuming the
+    /// output literals above the bound to be false, the lower bound is
+    /// enforced by assuming the output literal of the smallest reachable
+    /// value of at least `lb` to be true. This requires the implications
+    /// encoded by [`Self::encode_ge`].
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::EmptyEncoding`] if no input literals were added
+    /// - [`Error::Unsat`] if `lb` is larger than the sum of all input weights
+    /// - [`Error::NotEncoded`] if [`Self::encode_ge`] was not called after
+    ///   the last extension of the encoding
+    pub fn enforce_ge(&self, lb: usize) -> Result<Vec<Lit>, Error> {
+        if self.in_lits.is_empty() {
+            return Err(Error::EmptyEncoding);
+        }
+        if lb == 0 {
+            return Ok(vec![]);
+        }
+        if lb > self.weight_sum {
+            return Err(Error::Unsat);
+        }
+        if !self.ge_encoded || !self.lit_buffer.is_empty() {
+            return Err(Error::NotEncoded);
+        }
+        match &self.root {
+            None => Err(Error::NotEncoded),
+            Some(Node::Leaf { lit, .. }) => Ok(vec![*lit]),
+            Some(Node::Internal { out_lits, .. }) => match out_lits.range(lb..).next() {
+                Some((_, &lit)) => Ok(vec![lit]),
+                None => Err(Error::NotEncoded),
+            },
+        }
+    }
+
+    /// Reserves the variables needed for encoding upper bounds of at most
+    /// `max_bound`, so that they have consecutive indices, without reserving
+    /// variables for the entire tree as [`EncodeIncremental::reserve`] does.
+    /// Input literals are added to the tree as they would be by
+    /// [`BoundUpper::encode_ub`] for `max_bound`.
+    ///
+    /// Encoding bounds up to `max_bound` afterwards uses only the reserved
+    /// variables. Encoding larger bounds, e.g., with
+    /// [`BoundUpperIncremental::encode_ub_change`], reserves the missing
+    /// variables at that point, which are therefore not consecutive with the
+    /// ones reserved here.
+    pub fn reserve_up_to(&mut self, max_bound: usize, var_manager: &mut dyn ManageVars) {
+        self.extend_tree(max_bound);
+        let max_val = self.max_required_val(max_bound);
+        if let Some(root) = &mut self.root {
+            let n_vars_before = var_manager.n_used();
+            root.reserve_vars_rec(max_val + 2, var_manager);
+            self.n_vars += var_manager.n_used() - n_vars_before;
+        }
+    }
+
     /// Gets the maximum depth of the tree
-    pub fn depth(&mut self) -> usize {
+    pub fn depth(&self) -> usize {
         self.root.as_ref().map_or(0, |node| node.depth())
     }
 
+    /// Gets a [Graphviz](https://graphviz.org/) DOT description of the tree
+    /// for visualizing the structure of the encoding. Leaves are labeled with
+    /// their literal and weight, internal nodes with their maximum value,
+    /// encoded range and number of output literals. Input literals that are
+    /// not yet in the tree are not included.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph gte {\n");
+        if let Some(root) = &self.root {
+            root.write_dot(&mut dot, &mut 0);
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Writes the DOT description of the tree from [`Self::to_dot`] to a
+    /// writer
+    ///
+    /// # Errors
+    ///
+    /// If writing fails.
+    pub fn write_dot<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(self.to_dot().as_bytes())
+    }
+
+    /// Checks that every output literal in the tree belongs to a sum that is
+    /// reachable by a subset of the leaves of its node. Output literals for
+    /// unreachable sums can never be implied and are therefore wasted
+    /// variables. This is a debugging aid and takes time pseudo-polynomial in
+    /// the weights.
+    ///
+    /// # Panics
+    ///
+    /// If an output literal for an unreachable sum exists.
+    pub fn assert_no_unreachable_outputs(&self) {
+        if let Some(root) = &self.root {
+            root.check_reachable_outputs();
+        }
+    }
+
+    /// Gets the range of upper bounds that are currently encoded, i.e., for
+    /// which [`BoundUpper::enforce_ub`] will succeed. Upper bounds of at least
+    /// [`Encode::weight_sum`] are trivially enforcable and might not be
+    /// included. Returns [`None`] if no bound is encoded.
+    pub fn encoded_range(&self) -> Option<Range<usize>> {
+        let range = match self.root.as_ref()? {
+            Node::Leaf { .. } => 0..self.weight_sum,
+            Node::Internal {
+                enc_range, max_val, ..
+            } => {
+                if enc_range.is_empty() {
+                    return None;
+                }
+                let end = if enc_range.end > *max_val {
+                    self.weight_sum
+                } else if let Some(weight) = self.uniform_weight() {
+                    (enc_range.end - 1) / weight * weight
+                } else {
+                    enc_range.end.saturating_sub(self.max_leaf_weight)
+                };
+                enc_range.start.saturating_sub(1)..end
+            }
+        };
+        // Literals that are not yet in the tree limit the enforcable bounds
+        let range = match self.lit_buffer.values().min() {
+            Some(&min_weight) => range.start..cmp::min(range.end, min_weight),
+            None => range,
+        };
+        if range.is_empty() {
+            return None;
+        }
+        Some(range)
+    }
+
+    /// Builds the encoding for upper bounds within a range like
+    /// [`BoundUpper::encode_ub`], but without the bookkeeping needed for
+    /// incrementally extending the encoding later on. Only the encoded range
+    /// of the root is recorded, so that [`BoundUpper::enforce_ub`] can be used
+    /// afterwards.
+    ///
+    /// The statistics of the individual nodes are not updated and calling any
+    /// of the incremental methods
+    /// ([`BoundUpperIncremental::encode_ub_change`]) on the encoding
+    /// afterwards is invalid, since it would re-encode parts of the encoding
+    /// that already exist. The totals reported by [`EncodeStats`] are
+    /// updated. Since no larger bounds are encoded later on, the low values of
+    /// subtrees are cut off more aggressively than by
+    /// [`BoundUpper::encode_ub`], which can result in fewer clauses.
+    ///
+    /// # Errors
+    ///
+    /// If the collector runs out of memory, returns [`crate::OutOfMemory`].
+    pub fn encode_ub_oneshot<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_ub_range(self, range);
+        if range.is_empty() {
+            return Ok(());
+        };
+        let n_vars_before = var_manager.n_used();
+        let n_clauses_before = collector.n_clauses();
+        self.extend_tree(range.end - 1);
+        let max_val = self.max_required_val(range.end - 1);
+        if let Some(root) = &mut self.root {
+            let range = range.start + 1..max_val + 2;
+            let bound_end = range.end - self.max_leaf_weight;
+            root.rec_encode_oneshot(range.clone(), bound_end, collector, var_manager)?;
+            root.update_stats(root.limit_range(range), 0);
+        };
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.n_vars += var_manager.n_used() - n_vars_before;
+        Ok(())
+    }
+
     /// Fully builds the tree, then returns it
     #[cfg(feature = "internals")]
     pub fn tree(mut self) -> Option<Node> {
@@ -112,6 +825,15 @@ impl GeneralizedTotalizer {
     }
 }
 
+/// Seed-dependent key for breaking ties between literals. Mixes the seed and
+/// the literal index with the SplitMix64 finalizer.
+fn tie_breaker(seed: u64, lit: Lit) -> u64 {
+    let mut z = seed ^ (lit.lidx() as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
 impl Encode for GeneralizedTotalizer {
     fn weight_sum(&self) -> usize {
         self.weight_sum
@@ -143,6 +865,10 @@ impl IterWeightedInputs for GeneralizedTotalizer {
     fn iter(&self) -> Self::Iter<'_> {
         self.in_lits.iter().map(copy_key_val)
     }
+
+    fn n_lits(&self) -> usize {
+        self.in_lits.len()
+    }
 }
 
 impl EncodeIncremental for GeneralizedTotalizer {
@@ -171,68 +897,23 @@ impl BoundUpper for GeneralizedTotalizer {
         let n_vars_before = var_manager.n_used();
         let n_clauses_before = collector.n_clauses();
         self.extend_tree(range.end - 1);
+        let max_val = self.max_required_val(range.end - 1);
         match &mut self.root {
             None => (),
-            Some(root) => root.rec_encode(
-                range.start + 1..range.end + self.max_leaf_weight + 1,
-                collector,
-                var_manager,
-            )?,
+            Some(root) => {
+                root.rec_encode(range.start + 1..max_val + 2, collector, var_manager, true)?;
+            }
         };
         self.n_clauses += collector.n_clauses() - n_clauses_before;
         self.n_vars += var_manager.n_used() - n_vars_before;
         Ok(())
     }
 
+    /// Returns assumptions for enforcing an upper bound. If no input literals
+    /// have been added to the encoding, returns [`Error::EmptyEncoding`] rather
+    /// than treating the empty sum as trivially satisfied.
     fn enforce_ub(&self, ub: usize) -> Result<Vec<Lit>, Error> {
-        if ub >= self.weight_sum {
-            return Ok(vec![]);
-        }
-
-        // Assume literals that have higher weight than `ub`
-        let mut assumps = Vec::with_capacity(self.lit_buffer.len());
-        self.lit_buffer.iter().try_for_each(|(_, &w)| {
-            if w <= ub {
-                Err(Error::NotEncoded)
-            } else {
-                Ok(())
-            }
-        })?;
-        self.in_lits.iter().for_each(|(&l, &w)| {
-            if w > ub {
-                assumps.push(!l);
-            }
-        });
-        // Enforce bound on internal tree
-        assumps.extend(match &self.root {
-            None => {
-                vec![]
-            }
-            Some(root_node) => match &root_node {
-                // Assumes that literal is already enforced from wrapper function if it's weight is more than `ub`
-                Node::Leaf { .. } => vec![],
-                Node::Internal {
-                    out_lits,
-                    enc_range,
-                    max_val,
-                    ..
-                } => {
-                    if ub >= *max_val {
-                        vec![]
-                    } else if enc_range.contains(&(ub + 1))
-                        && enc_range.contains(&cmp::min(*max_val, ub + self.max_leaf_weight))
-                    {
-                        out_lits
-                            .range(ub + 1..=ub + self.max_leaf_weight)
-                            .map(|(_, &l)| !l)
-                            .collect()
-                    } else {
-                        return Err(Error::NotEncoded);
-                    }
-                }
-            },
-        });
-        Ok(assumps)
+        self.enforce_ub_upto(ub, usize::MAX)
     }
 }
 
@@ -254,12 +935,9 @@ impl BoundUpperIncremental for GeneralizedTotalizer {
         let n_vars_before = var_manager.n_used();
         let n_clauses_before = collector.n_clauses();
         self.extend_tree(range.end - 1);
+        let max_val = self.max_required_val(range.end - 1);
         if let Some(root) = self.root.as_mut() {
-            root.rec_encode_change(
-                range.start + 1..range.end + self.max_leaf_weight,
-                collector,
-                var_manager,
-            )?;
+            root.rec_encode_change(range.start + 1..max_val + 1, collector, var_manager)?;
         }
         self.n_clauses += collector.n_clauses() - n_clauses_before;
         self.n_vars += var_manager.n_used() - n_vars_before;
@@ -267,6 +945,52 @@ impl BoundUpperIncremental for GeneralizedTotalizer {
     }
 }
 
+impl BoundLower for GeneralizedTotalizer {
+    /// Fully encodes the tree with [`GeneralizedTotalizer::encode_ge`], so
+    /// that lower bounds are enforced on the same output literals as upper
+    /// bounds. The range is only checked for being empty.
+    ///
+    /// # Panics
+    ///
+    /// If the encoding was built with
+    /// [`GeneralizedTotalizer::with_stable_outputs`].
+    fn encode_lb<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        if super::prepare_lb_range(self, range).is_empty() {
+            return Ok(());
+        }
+        self.encode_ge(collector, var_manager)
+    }
+
+    fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error> {
+        self.enforce_ge(lb)
+    }
+}
+
+impl BoundLowerIncremental for GeneralizedTotalizer {
+    fn encode_lb_change<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        // Only the parts of the tree not encoded before are encoded
+        self.encode_lb(range, collector, var_manager)
+    }
+}
+
 impl EncodeStats for GeneralizedTotalizer {
     fn n_clauses(&self) -> usize {
         self.n_clauses
@@ -275,6 +999,10 @@ impl EncodeStats for GeneralizedTotalizer {
     fn n_vars(&self) -> u32 {
         self.n_vars
     }
+
+    fn depth(&self) -> usize {
+        self.depth()
+    }
 }
 
 pub(super) fn copy_key_val(key_val_refs: (&Lit, &usize)) -> (Lit, usize) {
@@ -288,7 +1016,12 @@ pub(super) type GteIter<'a> = std::iter::Map<
 impl From<RsHashMap<Lit, usize>> for GeneralizedTotalizer {
     fn from(lits: RsHashMap<Lit, usize>) -> Self {
         let weight_sum = lits.iter().fold(0, |sum, (_, w)| sum + *w);
+        let mut weight_counts = BTreeMap::new();
+        for &w in lits.values() {
+            *weight_counts.entry(w).or_insert(0) += 1;
+        }
         Self {
+            weight_counts,
             in_lits: lits.clone(),
             lit_buffer: lits,
             weight_sum,
@@ -307,21 +1040,12 @@ impl FromIterator<(Lit, usize)> for GeneralizedTotalizer {
 impl Extend<(Lit, usize)> for GeneralizedTotalizer {
     fn extend<T: IntoIterator<Item = (Lit, usize)>>(&mut self, iter: T) {
         iter.into_iter().for_each(|(l, w)| {
+            debug_assert!(
+                self.weight_sum.checked_add(w).is_some(),
+                "sum of input weights overflows, use `try_extend` to check"
+            );
             self.weight_sum += w;
-            // Insert into buffer to be added to tree
-            match self.lit_buffer.get_mut(&l) {
-                Some(old_w) => *old_w += w,
-                None => {
-                    self.lit_buffer.insert(l, w);
-                }
-            };
-            // Insert into map of input literals
-            match self.in_lits.get_mut(&l) {
-                Some(old_w) => *old_w += w,
-                None => {
-                    self.in_lits.insert(l, w);
-                }
-            };
+            self.add_input(l, w);
         });
     }
 }
@@ -338,6 +1062,7 @@ impl Extend<(Lit, usize)> for GeneralizedTotalizer {
 /// literals happens in the [`GeneralizedTotalizer`] or
 /// [`super::InvertedGeneralizedTotalizer`] structs.
 #[cfg_attr(feature = "internals", visibility::make(pub))]
+#[derive(Clone)]
 enum Node {
     /// A weighted input literal, i.e., a leaf node of the tree
     Leaf {
@@ -358,6 +1083,9 @@ enum Node {
         max_val: usize,
         /// The encoded range of this node
         enc_range: Range<usize>,
+        /// Whether the implications from the output literals to the children
+        /// are encoded, see [`GeneralizedTotalizer::encode_ge`]
+        ge_encoded: bool,
         /// The left child
         left: Box<Node>,
         /// The right child
@@ -371,16 +1099,167 @@ impl Node {
         Node::Leaf { lit, weight }
     }
 
-    /// Constructs a new internal node
-    pub fn new_internal(left: Node, right: Node) -> Node {
-        Node::Internal {
-            out_lits: BTreeMap::new(),
-            depth: cmp::max(left.depth() + 1, right.depth() + 1),
-            n_clauses: 0,
-            enc_range: 0..0,
-            max_val: left.max_val() + right.max_val(),
-            left: Box::new(left),
-            right: Box::new(right),
+    /// Constructs a new internal node
+    pub fn new_internal(left: Node, right: Node) -> Node {
+        Node::Internal {
+            out_lits: BTreeMap::new(),
+            depth: cmp::max(left.depth() + 1, right.depth() + 1),
+            n_clauses: 0,
+            enc_range: 0..0,
+            ge_encoded: false,
+            max_val: left.max_val() + right.max_val(),
+            left: Box::new(left),
+            right: Box::new(right),
+        }
+    }
+
+    /// Extends the subtree rooted in this node with another subtree while
+    /// keeping the output literals of this node. The other subtree is merged
+    /// with the right child, so that the output literals of this node are
+    /// never used as inputs to themselves. The clauses already encoded for
+    /// this node stay valid, but the node needs to be encoded again. A leaf
+    /// has no output literals of its own, so it is joined with the other
+    /// subtree at a new node.
+    fn extend_keep_outputs(self, subtree: Node) -> Node {
+        match self {
+            Node::Leaf { .. } => Node::new_internal(self, subtree),
+            Node::Internal {
+                out_lits,
+                left,
+                right,
+                ..
+            } => {
+                let mut node = Node::new_internal(*left, Node::new_internal(*right, subtree));
+                if let Node::Internal {
+                    out_lits: node_lits,
+                    ..
+                } = &mut node
+                {
+                    *node_lits = out_lits;
+                }
+                node
+            }
+        }
+    }
+
+    /// Appends the DOT node and edge declarations for the subtree rooted in
+    /// this node and returns the identifier of this node
+    fn write_dot(&self, dot: &mut String, next_id: &mut usize) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+        match self {
+            Node::Leaf { lit, weight } => {
+                dot.push_str(&format!(
+                    "  n{} [shape=box, label=\"{} : {}\"];\n",
+                    id, lit, weight
+                ));
+            }
+            Node::Internal {
+                out_lits,
+                max_val,
+                enc_range,
+                left,
+                right,
+                ..
+            } => {
+                dot.push_str(&format!(
+                    "  n{} [label=\"max {}\\nenc {}..{}\\nouts {}\"];\n",
+                    id,
+                    max_val,
+                    enc_range.start,
+                    enc_range.end,
+                    out_lits.len()
+                ));
+                for child in [left, right] {
+                    let child_id = child.write_dot(dot, next_id);
+                    dot.push_str(&format!("  n{} -> n{};\n", id, child_id));
+                }
+            }
+        }
+        id
+    }
+
+    /// Encodes the implications from the output literals of all nodes in the
+    /// subtree to their children, such that an output literal implies that
+    /// the sum of the node is at least its value. For every output value `k`
+    /// and every pair of child values `a + b < k`, the output implies that
+    /// one of the children exceeds its value. Requires the subtree to be
+    /// fully encoded. Subtrees for which the implications are already encoded
+    /// are skipped, since the output literals of a fully encoded node do not
+    /// change.
+    fn rec_encode_ge<Col>(&mut self, collector: &mut Col) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        if let Node::Internal {
+            out_lits,
+            left,
+            right,
+            ge_encoded,
+            ..
+        } = self
+        {
+            if *ge_encoded {
+                return Ok(());
+            }
+            left.rec_encode_ge(collector)?;
+            right.rec_encode_ge(collector)?;
+            let mut left_tmp_map = BTreeMap::new();
+            let mut right_tmp_map = BTreeMap::new();
+            let left_lits = left.lit_map(&mut left_tmp_map);
+            let right_lits = right.lit_map(&mut right_tmp_map);
+            let left_vals: Vec<usize> = std::iter::once(0)
+                .chain(left_lits.keys().copied())
+                .collect();
+            for (&val, &out_lit) in out_lits.iter() {
+                for &left_val in left_vals.iter().take_while(|&&lv| lv < val) {
+                    // The largest right value that does not reach `val`
+                    // together with `left_val`
+                    let right_val = right_lits
+                        .range(..val - left_val)
+                        .next_back()
+                        .map_or(0, |(&rv, _)| rv);
+                    let mut cl = clause![!out_lit];
+                    if let Some((_, &lit)) = left_lits.range(left_val + 1..).next() {
+                        cl.add(lit);
+                    }
+                    if let Some((_, &lit)) = right_lits.range(right_val + 1..).next() {
+                        cl.add(lit);
+                    }
+                    collector.add_clause(cl)?;
+                }
+            }
+            *ge_encoded = true;
+        }
+        Ok(())
+    }
+
+    /// Checks that all output literals in the subtree rooted in this node
+    /// belong to reachable sums and returns the reachable sums of this node
+    fn check_reachable_outputs(&self) -> BTreeSet<usize> {
+        match self {
+            Node::Leaf { weight, .. } => BTreeSet::from([0, *weight]),
+            Node::Internal {
+                out_lits,
+                left,
+                right,
+                ..
+            } => {
+                let left_sums = left.check_reachable_outputs();
+                let right_sums = right.check_reachable_outputs();
+                let sums: BTreeSet<usize> = left_sums
+                    .iter()
+                    .flat_map(|l| right_sums.iter().map(move |r| l + r))
+                    .collect();
+                for val in out_lits.keys() {
+                    assert!(
+                        sums.contains(val),
+                        "output literal for unreachable sum {}",
+                        val
+                    );
+                }
+                sums
+            }
         }
     }
 
@@ -400,6 +1279,23 @@ impl Node {
         }
     }
 
+    /// Gets the largest output value of the node below `end`, or zero if
+    /// there is none
+    fn max_val_below(&self, end: usize) -> usize {
+        match self {
+            Node::Leaf { weight, .. } => {
+                if *weight < end {
+                    *weight
+                } else {
+                    0
+                }
+            }
+            Node::Internal { out_lits, .. } => {
+                out_lits.range(..end).next_back().map_or(0, |(&val, _)| val)
+            }
+        }
+    }
+
     /// Gets a reference to the output literals. The temporary map is needed in
     /// case the node is not internal.
     fn lit_map<'a>(&'a self, tmp_map: &'a mut BTreeMap<usize, Lit>) -> &'a BTreeMap<usize, Lit> {
@@ -495,12 +1391,14 @@ impl Node {
 
     /// Encodes the output literals from the children to this node in a given
     /// range. Recurses depth first. Always encodes the full requested CNF
-    /// encoding.
+    /// encoding. If `track_stats` is `false`, the statistics and encoded
+    /// ranges of the nodes are not updated.
     pub fn rec_encode<Col>(
         &mut self,
         range: Range<usize>,
         collector: &mut Col,
         var_manager: &mut dyn ManageVars,
+        track_stats: bool,
     ) -> Result<(), crate::OutOfMemory>
     where
         Col: CollectClauses,
@@ -517,20 +1415,57 @@ impl Node {
                 let left_range = Node::compute_required_min_enc(range.clone(), right.max_val());
                 let right_range = Node::compute_required_min_enc(range.clone(), left.max_val());
                 // Recurse
-                left.rec_encode(left_range, collector, var_manager)?;
-                right.rec_encode(right_range, collector, var_manager)?;
+                left.rec_encode(left_range, collector, var_manager, track_stats)?;
+                right.rec_encode(right_range, collector, var_manager, track_stats)?;
 
                 // Encode current node
                 let n_clauses_before = collector.n_clauses();
                 self.encode_range(range.clone(), collector, var_manager)?;
 
-                self.update_stats(range, collector.n_clauses() - n_clauses_before);
+                if track_stats {
+                    self.update_stats(range, collector.n_clauses() - n_clauses_before);
+                }
             }
         };
 
         Ok(())
     }
 
+    /// Encodes the output literals from the children to this node in a given
+    /// range for upper bounds below `bound_end` without updating the
+    /// statistics of the nodes. Since the encoding is not extended later on,
+    /// the right child is encoded first and only its values below `bound_end`
+    /// are considered for cutting off the low values of the left child. A
+    /// larger value of the right child on its own already exceeds every bound
+    /// that is enforced.
+    fn rec_encode_oneshot<Col>(
+        &mut self,
+        range: Range<usize>,
+        bound_end: usize,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+    {
+        let range = self.limit_range(range);
+        if range.is_empty() {
+            return Ok(());
+        }
+
+        if let Node::Internal { left, right, .. } = self {
+            let right_range = Node::compute_required_min_enc(range.clone(), left.max_val());
+            right.rec_encode_oneshot(right_range, bound_end, collector, var_manager)?;
+            let left_range =
+                Node::compute_required_min_enc(range.clone(), right.max_val_below(bound_end));
+            left.rec_encode_oneshot(left_range, bound_end, collector, var_manager)?;
+
+            self.encode_range(range, collector, var_manager)?;
+        }
+
+        Ok(())
+    }
+
     /// Encodes the output literals from the children to this node in a given
     /// range. Recurses depth first. Incrementally only encodes new clauses.
     pub fn rec_encode_change<Col>(
@@ -660,8 +1595,24 @@ impl Node {
         self.reserve_all_vars(var_manager)
     }
 
+    /// Reserves the variables for all values below `end` in this node and the
+    /// lower subtree. Since child values of at least `end` can not add up to a
+    /// value below `end`, the same values are reserved in the children.
+    fn reserve_vars_rec(&mut self, end: usize, var_manager: &mut dyn ManageVars) {
+        match self {
+            Node::Leaf { .. } => return,
+            Node::Internal { left, right, .. } => {
+                left.reserve_vars_rec(end, var_manager);
+                right.reserve_vars_rec(end, var_manager);
+            }
+        };
+        self.reserve_vars_range(0..end, var_manager);
+    }
+
     /// Computes the required encoding range for a node given a requested range
-    /// for the parent and the maximum value of the sibling.
+    /// for the parent and the maximum value of the sibling. Output values of
+    /// the node below `requested_range.start - max_sibling` can not add up to
+    /// a value in the requested range and are therefore not required.
     fn compute_required_min_enc(requested_range: Range<usize>, max_sibling: usize) -> Range<usize> {
         if requested_range.is_empty() {
             0..0
@@ -711,12 +1662,14 @@ mod tests {
     use crate::{
         encodings::{
             card,
-            pb::{BoundUpper, BoundUpperIncremental},
-            EncodeStats, Error,
+            pb::{
+                BoundUpper, BoundUpperIncremental, EncodeIncremental, InvertedGeneralizedTotalizer,
+            },
+            CallbackCollector, CollectClauses, EncodeStats, Error, IterWeightedInputs,
         },
         instances::{BasicVarManager, Cnf, ManageVars},
         lit,
-        types::RsHashMap,
+        types::{Lit, RsHashMap},
         var,
     };
 
@@ -749,6 +1702,7 @@ mod tests {
             n_clauses: 0,
             max_val: 8,
             enc_range: 0..9,
+            ge_encoded: false,
             // Dummy nodes for children
             left: Box::new(Node::new_leaf(lit![0], 5)),
             right: Box::new(Node::new_leaf(lit![0], 3)),
@@ -763,6 +1717,7 @@ mod tests {
             n_clauses: 0,
             max_val: 8,
             enc_range: 0..9,
+            ge_encoded: false,
             // Dummy nodes for children
             left: Box::new(Node::new_leaf(lit![0], 5)),
             right: Box::new(Node::new_leaf(lit![0], 3)),
@@ -791,6 +1746,7 @@ mod tests {
             n_clauses: 0,
             max_val: 8,
             enc_range: 0..9,
+            ge_encoded: false,
             // Dummy nodes for children
             left: Box::new(Node::new_leaf(lit![0], 5)),
             right: Box::new(Node::new_leaf(lit![0], 3)),
@@ -805,6 +1761,7 @@ mod tests {
             n_clauses: 0,
             max_val: 8,
             enc_range: 0..9,
+            ge_encoded: false,
             // Dummy nodes for children
             left: Box::new(Node::new_leaf(lit![0], 5)),
             right: Box::new(Node::new_leaf(lit![0], 3)),
@@ -833,6 +1790,7 @@ mod tests {
             n_clauses: 0,
             max_val: 8,
             enc_range: 0..9,
+            ge_encoded: false,
             // Dummy nodes for children
             left: Box::new(Node::new_leaf(lit![0], 5)),
             right: Box::new(Node::new_leaf(lit![0], 3)),
@@ -847,6 +1805,7 @@ mod tests {
             n_clauses: 0,
             max_val: 8,
             enc_range: 0..9,
+            ge_encoded: false,
             // Dummy nodes for children
             left: Box::new(Node::new_leaf(lit![0], 5)),
             right: Box::new(Node::new_leaf(lit![0], 3)),
@@ -965,4 +1924,553 @@ mod tests {
         assert_eq!(gte_cnf.len(), gte.n_clauses());
         assert_eq!(tot_cnf.len(), tot.n_clauses());
     }
+
+    #[test]
+    fn ub_gte_clone_incremental() {
+        let mut gte = GeneralizedTotalizer::default();
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 5);
+        lits.insert(lit![1], 5);
+        lits.insert(lit![2], 3);
+        lits.insert(lit![3], 3);
+        gte.extend(lits);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        gte.encode_ub(0..3, &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        let mut clone = gte.clone();
+        let mut clone_var_manager = var_manager.clone();
+        assert_eq!(gte.n_clauses(), clone.n_clauses());
+        assert_eq!(gte.n_vars(), clone.n_vars());
+        let mut cnf = Cnf::new();
+        gte.encode_ub_change(0..7, &mut cnf, &mut var_manager)
+            .unwrap();
+        let mut clone_cnf = Cnf::new();
+        clone
+            .encode_ub_change(0..7, &mut clone_cnf, &mut clone_var_manager)
+            .unwrap();
+        assert_eq!(cnf, clone_cnf);
+        assert_eq!(gte.n_clauses(), clone.n_clauses());
+        assert_eq!(gte.enforce_ub(4), clone.enforce_ub(4));
+    }
+
+    #[test]
+    fn ub_gte_callback_collector() {
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 5);
+        lits.insert(lit![1], 5);
+        lits.insert(lit![2], 3);
+        lits.insert(lit![3], 3);
+        let mut gte1 = GeneralizedTotalizer::from(lits.clone());
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf = Cnf::new();
+        gte1.encode_ub(0..7, &mut cnf, &mut var_manager).unwrap();
+        let mut gte2 = GeneralizedTotalizer::from(lits);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut streamed = Cnf::new();
+        let mut collector = CallbackCollector::new(|cl| streamed.add_clause(cl));
+        gte2.encode_ub(0..7, &mut collector, &mut var_manager)
+            .unwrap();
+        assert_eq!(collector.n_clauses(), gte2.n_clauses());
+        assert_eq!(cnf, streamed);
+    }
+
+    #[test]
+    fn ub_gte_encoded_range() {
+        let mut gte = GeneralizedTotalizer::default();
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 5);
+        lits.insert(lit![1], 5);
+        lits.insert(lit![2], 3);
+        lits.insert(lit![3], 3);
+        gte.extend(lits);
+        assert_eq!(gte.encoded_range(), None);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut cnf = Cnf::new();
+        gte.encode_ub(0..7, &mut cnf, &mut var_manager).unwrap();
+        let range = gte.encoded_range().unwrap();
+        assert!(range.start == 0 && range.end >= 7);
+        for ub in 0..gte.weight_sum {
+            assert_eq!(gte.enforce_ub(ub).is_ok(), range.contains(&ub));
+        }
+    }
+
+    #[test]
+    fn ub_gte_lower_cutoff() {
+        // Outputs below `start - max_sibling` are not encoded
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 5);
+        lits.insert(lit![1], 5);
+        lits.insert(lit![2], 3);
+        lits.insert(lit![3], 3);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut full = GeneralizedTotalizer::from(lits.clone());
+        full.encode_ub(0..7, &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        let mut cut = GeneralizedTotalizer::from(lits);
+        cut.encode_ub(4..7, &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        assert!(cut.n_clauses() < full.n_clauses());
+        assert!(cut.n_vars() < full.n_vars());
+        for ub in 4..7 {
+            assert!(cut.enforce_ub(ub).is_ok());
+        }
+    }
+
+    #[test]
+    fn ub_gte_enforce_window_clamped() {
+        let mut gte = GeneralizedTotalizer::default();
+        gte.extend([(lit![0], 3), (lit![1], 3), (lit![2], 2), (lit![3], 1)]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        gte.encode_ub(2..5, &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        let single = gte.enforce_ub(2).unwrap();
+        // Bounds in the window that are not encoded do not fail
+        let window = gte.enforce_ub_window(2, usize::MAX).unwrap();
+        assert!(single.iter().all(|l| window.contains(l)));
+        assert!(window.len() > single.len());
+        let mut dedup = window.clone();
+        dedup.sort_unstable();
+        dedup.dedup();
+        assert_eq!(dedup.len(), window.len());
+        assert_eq!(gte.enforce_ub_window(0, 3), Err(Error::NotEncoded));
+    }
+
+    #[test]
+    #[should_panic(expected = "stable outputs")]
+    fn ub_gte_encode_ge_stable_outputs() {
+        let mut gte = GeneralizedTotalizer::default().with_stable_outputs();
+        gte.extend([(lit![0], 1), (lit![1], 2)]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![2]);
+        gte.encode_ge(&mut Cnf::new(), &mut var_manager).unwrap();
+    }
+
+    #[test]
+    fn ub_gte_stable_outputs_single_input() {
+        let mut gte = GeneralizedTotalizer::default().with_stable_outputs();
+        gte.extend([(lit![0], 3)]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![2]);
+        gte.encode_ub(.., &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        assert_eq!(gte.enforce_ub(2).unwrap(), vec![!lit![0]]);
+        gte.extend([(lit![1], 1)]);
+        gte.encode_ub(.., &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        // The leaf root is replaced by an internal root with fresh outputs
+        let after = gte.enforce_ub(2).unwrap();
+        assert!(after.iter().any(|l| l.var() >= var![2]));
+        assert!(matches!(gte.root, Some(Node::Internal { .. })));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not tighten")]
+    fn ub_gte_tighten_ub_looser() {
+        let mut gte = GeneralizedTotalizer::default();
+        gte.extend([(lit![0], 1), (lit![1], 2), (lit![2], 3)]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        gte.tighten_ub(4, &mut var_manager).unwrap();
+        gte.tighten_ub(2, &mut var_manager).unwrap();
+        gte.tighten_ub(2, &mut var_manager).unwrap();
+    }
+
+    #[test]
+    fn ub_gte_empty() {
+        let mut gte = GeneralizedTotalizer::default();
+        assert_eq!(gte.enforce_ub(5), Err(Error::EmptyEncoding));
+        gte.encode_ub(0..5, &mut Cnf::new(), &mut BasicVarManager::default())
+            .unwrap();
+        assert_eq!(gte.enforce_ub(5), Err(Error::EmptyEncoding));
+        gte.extend([(lit![0], 1)]);
+        assert_eq!(gte.enforce_ub(5), Ok(vec![]));
+    }
+
+    #[test]
+    fn ub_gte_uniform_weights() {
+        let mut gte = GeneralizedTotalizer::default();
+        gte.extend([(lit![0], 3), (lit![1], 3), (lit![2], 3), (lit![3], 3)]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        gte.encode_ub(4..5, &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        match &gte.root {
+            Some(Node::Internal { out_lits, .. }) => {
+                assert_eq!(out_lits.keys().copied().collect::<Vec<_>>(), vec![6])
+            }
+            _ => panic!(),
+        }
+        assert_eq!(gte.encoded_range(), Some(4..6));
+        assert_eq!(gte.enforce_ub(4).unwrap().len(), 1);
+        assert_eq!(gte.enforce_ub(5).unwrap().len(), 1);
+        assert_eq!(gte.enforce_ub(6), Err(Error::NotEncoded));
+    }
+
+    #[test]
+    fn ub_gte_uniform_weight_tracked() {
+        let mut gte = GeneralizedTotalizer::from_iter([(lit![0], 1), (lit![1], 2)]);
+        assert_eq!(gte.uniform_weight(), None);
+        // Increasing the weight of an input can make the weights uniform
+        gte.extend([(lit![0], 1)]);
+        assert_eq!(gte.uniform_weight(), Some(2));
+        gte.extend([(lit![2], 0)]);
+        assert_eq!(gte.uniform_weight(), None);
+    }
+
+    #[test]
+    fn ub_gte_shared_collector() {
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 5);
+        lits.insert(lit![1], 5);
+        lits.insert(lit![2], 3);
+        lits.insert(lit![3], 3);
+        // Encode into separate CNFs and join them
+        let mut gte = GeneralizedTotalizer::from(lits.clone());
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut joined = Cnf::new();
+        for ub in [2, 7, 11] {
+            let mut cnf = Cnf::new();
+            gte.encode_ub_change(ub..ub + 1, &mut cnf, &mut var_manager)
+                .unwrap();
+            joined.extend(cnf);
+        }
+        // Encode directly into one buffer
+        let mut gte = GeneralizedTotalizer::from(lits);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut buffer = Cnf::new();
+        for ub in [2, 7, 11] {
+            gte.encode_ub_change(ub..ub + 1, &mut buffer, &mut var_manager)
+                .unwrap();
+        }
+        assert_eq!(joined, buffer);
+        assert_eq!(gte.n_clauses(), buffer.len());
+    }
+
+    #[test]
+    fn ub_gte_trivial_bound() {
+        let mut gte = GeneralizedTotalizer::default();
+        gte.extend([(lit![0], 4), (lit![1], 3), (lit![2], 2)]);
+        // Trivial bounds need no assumptions, even without an encoding
+        for ub in 9..12 {
+            assert_eq!(gte.enforce_ub(ub), Ok(vec![]));
+        }
+        assert_eq!(gte.enforce_ub(8), Err(Error::NotEncoded));
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        gte.encode_ub(2..5, &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        for ub in 9..12 {
+            assert_eq!(gte.enforce_ub(ub), Ok(vec![]));
+        }
+    }
+
+    #[test]
+    fn ub_gte_buffer_counts() {
+        let mut gte = GeneralizedTotalizer::default();
+        gte.extend([(lit![0], 1), (lit![1], 2), (lit![2], 5)]);
+        assert_eq!(gte.n_buffered(), 3);
+        assert_eq!(gte.n_in_tree(), 0);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        gte.encode_ub_change(0..3, &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        assert_eq!(gte.n_buffered(), 1);
+        assert_eq!(gte.n_in_tree(), 2);
+        gte.extend([(lit![3], 1), (lit![0], 1)]);
+        assert_eq!(gte.n_buffered(), 3);
+        assert_eq!(gte.n_in_tree(), 2);
+        gte.encode_ub_change(0..6, &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        assert_eq!(gte.n_buffered(), 0);
+        assert_eq!(gte.n_in_tree(), 4);
+    }
+
+    #[test]
+    fn ub_gte_enforce_delta() {
+        let mut gte = GeneralizedTotalizer::from_iter([
+            (lit![0], 6),
+            (lit![1], 5),
+            (lit![2], 3),
+            (lit![3], 1),
+        ]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        gte.encode_ub(.., &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        for prev_ub in 1..=gte.weight_sum {
+            let prev = gte.enforce_ub(prev_ub).unwrap();
+            for ub in 0..=prev_ub {
+                let delta = gte.enforce_ub_delta(prev_ub, ub).unwrap();
+                let full = gte.enforce_ub(ub).unwrap();
+                assert!(delta.iter().all(|l| full.contains(l) && !prev.contains(l)));
+                assert!(full.iter().all(|l| delta.contains(l) || prev.contains(l)));
+            }
+        }
+        assert!(gte.enforce_ub_delta(7, 7).unwrap().is_empty());
+    }
+
+    #[test]
+    fn ub_gte_dot() {
+        let mut gte = GeneralizedTotalizer::from_iter([
+            (lit![0], 6),
+            (lit![1], 5),
+            (lit![2], 3),
+            (lit![3], 1),
+        ]);
+        assert_eq!(gte.to_dot(), "digraph gte {\n}\n");
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        gte.encode_ub(.., &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        let dot = gte.to_dot();
+        assert!(dot.starts_with("digraph gte {\n"));
+        assert!(dot.ends_with("}\n"));
+        // Four leaves and three internal nodes
+        let n_nodes = dot.lines().filter(|l| l.contains(" [")).count();
+        let n_edges = dot.lines().filter(|l| l.contains(" -> ")).count();
+        assert_eq!(n_nodes, 7);
+        assert_eq!(n_edges, 6);
+        assert_eq!(dot.matches("shape=box").count(), 4);
+        assert!(dot.contains("x0 : 6"));
+        assert!(dot.contains("max 15"));
+        assert!(dot.contains("outs "));
+        let mut written = vec![];
+        gte.write_dot(&mut written).unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), dot);
+    }
+
+    #[test]
+    fn ub_gte_reachable_outputs() {
+        let mut gte = GeneralizedTotalizer::from_iter([(lit![0], 3), (lit![1], 3)]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![2]);
+        gte.encode_ub(.., &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        gte.assert_no_unreachable_outputs();
+        match &gte.root {
+            Some(Node::Internal { out_lits, .. }) => {
+                assert_eq!(out_lits.keys().copied().collect::<Vec<_>>(), vec![3, 6]);
+            }
+            _ => panic!("expected internal root"),
+        }
+
+        let mut gte = GeneralizedTotalizer::from_iter(
+            (0..10).map(|idx| (lit![idx], [4, 6, 10][idx as usize % 3])),
+        );
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![10]);
+        gte.encode_ub(7..=23, &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        gte.assert_no_unreachable_outputs();
+    }
+
+    #[test]
+    fn ub_gte_oneshot() {
+        let terms: Vec<(Lit, usize)> = (0..20)
+            .map(|idx| (lit![idx], [1, 2, 3, 5, 8][idx as usize % 5]))
+            .collect();
+        let mut regular = GeneralizedTotalizer::from_iter(terms.iter().copied());
+        let mut oneshot = GeneralizedTotalizer::from_iter(terms);
+        let mut regular_cnf = Cnf::new();
+        let mut oneshot_cnf = Cnf::new();
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![20]);
+        regular
+            .encode_ub(10..=20, &mut regular_cnf, &mut var_manager.clone())
+            .unwrap();
+        oneshot
+            .encode_ub_oneshot(10..=20, &mut oneshot_cnf, &mut var_manager)
+            .unwrap();
+        assert_eq!(regular.encoded_range(), oneshot.encoded_range());
+        for ub in 10..=20 {
+            assert!(oneshot.enforce_ub(ub).is_ok());
+        }
+        assert_eq!(oneshot.n_clauses(), oneshot_cnf.len());
+        assert!(oneshot.n_clauses() <= regular.n_clauses());
+        assert!(oneshot.n_vars() <= regular.n_vars());
+    }
+
+    #[test]
+    fn ub_gte_oneshot_lower_cutoff() {
+        let terms = [(lit![0], 1), (lit![1], 3), (lit![2], 7), (lit![3], 9)];
+        let mut regular = GeneralizedTotalizer::from_iter(terms);
+        let mut oneshot = GeneralizedTotalizer::from_iter(terms);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        regular
+            .encode_ub(14..15, &mut Cnf::new(), &mut var_manager.clone())
+            .unwrap();
+        oneshot
+            .encode_ub_oneshot(14..15, &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        // The subtree of 1 and 3 is not encoded, since it only reaches the
+        // range together with 7 and 9, which on their own exceed the bound
+        assert!(oneshot.n_clauses() < regular.n_clauses());
+        assert!(oneshot.n_vars() < regular.n_vars());
+        assert_eq!(regular.encoded_range(), oneshot.encoded_range());
+        assert!(oneshot.enforce_ub(14).is_ok());
+    }
+
+    #[test]
+    fn ub_gte_input_counts() {
+        let mut gte = GeneralizedTotalizer::default();
+        gte.extend([(lit![0], 2), (lit![1], 3), (lit![2], 1)]);
+        assert_eq!(gte.n_lits(), 3);
+        assert_eq!(gte.n_distinct_weights(), 3);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        gte.encode_ub(.., &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        // Weights of repeated literals are summed
+        gte.extend([(lit![2], 1), (lit![0], 1), (lit![3], 3)]);
+        assert_eq!(gte.n_lits(), 4);
+        assert_eq!(gte.n_distinct_weights(), 2);
+        let inverted =
+            InvertedGeneralizedTotalizer::from_iter([(lit![0], 2), (lit![1], 2), (lit![0], 1)]);
+        assert_eq!(inverted.n_lits(), 2);
+        assert_eq!(inverted.n_distinct_weights(), 2);
+    }
+
+    #[test]
+    fn ub_gte_compressed() {
+        let terms: Vec<(Lit, usize)> = (0..30)
+            .map(|idx| (lit![idx], [2, 3, 5, 7, 11][idx as usize % 5]))
+            .collect();
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![30]);
+        let mut plain = GeneralizedTotalizer::from_iter(terms.iter().copied());
+        let mut plain_cnf = Cnf::new();
+        plain
+            .encode_ub(.., &mut plain_cnf, &mut var_manager)
+            .unwrap();
+        let mut compressed = GeneralizedTotalizer::from_compressed(&terms);
+        let mut compressed_cnf = Cnf::new();
+        compressed
+            .encode_ub(.., &mut compressed_cnf, &mut var_manager)
+            .unwrap();
+        assert!(compressed.n_clauses() < plain.n_clauses());
+        assert_eq!(compressed.weight_sum, plain.weight_sum);
+    }
+
+    #[test]
+    fn ub_gte_weight_overflow() {
+        let mut gte = GeneralizedTotalizer::default();
+        gte.try_extend([(lit![0], usize::MAX - 2), (lit![1], 2)])
+            .unwrap();
+        assert_eq!(gte.weight_sum, usize::MAX);
+        assert_eq!(gte.try_extend([(lit![2], 1)]), Err(Error::WeightOverflow));
+        assert_eq!(gte.try_extend([(lit![0], 1)]), Err(Error::WeightOverflow));
+        assert_eq!(gte.weight_sum, usize::MAX);
+        assert_eq!(gte.in_lits.len(), 2);
+    }
+
+    #[test]
+    fn ub_gte_encode_ge_no_duplicates() {
+        let lits = [(lit![0], 3), (lit![1], 2), (lit![2], 2)];
+        let new_lits = [(lit![3], 4), (lit![4], 1)];
+
+        // Upper bounding encoding and repeated calls before and after
+        // extending the encoding
+        let mut gte = GeneralizedTotalizer::from_iter(lits);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![5]);
+        let mut cnf = Cnf::new();
+        gte.encode_ub(.., &mut cnf, &mut var_manager).unwrap();
+        gte.encode_ge(&mut cnf, &mut var_manager).unwrap();
+        gte.encode_ge(&mut cnf, &mut var_manager).unwrap();
+        gte.extend(new_lits);
+        gte.encode_ge(&mut cnf, &mut var_manager).unwrap();
+        assert_eq!(gte.n_clauses(), cnf.len());
+
+        // Fresh encoding of the same tree
+        let mut fresh = GeneralizedTotalizer::from_iter(lits);
+        let mut fresh_var_manager = BasicVarManager::default();
+        fresh_var_manager.increase_next_free(var![5]);
+        fresh.reserve_up_to(7, &mut fresh_var_manager);
+        fresh.extend(new_lits);
+        let mut fresh_cnf = Cnf::new();
+        fresh
+            .encode_ge(&mut fresh_cnf, &mut fresh_var_manager)
+            .unwrap();
+        assert_eq!(fresh.depth(), gte.depth());
+        assert_eq!(cnf.len(), fresh_cnf.len());
+    }
+
+    #[test]
+    fn ub_gte_reserve_up_to() {
+        let lits = [
+            (lit![0], 4),
+            (lit![1], 3),
+            (lit![2], 3),
+            (lit![3], 2),
+            (lit![4], 1),
+        ];
+        let mut partial = GeneralizedTotalizer::from_iter(lits);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![5]);
+        partial.reserve_up_to(5, &mut var_manager);
+        let n_reserved = var_manager.n_used();
+        let mut cnf = Cnf::new();
+        partial.encode_ub(..=5, &mut cnf, &mut var_manager).unwrap();
+        // Encoding up to the bound only uses reserved variables
+        assert_eq!(var_manager.n_used(), n_reserved);
+        assert_eq!(partial.n_vars(), n_reserved - 5);
+
+        let mut full = GeneralizedTotalizer::from_iter(lits);
+        let mut full_var_manager = BasicVarManager::default();
+        full_var_manager.increase_next_free(var![5]);
+        full.encode_ub(..=5, &mut Cnf::new(), &mut full_var_manager)
+            .unwrap();
+        full.reserve(&mut full_var_manager);
+        assert!(full_var_manager.n_used() > n_reserved);
+
+        // Same outputs as for full encoding up to the bound
+        let mut no_reserve = GeneralizedTotalizer::from_iter(lits);
+        let mut no_reserve_var_manager = BasicVarManager::default();
+        no_reserve_var_manager.increase_next_free(var![5]);
+        no_reserve
+            .encode_ub(..=5, &mut Cnf::new(), &mut no_reserve_var_manager)
+            .unwrap();
+        assert_eq!(no_reserve_var_manager.n_used(), n_reserved);
+        for ub in 0..=5 {
+            assert_eq!(
+                partial.enforce_ub(ub).unwrap().len(),
+                no_reserve.enforce_ub(ub).unwrap().len()
+            );
+        }
+
+        // Larger bounds need additional variables
+        partial
+            .encode_ub_change(..=8, &mut cnf, &mut var_manager)
+            .unwrap();
+        assert!(var_manager.n_used() > n_reserved);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn ub_gte_parallel_build() {
+        let mut lits: Vec<(Lit, usize)> = (0..10_000)
+            .map(|idx| (lit![idx], (idx as usize * 7919) % 97 + 1))
+            .collect();
+        lits.sort_by_key(|(_, w)| *w);
+        let seq = GeneralizedTotalizer::build_tree_sequential(&lits);
+        let mut seq_dot = String::new();
+        seq.write_dot(&mut seq_dot, &mut 0);
+        for threads in [1, 3, 8] {
+            let par = GeneralizedTotalizer::build_tree_parallel(&lits, 64, threads);
+            assert_eq!(seq.depth(), par.depth());
+            let mut par_dot = String::new();
+            par.write_dot(&mut par_dot, &mut 0);
+            // Same structure and leaf order
+            assert_eq!(seq_dot, par_dot);
+        }
+    }
 }
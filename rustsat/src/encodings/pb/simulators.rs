@@ -15,17 +15,20 @@ use super::{
 use crate::{
     encodings::{card, CollectClauses, EncodeStats, Error, IterInputs, IterWeightedInputs},
     instances::ManageVars,
-    types::{Lit, RsHashMap},
+    types::{Lit, RsHashMap, WLitIterExt},
 };
 
 /// Simulator type that builds a pseudo-boolean encoding of type `PBE` over the
 /// negated input literals in order to simulate the other bound type
+#[derive(Clone)]
 pub struct Inverted<PBE>
 where
     PBE: Encode + 'static,
 {
     pb_enc: PBE,
     weight_sum: usize,
+    /// Whether any input literals were added to the encoding
+    has_lits: bool,
 }
 
 impl<PBE> Default for Inverted<PBE>
@@ -36,6 +39,7 @@ where
         Self {
             pb_enc: Default::default(),
             weight_sum: Default::default(),
+            has_lits: false,
         }
     }
 }
@@ -44,6 +48,22 @@ impl<PBE> Inverted<PBE>
 where
     PBE: Encode + 'static,
 {
+    /// Gets the smallest lower bound that can be satisfied, which is always
+    /// zero
+    #[must_use]
+    pub fn min_achievable_lb(&self) -> usize {
+        0
+    }
+
+    /// Gets the largest lower bound that can be satisfied, i.e., the sum of
+    /// all input weights. Enforcing a lower bound larger than this with
+    /// [`BoundLower::enforce_lb`] is unsatisfiable and returns
+    /// [`Error::Unsat`].
+    #[must_use]
+    pub fn max_achievable_lb(&self) -> usize {
+        self.weight_sum
+    }
+
     fn convert_encoding_range(&self, range: Range<usize>) -> Range<usize> {
         let min = self.weight_sum() - (range.end - 1);
         let max = if self.weight_sum() >= range.start {
@@ -55,16 +75,30 @@ where
     }
 }
 
+impl Inverted<super::GeneralizedTotalizer> {
+    /// Gets the range of lower bounds that are currently encoded, i.e., for
+    /// which [`BoundLower::enforce_lb`] will succeed. The range is given in
+    /// terms of lower bounds on the original input literals, not in terms of
+    /// the internal upper bounding encoding. The trivial lower bound zero
+    /// might not be included. Returns [`None`] if no bound is encoded.
+    pub fn encoded_range(&self) -> Option<Range<usize>> {
+        let ub_range = self.pb_enc.encoded_range()?;
+        Some(self.weight_sum + 1 - ub_range.end..self.weight_sum + 1 - ub_range.start)
+    }
+}
+
 impl<PBE> From<RsHashMap<Lit, usize>> for Inverted<PBE>
 where
     PBE: Encode + From<RsHashMap<Lit, usize>> + 'static,
 {
     fn from(lits: RsHashMap<Lit, usize>) -> Self {
         let ws = lits.iter().fold(0, |ws, (_, w)| ws + w);
-        let lits: RsHashMap<Lit, usize> = lits.into_iter().map(|(l, w)| (!l, w)).collect();
+        let has_lits = !lits.is_empty();
+        let lits: RsHashMap<Lit, usize> = lits.into_iter().negated_weighted().collect();
         Self {
             pb_enc: PBE::from(lits),
             weight_sum: ws,
+            has_lits,
         }
     }
 }
@@ -84,8 +118,9 @@ where
     PBE: Encode + Extend<(Lit, usize)> + 'static,
 {
     fn extend<T: IntoIterator<Item = (Lit, usize)>>(&mut self, iter: T) {
-        let lits: RsHashMap<Lit, usize> = iter.into_iter().map(|(l, w)| (!l, w)).collect();
+        let lits: RsHashMap<Lit, usize> = iter.into_iter().negated_weighted().collect();
         let ws = lits.iter().fold(0, |ws, (_, w)| ws + w);
+        self.has_lits |= !lits.is_empty();
         self.pb_enc.extend(lits);
         self.weight_sum += ws;
     }
@@ -150,6 +185,9 @@ where
     }
 
     fn enforce_ub(&self, ub: usize) -> Result<Vec<Lit>, Error> {
+        if !self.has_lits {
+            return Err(Error::EmptyEncoding);
+        }
         let lb = if self.weight_sum > ub {
             self.weight_sum - ub
         } else {
@@ -180,7 +218,12 @@ where
         )
     }
 
+    /// Returns assumptions for enforcing a lower bound. If no input literals
+    /// have been added to the encoding, returns [`Error::EmptyEncoding`].
     fn enforce_lb(&self, lb: usize) -> Result<Vec<Lit>, Error> {
+        if !self.has_lits {
+            return Err(Error::EmptyEncoding);
+        }
         let ub = if self.weight_sum >= lb {
             self.weight_sum - lb
         } else {
@@ -245,6 +288,10 @@ where
     fn n_vars(&self) -> u32 {
         self.pb_enc.n_vars()
     }
+
+    fn depth(&self) -> usize {
+        self.pb_enc.depth()
+    }
 }
 
 fn negate_weighted(weighted_lit: (Lit, usize)) -> (Lit, usize) {
@@ -255,6 +302,7 @@ type InvertedIter<IPBE> = std::iter::Map<IPBE, fn((Lit, usize)) -> (Lit, usize)>
 /// Simulator type that builds a combined pseudo-boolean encoding supporting
 /// both bounds from two individual pseudo-boolean encodings supporting each
 /// bound separately
+#[derive(Clone)]
 pub struct Double<UBE, LBE>
 where
     UBE: BoundUpper + 'static,
@@ -450,6 +498,10 @@ where
     fn n_vars(&self) -> u32 {
         self.ub_enc.n_vars() + self.lb_enc.n_vars()
     }
+
+    fn depth(&self) -> usize {
+        std::cmp::max(self.ub_enc.depth(), self.lb_enc.depth())
+    }
 }
 
 /// Simulator type that mimics a pseudo-boolean encoding based on a cardinality
@@ -638,10 +690,75 @@ type CardIter<ICE> = std::iter::Map<ICE, fn(Lit) -> (Lit, usize)>;
 
 #[cfg(test)]
 mod tests {
-    use crate::{encodings::pb::GeneralizedTotalizer, lit, types::RsHashMap};
+    use crate::{
+        encodings::{
+            pb::{
+                BoundBoth, BoundLower, BoundUpper, DoubleGeneralizedTotalizer, Encode,
+                GeneralizedTotalizer,
+            },
+            EncodeStats, Error,
+        },
+        instances::{BasicVarManager, Cnf, ManageVars},
+        lit,
+        types::{constraints::PBConstraint, Lit, RsHashMap},
+        var,
+    };
 
     use super::Inverted;
 
+    #[test]
+    fn inv_achievable_lb() {
+        let mut enc =
+            Inverted::<GeneralizedTotalizer>::from_iter([(lit![0], 1), (lit![1], 2), (lit![2], 3)]);
+        assert_eq!(enc.min_achievable_lb(), 0);
+        assert_eq!(enc.max_achievable_lb(), 6);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        enc.encode_lb(.., &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        for lb in enc.min_achievable_lb()..=enc.max_achievable_lb() {
+            assert!(enc.enforce_lb(lb).is_ok());
+        }
+        // Reaching the total weight requires all inputs
+        let assumps = enc.enforce_lb(6).unwrap();
+        assert!([lit![0], lit![1], lit![2]]
+            .iter()
+            .all(|l| assumps.contains(l)));
+        assert_eq!(enc.enforce_lb(7), Err(Error::Unsat));
+    }
+
+    #[test]
+    fn inv_empty() {
+        let mut enc = Inverted::<GeneralizedTotalizer>::default();
+        enc.encode_lb(.., &mut Cnf::new(), &mut BasicVarManager::default())
+            .unwrap();
+        assert_eq!(enc.enforce_lb(0), Err(Error::EmptyEncoding));
+        assert_eq!(enc.enforce_lb(3), Err(Error::EmptyEncoding));
+        let mut enc = Inverted::<Inverted<GeneralizedTotalizer>>::default();
+        assert_eq!(enc.enforce_ub(3), Err(Error::EmptyEncoding));
+        enc.extend([(lit![0], 1)]);
+        assert_eq!(enc.enforce_ub(3), Ok(vec![]));
+    }
+
+    #[test]
+    fn empty_constrs() {
+        let mut cnf = Cnf::new();
+        let mut var_manager = BasicVarManager::default();
+        for constr in [
+            PBConstraint::new_ub([], 2),
+            PBConstraint::new_lb([], 0),
+            PBConstraint::new_eq([], 0),
+        ] {
+            DoubleGeneralizedTotalizer::encode_constr(constr, &mut cnf, &mut var_manager).unwrap();
+        }
+        assert!(cnf.is_empty());
+        for constr in [PBConstraint::new_lb([], 2), PBConstraint::new_eq([], 2)] {
+            let err = DoubleGeneralizedTotalizer::encode_constr(constr, &mut cnf, &mut var_manager)
+                .unwrap_err();
+            assert_eq!(err.downcast::<Error>().unwrap(), Error::Unsat);
+        }
+    }
+
     #[test]
     fn inv_inv_range_map() {
         let mut lits = RsHashMap::default();
@@ -667,4 +784,124 @@ mod tests {
                 .convert_encoding_range(enc.convert_encoding_range(0..2))
         );
     }
+
+    #[test]
+    fn double_depth() {
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 1);
+        lits.insert(lit![1], 2);
+        lits.insert(lit![2], 1);
+        lits.insert(lit![3], 3);
+        let mut enc = DoubleGeneralizedTotalizer::from(lits);
+        assert_eq!(EncodeStats::depth(&enc), 0);
+        enc.encode_both(.., &mut Cnf::new(), &mut BasicVarManager::default())
+            .unwrap();
+        assert_eq!(EncodeStats::depth(&enc), 3);
+        assert_eq!(EncodeStats::depth(&enc.ub_enc), enc.ub_enc.depth());
+    }
+
+    #[test]
+    fn weight_sum_unencoded() {
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 1);
+        lits.insert(lit![1], 2);
+        lits.insert(lit![2], 4);
+        let mut gte = GeneralizedTotalizer::from(lits.clone());
+        let mut inv = Inverted::<GeneralizedTotalizer>::from(lits.clone());
+        let mut double = DoubleGeneralizedTotalizer::from(lits);
+        gte.encode_ub(0..2, &mut Cnf::new(), &mut BasicVarManager::default())
+            .unwrap();
+        // Merged and not yet encoded weights
+        gte.extend([(lit![0], 3), (lit![3], 8)]);
+        inv.extend([(lit![0], 3), (lit![3], 8)]);
+        double.extend([(lit![0], 3), (lit![3], 8)]);
+        assert_eq!(gte.weight_sum(), 18);
+        assert_eq!(inv.weight_sum(), 18);
+        assert_eq!(double.weight_sum(), 18);
+    }
+
+    #[test]
+    fn double_lazy_sides() {
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 1);
+        lits.insert(lit![1], 2);
+        lits.insert(lit![2], 4);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        let mut enc = DoubleGeneralizedTotalizer::from(lits);
+        enc.encode_ub(.., &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        // Only encoding upper bounds does not build the lower bounding side
+        assert_eq!(enc.lb_enc.n_vars(), 0);
+        assert_eq!(enc.n_vars(), enc.ub_enc.n_vars());
+        enc.encode_lb(.., &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        assert_eq!(enc.n_vars(), enc.ub_enc.n_vars() + enc.lb_enc.n_vars());
+        assert_eq!(enc.lb_enc.n_vars(), enc.ub_enc.n_vars());
+    }
+
+    #[test]
+    fn double_vs_shared_tree() {
+        let lits: RsHashMap<Lit, usize> = [(lit![0], 1), (lit![1], 2), (lit![2], 4), (lit![3], 3)]
+            .into_iter()
+            .collect();
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        let mut double = DoubleGeneralizedTotalizer::from(lits.clone());
+        double
+            .encode_both(.., &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        let mut shared = GeneralizedTotalizer::from(lits);
+        shared
+            .encode_both(.., &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        // Both bounds use the output literals of the same tree
+        assert_eq!(shared.n_vars(), double.ub_enc.n_vars());
+        assert_eq!(2 * shared.n_vars(), double.n_vars());
+        for b in 0..=10 {
+            assert!(shared.enforce_ub(b).is_ok());
+            assert!(shared.enforce_lb(b).is_ok());
+        }
+        assert_eq!(shared.enforce_lb(11), Err(Error::Unsat));
+    }
+
+    #[test]
+    fn double_strict_bounds() {
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 1);
+        lits.insert(lit![1], 2);
+        lits.insert(lit![2], 4);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        let mut enc = DoubleGeneralizedTotalizer::from(lits);
+        enc.encode_both(.., &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        for b in 1..=7 {
+            assert_eq!(enc.enforce_ub_strict(b), enc.enforce_ub(b - 1));
+        }
+        for b in 0..7 {
+            assert_eq!(enc.enforce_lb_strict(b), enc.enforce_lb(b + 1));
+        }
+        assert_eq!(enc.enforce_ub_strict(0), Err(Error::Unsat));
+        assert_eq!(enc.enforce_lb_strict(7), Err(Error::Unsat));
+    }
+
+    #[test]
+    fn inverted_encoded_range() {
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 1);
+        lits.insert(lit![1], 2);
+        lits.insert(lit![2], 4);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        let mut enc = Inverted::<GeneralizedTotalizer>::from(lits);
+        assert_eq!(enc.encoded_range(), None);
+        enc.encode_lb(5..7, &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        let range = enc.encoded_range().unwrap();
+        assert!(range.start <= 5 && range.end >= 7);
+        for lb in 1..=7 {
+            assert_eq!(enc.enforce_lb(lb).is_ok(), range.contains(&lb));
+        }
+    }
 }
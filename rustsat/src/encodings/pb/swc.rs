@@ -0,0 +1,239 @@
+//! # Sequential Weight Counter
+//!
+//! Implementation of the sequential weight counter encoding \[1\]. The encoding
+//! is not incremental, but typically produces fewer clauses than the
+//! [`super::GeneralizedTotalizer`] for single-shot encodings of PB constraints
+//! with moderate weights.
+//!
+//! ## References
+//!
+//! - \[1\] Steffen Hölldobler and Norbert Manthey and Peter Steinke: _A
+//!   Compact Encoding of Pseudo-Boolean Constraints into SAT_, KI 2012.
+
+use std::{cmp, ops::RangeBounds};
+
+use super::{
+    gte::{copy_key_val, GteIter},
+    BoundUpper, Encode,
+};
+use crate::{
+    clause,
+    encodings::{CollectClauses, EncodeStats, Error, IterWeightedInputs},
+    instances::ManageVars,
+    types::{Lit, RsHashMap},
+};
+
+/// Implementation of the sequential weight counter encoding \[1\]. The input
+/// literals are processed in a fixed order and a register of counter
+/// variables is introduced for each of them, where the `j`-th register
+/// variable of a literal is implied if the weighted sum of the literals up to
+/// this one is at least `j + 1`. The counters only count up to the largest
+/// upper bound to encode. This encoding only supports upper bounding and is
+/// not incremental, i.e., calling [`BoundUpper::encode_ub`] again rebuilds the
+/// full encoding.
+///
+/// # References
+///
+/// - \[1\] Steffen Hölldobler and Norbert Manthey and Peter Steinke: _A
+///   Compact Encoding of Pseudo-Boolean Constraints into SAT_, KI 2012.
+#[derive(Default, Clone)]
+pub struct SequentialWeightCounter {
+    /// Input literals and weights for the encoding
+    in_lits: RsHashMap<Lit, usize>,
+    /// Sum of all input weight
+    weight_sum: usize,
+    /// The register of the last input literal, if encoded. Literal `j`
+    /// indicates that the weighted sum is at least `j + 1`.
+    out_lits: Vec<Lit>,
+    /// The number of variables in the encoding
+    n_vars: u32,
+    /// The number of clauses in the encoding
+    n_clauses: usize,
+}
+
+impl Encode for SequentialWeightCounter {
+    fn weight_sum(&self) -> usize {
+        self.weight_sum
+    }
+}
+
+impl IterWeightedInputs for SequentialWeightCounter {
+    type Iter<'a> = GteIter<'a>;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.in_lits.iter().map(copy_key_val)
+    }
+}
+
+impl BoundUpper for SequentialWeightCounter {
+    fn encode_ub<Col, R>(
+        &mut self,
+        range: R,
+        collector: &mut Col,
+        var_manager: &mut dyn ManageVars,
+    ) -> Result<(), crate::OutOfMemory>
+    where
+        Col: CollectClauses,
+        R: RangeBounds<usize>,
+    {
+        let range = super::prepare_ub_range(self, range);
+        self.out_lits.clear();
+        if range.is_empty() {
+            return Ok(());
+        }
+        let n_vars_before = var_manager.n_used();
+        let n_clauses_before = collector.n_clauses();
+        // The registers only need to count up to the largest bound plus one
+        let max_val = range.end;
+        // Sort inputs for a deterministic encoding
+        let mut lits: Vec<(Lit, usize)> = self
+            .in_lits
+            .iter()
+            .filter_map(|(&l, &w)| if w > 0 { Some((l, w)) } else { None })
+            .collect();
+        lits.sort_unstable_by_key(|&(l, w)| (w, l));
+        let mut prefix_sum = 0;
+        let mut prev: Vec<Lit> = vec![];
+        for (lit, weight) in lits {
+            prefix_sum += weight;
+            let reg: Vec<Lit> = (0..cmp::min(prefix_sum, max_val))
+                .map(|_| var_manager.new_lit())
+                .collect();
+            // The literal itself sets the register up to its weight
+            collector.extend_clauses(
+                reg.iter()
+                    .take(weight)
+                    .map(|&reg_lit| clause![!lit, reg_lit]),
+            )?;
+            // The previous register carries over
+            collector.extend_clauses(
+                prev.iter()
+                    .zip(reg.iter())
+                    .map(|(&prev_lit, &reg_lit)| clause![!prev_lit, reg_lit]),
+            )?;
+            // The literal adds its weight to the previous register
+            collector.extend_clauses(prev.iter().enumerate().map(|(idx, &prev_lit)| {
+                clause![!lit, !prev_lit, reg[cmp::min(idx + weight, max_val - 1)]]
+            }))?;
+            prev = reg;
+        }
+        self.out_lits = prev;
+        self.n_clauses += collector.n_clauses() - n_clauses_before;
+        self.n_vars += var_manager.n_used() - n_vars_before;
+        Ok(())
+    }
+
+    /// Returns assumptions for enforcing an upper bound. If no input literals
+    /// have been added to the encoding, returns [`Error::EmptyEncoding`].
+    fn enforce_ub(&self, ub: usize) -> Result<Vec<Lit>, Error> {
+        if self.in_lits.is_empty() {
+            return Err(Error::EmptyEncoding);
+        }
+        if ub >= self.weight_sum {
+            return Ok(vec![]);
+        }
+        if ub < self.out_lits.len() {
+            return Ok(vec![!self.out_lits[ub]]);
+        }
+        Err(Error::NotEncoded)
+    }
+}
+
+impl EncodeStats for SequentialWeightCounter {
+    fn n_clauses(&self) -> usize {
+        self.n_clauses
+    }
+
+    fn n_vars(&self) -> u32 {
+        self.n_vars
+    }
+}
+
+impl From<RsHashMap<Lit, usize>> for SequentialWeightCounter {
+    fn from(lits: RsHashMap<Lit, usize>) -> Self {
+        Self {
+            weight_sum: lits.iter().fold(0, |sum, (_, w)| sum + w),
+            in_lits: lits,
+            ..Default::default()
+        }
+    }
+}
+
+impl FromIterator<(Lit, usize)> for SequentialWeightCounter {
+    fn from_iter<T: IntoIterator<Item = (Lit, usize)>>(iter: T) -> Self {
+        let lits: RsHashMap<Lit, usize> = RsHashMap::from_iter(iter);
+        Self::from(lits)
+    }
+}
+
+impl Extend<(Lit, usize)> for SequentialWeightCounter {
+    fn extend<T: IntoIterator<Item = (Lit, usize)>>(&mut self, iter: T) {
+        // Extending invalidates the encoding
+        self.out_lits.clear();
+        iter.into_iter().for_each(|(l, w)| {
+            self.weight_sum += w;
+            match self.in_lits.get_mut(&l) {
+                Some(old_w) => *old_w += w,
+                None => {
+                    self.in_lits.insert(l, w);
+                }
+            };
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SequentialWeightCounter;
+    use crate::{
+        encodings::{pb::BoundUpper, EncodeStats, Error},
+        instances::{BasicVarManager, Cnf, ManageVars},
+        lit,
+        types::RsHashMap,
+        var,
+    };
+
+    #[test]
+    fn ub_functions() {
+        let mut swc = SequentialWeightCounter::default();
+        let mut lits = RsHashMap::default();
+        lits.insert(lit![0], 1);
+        lits.insert(lit![1], 2);
+        lits.insert(lit![2], 1);
+        swc.extend(lits);
+        assert_eq!(swc.enforce_ub(2), Err(Error::NotEncoded));
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        let mut cnf = Cnf::new();
+        swc.encode_ub(0..3, &mut cnf, &mut var_manager).unwrap();
+        assert_eq!(swc.n_vars(), 6);
+        assert_eq!(swc.n_clauses(), 10);
+        assert_eq!(cnf.len(), 10);
+        assert_eq!(swc.enforce_ub(2), Ok(vec![!lit![8]]));
+        assert_eq!(swc.enforce_ub(3), Err(Error::NotEncoded));
+        assert_eq!(swc.enforce_ub(4), Ok(vec![]));
+    }
+
+    #[test]
+    fn ub_empty() {
+        let mut swc = SequentialWeightCounter::default();
+        assert_eq!(swc.enforce_ub(5), Err(Error::EmptyEncoding));
+        swc.encode_ub(0..5, &mut Cnf::new(), &mut BasicVarManager::default())
+            .unwrap();
+        assert_eq!(swc.enforce_ub(5), Err(Error::EmptyEncoding));
+        swc.extend([(lit![0], 1)]);
+        assert_eq!(swc.enforce_ub(5), Ok(vec![]));
+    }
+
+    #[test]
+    fn ub_extend_invalidates() {
+        let mut swc = SequentialWeightCounter::from_iter([(lit![0], 1), (lit![1], 2)]);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        swc.encode_ub(0..2, &mut Cnf::new(), &mut var_manager)
+            .unwrap();
+        assert!(swc.enforce_ub(1).is_ok());
+        swc.extend([(lit![2], 1)]);
+        assert_eq!(swc.enforce_ub(1), Err(Error::NotEncoded));
+    }
+}
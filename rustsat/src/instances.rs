@@ -6,6 +6,7 @@
 use std::{
     any::{Any, TypeId},
     hash::{Hash, Hasher},
+    ops::Range,
 };
 
 use crate::{
@@ -14,7 +15,7 @@ use crate::{
 };
 
 mod sat;
-pub use sat::{Cnf, SatInstance};
+pub use sat::{CheckError, Cnf, SatInstance};
 
 #[cfg(feature = "optimization")]
 mod opt;
@@ -36,6 +37,15 @@ pub trait ManageVars {
     fn new_lit(&mut self) -> Lit {
         self.new_var().pos_lit()
     }
+    /// Uses up the next `n` free variables at once and returns them as a
+    /// contiguous range.
+    fn new_vars(&mut self, n: usize) -> Range<Var> {
+        let start = var![self.n_used()];
+        (0..n).for_each(|_| {
+            self.new_var();
+        });
+        start..var![self.n_used()]
+    }
     /// Gets the used variable with the highest index
     fn max_var(&self) -> Option<Var>;
     /// Increases the next free variable index if the provided variable has a
@@ -106,6 +116,12 @@ impl ManageVars for BasicVarManager {
         v
     }
 
+    fn new_vars(&mut self, n: usize) -> Range<Var> {
+        let start = self.next_var;
+        self.next_var += n as u32;
+        start..self.next_var
+    }
+
     fn max_var(&self) -> Option<Var> {
         if self.next_var == var![0] {
             None
@@ -457,7 +473,8 @@ impl Hash for Box<dyn VarKey> {
 
 #[cfg(test)]
 mod tests {
-    use super::{ManageVars, ObjectVarManager};
+    use super::{BasicVarManager, ManageVars, ObjectVarManager};
+    use crate::var;
 
     #[test]
     fn var_manager_sequence() {
@@ -483,4 +500,21 @@ mod tests {
         assert_ne!(v1, v2);
         assert_eq!(v1, v3);
     }
+
+    #[test]
+    fn var_manager_bulk() {
+        let mut man = BasicVarManager::default();
+        man.new_var();
+        let range = man.new_vars(5);
+        assert_eq!(range, var![1]..var![6]);
+        assert_eq!(man.n_used(), 6);
+        assert_eq!(man.new_var(), var![6]);
+        assert!(man.new_vars(0).is_empty());
+        assert_eq!(man.n_used(), 7);
+        // Default implementation
+        let mut man = ObjectVarManager::default();
+        man.new_var();
+        assert_eq!(man.new_vars(3), var![1]..var![4]);
+        assert_eq!(man.n_used(), 4);
+    }
 }
@@ -11,18 +11,104 @@ use std::{
 };
 use thiserror::Error;
 
-use crate::types::{self, Assignment};
+#[cfg(feature = "multiopt")]
+use crate::instances::MultiOptInstance;
+#[cfg(feature = "optimization")]
+use crate::instances::{Objective, OptInstance};
+use crate::{
+    instances::{Cnf, ManageVars, SatInstance},
+    types::{
+        self,
+        constraints::{CardConstraint, PBConstraint},
+        Assignment,
+    },
+};
 
 pub mod dimacs;
 pub mod opb;
+pub mod smtlib;
 
 /// An error for when a requested objective does not exist
 #[derive(Error, Debug, PartialEq, Eq, Clone, Copy)]
 #[error("the file only has {0} objectives")]
 pub struct ObjNoExist(usize);
 
+/// A format independent view of the constraints and objectives read by the
+/// parsers in this module. The parsers for DIMACS CNF, WCNF, MCNF, and OPB
+/// all return one of the instance types implementing this trait, which allows
+/// for consuming instances without depending on the format they were parsed
+/// from.
+pub trait ConstraintSource {
+    /// Gets the clauses
+    fn clauses(&self) -> &Cnf;
+
+    /// Gets the cardinality constraints
+    fn cards(&self) -> &[CardConstraint];
+
+    /// Gets the pseudo-boolean constraints
+    fn pbs(&self) -> &[PBConstraint];
+
+    /// Gets the objectives. Defaults to no objectives for decision instances.
+    #[cfg(feature = "optimization")]
+    fn objectives(&self) -> &[Objective] {
+        &[]
+    }
+}
+
+impl<VM: ManageVars> ConstraintSource for SatInstance<VM> {
+    fn clauses(&self) -> &Cnf {
+        &self.cnf
+    }
+
+    fn cards(&self) -> &[CardConstraint] {
+        &self.cards
+    }
+
+    fn pbs(&self) -> &[PBConstraint] {
+        &self.pbs
+    }
+}
+
+#[cfg(feature = "optimization")]
+impl<VM: ManageVars> ConstraintSource for OptInstance<VM> {
+    fn clauses(&self) -> &Cnf {
+        self.constrs.clauses()
+    }
+
+    fn cards(&self) -> &[CardConstraint] {
+        self.constrs.cards()
+    }
+
+    fn pbs(&self) -> &[PBConstraint] {
+        self.constrs.pbs()
+    }
+
+    fn objectives(&self) -> &[Objective] {
+        std::slice::from_ref(&self.obj)
+    }
+}
+
+#[cfg(feature = "multiopt")]
+impl<VM: ManageVars> ConstraintSource for MultiOptInstance<VM> {
+    fn clauses(&self) -> &Cnf {
+        self.constrs.clauses()
+    }
+
+    fn cards(&self) -> &[CardConstraint] {
+        self.constrs.cards()
+    }
+
+    fn pbs(&self) -> &[PBConstraint] {
+        self.constrs.pbs()
+    }
+
+    fn objectives(&self) -> &[Objective] {
+        &self.objs
+    }
+}
+
 /// Opens a reader for the file at Path.
-/// With feature `compression` supports bzip2 and gzip compression.
+/// With feature `compression` supports bzip2, gzip, and xz compression.
 pub fn open_compressed_uncompressed_read<P: AsRef<Path>>(
     path: P,
 ) -> Result<Box<dyn io::BufRead>, io::Error> {
@@ -50,7 +136,7 @@ pub fn open_compressed_uncompressed_read<P: AsRef<Path>>(
 }
 
 /// Opens a writer for the file at Path.
-/// With feature `compression` supports bzip2 and gzip compression.
+/// With feature `compression` supports bzip2, gzip, and xz compression.
 pub fn open_compressed_uncompressed_write<P: AsRef<Path>>(
     path: P,
 ) -> Result<Box<dyn io::Write>, io::Error> {
@@ -157,6 +243,35 @@ mod tests {
 
     use super::{parse_sat_solver_output, SatSolverOutputError, SolverOutput};
 
+    #[cfg(feature = "optimization")]
+    #[test]
+    fn constraint_source_formats_equal() {
+        use super::{dimacs, open_compressed_uncompressed_read, ConstraintSource};
+        use crate::instances::OptInstance;
+
+        fn constraints<CS: ConstraintSource>(
+            source: &CS,
+        ) -> (Vec<crate::types::Clause>, usize, usize, usize) {
+            let mut clauses: Vec<_> = source.clauses().iter().cloned().collect();
+            clauses.sort_unstable();
+            (
+                clauses,
+                source.cards().len(),
+                source.pbs().len(),
+                source.objectives().len(),
+            )
+        }
+
+        let reader = open_compressed_uncompressed_read("./data/small.wcnf").unwrap();
+        let post22: OptInstance = dimacs::parse_wcnf_with_idx(reader, 0).unwrap();
+        let reader = open_compressed_uncompressed_read("./data/small-pre22.wcnf").unwrap();
+        let pre22: OptInstance = dimacs::parse_wcnf_with_idx(reader, 0).unwrap();
+        assert_eq!(constraints(&post22), constraints(&pre22));
+        assert_eq!(post22.objectives(), pre22.objectives());
+        assert_eq!(post22.clauses().len(), 3);
+        assert_eq!(post22.objectives()[0].n_lits(), 4);
+    }
+
     #[test]
     fn parse_solver_output_sat() {
         let ground_truth = SolverOutput::Sat(Assignment::from(vec![
@@ -177,15 +177,43 @@ where
 {
     let mut inst = SatInstance::<VM>::new();
     let mut buf = String::new();
+    // Literals of a clause that is continued on the next line
+    let mut pending: Option<Clause> = None;
     while reader.read_line(&mut buf)? > 0 {
-        let (_, opt_clause) = parse_cnf_line(&buf)
-            .map_err(|e| e.to_owned())
-            .with_context(|| format!("failed to parse cnf line '{}'", buf))?;
-        if let Some(clause) = opt_clause {
-            inst.add_clause(clause)
+        let mut input = buf.as_str();
+        // A line can contain multiple clauses
+        loop {
+            let (rest, opt_clause) = parse_cnf_line(input)
+                .map_err(|e| e.to_owned())
+                .with_context(|| format!("failed to parse cnf line '{}'", buf))?;
+            let Some(clause) = opt_clause else {
+                break;
+            };
+            let clause = match pending.take() {
+                Some(mut pend) => {
+                    pend.extend(clause);
+                    pend
+                }
+                None => clause,
+            };
+            let terminated =
+                input[..input.len() - rest.len()].split_whitespace().last() == Some("0");
+            if terminated {
+                inst.add_clause(clause);
+            } else if !clause.is_empty() {
+                pending = Some(clause);
+            }
+            if rest.trim().is_empty() {
+                break;
+            }
+            input = rest;
         }
         buf.clear();
     }
+    // Tolerate a missing `0` after the last clause
+    if let Some(clause) = pending {
+        inst.add_clause(clause);
+    }
     #[cfg(feature = "optimization")]
     {
         Ok((inst, vec![]))
@@ -1022,6 +1050,25 @@ mod tests {
         assert_eq!(parsed_inst, (true_inst, vec![]));
     }
 
+    #[test]
+    fn parse_cnf_clause_layout() {
+        let data = "p cnf 5 4\n1 2 0 -1\n3\n 0\n4 0 5 0";
+        let reader = Cursor::new(data);
+
+        let parsed_inst = parse_dimacs(reader).unwrap();
+
+        let mut true_inst: SatInstance = SatInstance::new();
+        true_inst.add_clause(clause![ipasir_lit![1], ipasir_lit![2]]);
+        true_inst.add_clause(clause![ipasir_lit![-1], ipasir_lit![3]]);
+        true_inst.add_clause(clause![ipasir_lit![4]]);
+        true_inst.add_clause(clause![ipasir_lit![5]]);
+
+        #[cfg(not(feature = "optimization"))]
+        assert_eq!(parsed_inst, true_inst);
+        #[cfg(feature = "optimization")]
+        assert_eq!(parsed_inst, (true_inst, vec![]));
+    }
+
     #[cfg(feature = "optimization")]
     #[test]
     fn parse_wcnf_pre22() {
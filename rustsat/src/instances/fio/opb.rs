@@ -11,7 +11,7 @@
 use crate::{
     instances::{ManageVars, SatInstance},
     types::{
-        constraints::{CardConstraint, PBConstraint},
+        constraints::{CardConstraint, Comparator, PBConstraint},
         Clause, Lit, Var,
     },
 };
@@ -63,16 +63,12 @@ impl Default for Options {
 /// Possible relational operators
 #[derive(Debug, PartialEq, Eq)]
 enum OpbOperator {
-    /// <=
-    LE,
-    /// >=
-    GE,
+    /// <=, >= or =
+    Cmp(Comparator),
     /// <
     LT,
     /// >
     GT,
-    /// =
-    EQ,
 }
 
 /// Possible parsing results for comment or constraint or objective
@@ -219,16 +215,10 @@ fn operator(input: &str) -> IResult<&str, OpbOperator> {
     let (input, op_str) = alt((tag("<="), tag(">="), tag("<"), tag(">"), tag("=")))(input)?;
     Ok((
         input,
-        if op_str == "<=" {
-            OpbOperator::LE
-        } else if op_str == ">=" {
-            OpbOperator::GE
-        } else if op_str == "<" {
-            OpbOperator::LT
-        } else if op_str == ">" {
-            OpbOperator::GT
-        } else {
-            OpbOperator::EQ
+        match Comparator::from_opb_str(op_str) {
+            Some(cmp) => OpbOperator::Cmp(cmp),
+            None if op_str == "<" => OpbOperator::LT,
+            None => OpbOperator::GT,
         },
     ))
 }
@@ -287,14 +277,13 @@ fn constraint(input: &str, opts: Options) -> IResult<&str, PBConstraint> {
             cut(opb_ending),
         )),
         |(wls, op, _, b, _)| {
-            let lits = wls.into_iter();
-            Ok::<_, ()>(match op {
-                OpbOperator::LE => PBConstraint::new_ub(lits, b),
-                OpbOperator::GE => PBConstraint::new_lb(lits, b),
-                OpbOperator::LT => PBConstraint::new_ub(lits, b + 1),
-                OpbOperator::GT => PBConstraint::new_lb(lits, b + 1),
-                OpbOperator::EQ => PBConstraint::new_eq(lits, b),
-            })
+            // Strict operators are converted by adjusting the bound
+            let (cmp, b) = match op {
+                OpbOperator::Cmp(cmp) => (cmp, b),
+                OpbOperator::LT => (Comparator::Leq, b - 1),
+                OpbOperator::GT => (Comparator::Geq, b + 1),
+            };
+            Ok::<_, ()>(PBConstraint::new(wls, cmp, b))
         },
     )(input)
 }
@@ -495,7 +484,7 @@ fn write_card<W: Write>(
 ) -> Result<(), io::Error> {
     let mut iter_a;
     let mut iter_b;
-    let neg_lit = |l: &Lit| !*l;
+    let neg_lit = |l: &Lit| !l;
     if opts.no_negated_lits {
         let (lits, bound, op): (&mut dyn Iterator<Item = Lit>, _, _) = match card {
             CardConstraint::UB(constr) => {
@@ -503,17 +492,17 @@ fn write_card<W: Write>(
                 let bound = lits.len() as isize - *bound as isize;
                 // Flip operator by negating literals
                 iter_a = lits.iter().map(neg_lit);
-                (&mut iter_a, bound, ">=")
+                (&mut iter_a, bound, Comparator::Geq)
             }
             CardConstraint::LB(constr) => {
                 let (lits, bound) = constr.decompose_ref();
                 iter_b = lits.iter().copied();
-                (&mut iter_b, *bound as isize, ">=")
+                (&mut iter_b, *bound as isize, Comparator::Geq)
             }
             CardConstraint::EQ(constr) => {
                 let (lits, bound) = constr.decompose_ref();
                 iter_b = lits.iter().copied();
-                (&mut iter_b, *bound as isize, "=")
+                (&mut iter_b, *bound as isize, Comparator::Eq)
             }
         };
         let mut offset = 0;
@@ -525,7 +514,7 @@ fn write_card<W: Write>(
                 write!(writer, "-1 x{} ", l.vidx32() + opts.first_var_idx)?;
             }
         }
-        writeln!(writer, "{} {};", op, bound - offset)
+        writeln!(writer, "{} {};", op.as_opb_str(), bound - offset)
     } else {
         let (lits, bound, op): (&mut dyn Iterator<Item = Lit>, _, _) = match card {
             CardConstraint::UB(constr) => {
@@ -533,17 +522,17 @@ fn write_card<W: Write>(
                 let bound = lits.len() as isize - *bound as isize;
                 // Flip operator by negating literals
                 iter_a = lits.iter().map(neg_lit);
-                (&mut iter_a, bound, ">=")
+                (&mut iter_a, bound, Comparator::Geq)
             }
             CardConstraint::LB(constr) => {
                 let (lits, bound) = constr.decompose_ref();
                 iter_b = lits.iter().copied();
-                (&mut iter_b, *bound as isize, ">=")
+                (&mut iter_b, *bound as isize, Comparator::Geq)
             }
             CardConstraint::EQ(constr) => {
                 let (lits, bound) = constr.decompose_ref();
                 iter_b = lits.iter().copied();
-                (&mut iter_b, *bound as isize, "=")
+                (&mut iter_b, *bound as isize, Comparator::Eq)
             }
         };
         for l in lits {
@@ -553,7 +542,7 @@ fn write_card<W: Write>(
                 write!(writer, "1 ~x{} ", l.vidx32() + opts.first_var_idx)?;
             }
         }
-        writeln!(writer, "{} {};", op, bound)
+        writeln!(writer, "{} {};", op.as_opb_str(), bound)
     }
 }
 
@@ -561,7 +550,7 @@ fn write_card<W: Write>(
 fn write_pb<W: Write>(writer: &mut W, pb: &PBConstraint, opts: Options) -> Result<(), io::Error> {
     let mut iter_a;
     let mut iter_b;
-    let neg_lit = |(l, w): &(Lit, usize)| (!*l, *w);
+    let neg_lit = |(l, w): &(Lit, usize)| (!l, *w);
     if opts.no_negated_lits {
         let (lits, bound, op): (&mut dyn Iterator<Item = (Lit, usize)>, _, _) = match pb {
             PBConstraint::UB(constr) => {
@@ -569,17 +558,17 @@ fn write_pb<W: Write>(writer: &mut W, pb: &PBConstraint, opts: Options) -> Resul
                 let weight_sum = lits.iter().fold(0, |sum, (_, w)| sum + w);
                 // Flip operator by negating literals
                 iter_a = lits.iter().map(neg_lit);
-                (&mut iter_a, weight_sum as isize - bound, ">=")
+                (&mut iter_a, weight_sum as isize - bound, Comparator::Geq)
             }
             PBConstraint::LB(constr) => {
                 let (lits, bound) = constr.decompose_ref();
                 iter_b = lits.iter().copied();
-                (&mut iter_b, *bound, ">=")
+                (&mut iter_b, *bound, Comparator::Geq)
             }
             PBConstraint::EQ(constr) => {
                 let (lits, bound) = constr.decompose_ref();
                 iter_b = lits.iter().copied();
-                (&mut iter_b, *bound, "=")
+                (&mut iter_b, *bound, Comparator::Eq)
             }
         };
         let mut offset: isize = 0;
@@ -593,7 +582,7 @@ fn write_pb<W: Write>(writer: &mut W, pb: &PBConstraint, opts: Options) -> Resul
                 write!(writer, "{} x{} ", -w, l.vidx32() + opts.first_var_idx)?;
             }
         }
-        writeln!(writer, "{} {};", op, bound - offset)
+        writeln!(writer, "{} {};", op.as_opb_str(), bound - offset)
     } else {
         let (lits, bound, op): (&mut dyn Iterator<Item = (Lit, usize)>, _, _) = match pb {
             PBConstraint::UB(constr) => {
@@ -601,17 +590,17 @@ fn write_pb<W: Write>(writer: &mut W, pb: &PBConstraint, opts: Options) -> Resul
                 let weight_sum = lits.iter().fold(0, |sum, (_, w)| sum + w);
                 // Flip operator by negating literals
                 iter_a = lits.iter().map(neg_lit);
-                (&mut iter_a, weight_sum as isize - bound, ">=")
+                (&mut iter_a, weight_sum as isize - bound, Comparator::Geq)
             }
             PBConstraint::LB(constr) => {
                 let (lits, bound) = constr.decompose_ref();
                 iter_b = lits.iter().copied();
-                (&mut iter_b, *bound, ">=")
+                (&mut iter_b, *bound, Comparator::Geq)
             }
             PBConstraint::EQ(constr) => {
                 let (lits, bound) = constr.decompose_ref();
                 iter_b = lits.iter().copied();
-                (&mut iter_b, *bound, "=")
+                (&mut iter_b, *bound, Comparator::Eq)
             }
         };
         for (l, w) in lits {
@@ -621,7 +610,7 @@ fn write_pb<W: Write>(writer: &mut W, pb: &PBConstraint, opts: Options) -> Resul
                 write!(writer, "{} ~x{} ", w, l.vidx32() + opts.first_var_idx)?;
             }
         }
-        writeln!(writer, "{} {};", op, bound)
+        writeln!(writer, "{} {};", op.as_opb_str(), bound)
     }
 }
 
@@ -678,7 +667,7 @@ mod test {
         clause,
         instances::{BasicVarManager, SatInstance},
         lit,
-        types::constraints::{CardConstraint, PBConstraint},
+        types::constraints::{CardConstraint, Comparator, PBConstraint},
         var,
     };
     use nom::error::{Error as NomError, ErrorKind};
@@ -749,11 +738,20 @@ mod test {
 
     #[test]
     fn parse_operator() {
-        assert_eq!(operator("<= test"), Ok((" test", OpbOperator::LE)));
-        assert_eq!(operator(">= test"), Ok((" test", OpbOperator::GE)));
+        assert_eq!(
+            operator("<= test"),
+            Ok((" test", OpbOperator::Cmp(Comparator::Leq)))
+        );
+        assert_eq!(
+            operator(">= test"),
+            Ok((" test", OpbOperator::Cmp(Comparator::Geq)))
+        );
         assert_eq!(operator("< test"), Ok((" test", OpbOperator::LT)));
         assert_eq!(operator("> test"), Ok((" test", OpbOperator::GT)));
-        assert_eq!(operator("= test"), Ok((" test", OpbOperator::EQ)));
+        assert_eq!(
+            operator("= test"),
+            Ok((" test", OpbOperator::Cmp(Comparator::Eq)))
+        );
     }
 
     #[test]
@@ -817,6 +815,20 @@ mod test {
         }
     }
 
+    #[test]
+    fn parse_strict_constraint() {
+        let (_, constr) = constraint("1 x1 1 x2 < 2;", Options::default()).unwrap();
+        assert_eq!(
+            constr,
+            PBConstraint::new_ub([(lit![0], 1), (lit![1], 1)], 1)
+        );
+        let (_, constr) = constraint("1 x1 1 x2 > 0;", Options::default()).unwrap();
+        assert_eq!(
+            constr,
+            PBConstraint::new_lb([(lit![0], 1), (lit![1], 1)], 1)
+        );
+    }
+
     #[cfg(feature = "optimization")]
     #[test]
     fn parse_objective() {
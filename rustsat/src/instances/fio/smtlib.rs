@@ -0,0 +1,308 @@
+//! # Writing SMT-LIB 2 Files
+//!
+//! Internal module containing functions for writing constraints in
+//! SMT-LIB 2 format, e.g., to cross-check encodings with SMT solvers.
+//! Variable `var![i]` is declared as the Boolean constant `x<i+1>` and
+//! pseudo-boolean sums are expressed in linear integer arithmetic by
+//! converting literals with `ite`.
+//!
+//! ## References
+//!
+//! - [SMT-LIB 2.6](https://smtlib.cs.uiowa.edu/papers/smt-lib-reference-v2.6-r2021-05-12.pdf)
+
+use crate::{
+    instances::{ManageVars, SatInstance},
+    types::{
+        constraints::{CardConstraint, PBConstraint},
+        Clause, Lit, Var,
+    },
+};
+use std::io::{self, Write};
+
+/// Writes a satisfiability instance to an SMT-LIB 2 file
+pub fn write_sat<W, VM>(writer: &mut W, inst: &SatInstance<VM>) -> Result<(), io::Error>
+where
+    W: Write,
+    VM: ManageVars,
+{
+    writeln!(writer, "; SMT-LIB file written by RustSAT")?;
+    writeln!(writer, "; {} clauses", inst.n_clauses())?;
+    writeln!(writer, "; {} cardinality constraints", inst.cards.len())?;
+    writeln!(writer, "; {} pseudo-boolean constraints", inst.pbs.len())?;
+    writeln!(writer, "(set-logic QF_LIA)")?;
+    if let Some(max_var) = inst.var_manager.max_var() {
+        write_declarations(writer, max_var)?;
+    }
+    inst.cnf
+        .iter()
+        .try_for_each(|cl| write_clause(writer, cl))?;
+    inst.cards
+        .iter()
+        .try_for_each(|card| write_card(writer, card))?;
+    inst.pbs.iter().try_for_each(|pb| write_pb(writer, pb))?;
+    writeln!(writer, "(check-sat)")?;
+    writer.flush()
+}
+
+/// Writes the declarations of all variables up to `max_var`
+pub fn write_declarations<W: Write>(writer: &mut W, max_var: Var) -> Result<(), io::Error> {
+    (0..=max_var.idx32()).try_for_each(|idx| writeln!(writer, "(declare-const x{} Bool)", idx + 1))
+}
+
+/// Writes an assertion that the weighted sum of the given literals is at
+/// most `ub`. Together with [`crate::encodings::IterWeightedInputs`], this
+/// writes the semantics of a pseudo-boolean encoding for a given upper bound.
+pub fn write_ub<W, LI>(writer: &mut W, lits: LI, ub: usize) -> Result<(), io::Error>
+where
+    W: Write,
+    LI: IntoIterator<Item = (Lit, usize)>,
+{
+    write!(writer, "(assert (<= ")?;
+    write_sum(writer, lits)?;
+    writeln!(writer, " {}))", ub)
+}
+
+/// Writes a clause as an assertion
+pub fn write_clause<W: Write>(writer: &mut W, clause: &Clause) -> Result<(), io::Error> {
+    match clause.len() {
+        0 => writeln!(writer, "(assert false)"),
+        1 => {
+            write!(writer, "(assert ")?;
+            write_lit(writer, clause[0])?;
+            writeln!(writer, ")")
+        }
+        _ => {
+            write!(writer, "(assert (or")?;
+            clause.iter().try_for_each(|&l| {
+                write!(writer, " ")?;
+                write_lit(writer, l)
+            })?;
+            writeln!(writer, "))")
+        }
+    }
+}
+
+/// Writes a cardinality constraint as an assertion
+pub fn write_card<W: Write>(writer: &mut W, card: &CardConstraint) -> Result<(), io::Error> {
+    let (lits, bound, op) = match card {
+        CardConstraint::UB(constr) => {
+            let (lits, bound) = constr.decompose_ref();
+            (lits, bound, "<=")
+        }
+        CardConstraint::LB(constr) => {
+            let (lits, bound) = constr.decompose_ref();
+            (lits, bound, ">=")
+        }
+        CardConstraint::EQ(constr) => {
+            let (lits, bound) = constr.decompose_ref();
+            (lits, bound, "=")
+        }
+    };
+    write!(writer, "(assert ({} ", op)?;
+    write_sum(writer, lits.iter().map(|&l| (l, 1)))?;
+    writeln!(writer, " {}))", bound)
+}
+
+/// Writes a pseudo-boolean constraint as an assertion
+pub fn write_pb<W: Write>(writer: &mut W, pb: &PBConstraint) -> Result<(), io::Error> {
+    let (lits, bound, op) = match pb {
+        PBConstraint::UB(constr) => {
+            let (lits, bound) = constr.decompose_ref();
+            (lits, *bound, "<=")
+        }
+        PBConstraint::LB(constr) => {
+            let (lits, bound) = constr.decompose_ref();
+            (lits, *bound, ">=")
+        }
+        PBConstraint::EQ(constr) => {
+            let (lits, bound) = constr.decompose_ref();
+            (lits, *bound, "=")
+        }
+    };
+    write!(writer, "(assert ({} ", op)?;
+    write_sum(writer, lits.iter().copied())?;
+    if bound < 0 {
+        // Negative numerals are not literals in SMT-LIB
+        writeln!(writer, " (- {})))", bound.unsigned_abs())
+    } else {
+        writeln!(writer, " {}))", bound)
+    }
+}
+
+/// Writes a literal as a Boolean term
+fn write_lit<W: Write>(writer: &mut W, lit: Lit) -> Result<(), io::Error> {
+    if lit.is_pos() {
+        write!(writer, "x{}", lit.vidx32() + 1)
+    } else {
+        write!(writer, "(not x{})", lit.vidx32() + 1)
+    }
+}
+
+/// Writes a weighted literal as an integer term
+fn write_term<W: Write>(writer: &mut W, (lit, weight): (Lit, usize)) -> Result<(), io::Error> {
+    let (t, f) = if lit.is_pos() { (1, 0) } else { (0, 1) };
+    if weight == 1 {
+        write!(writer, "(ite x{} {} {})", lit.vidx32() + 1, t, f)
+    } else {
+        write!(
+            writer,
+            "(* {} (ite x{} {} {}))",
+            weight,
+            lit.vidx32() + 1,
+            t,
+            f
+        )
+    }
+}
+
+/// Writes a sum of weighted literals as an integer term. Since `+` requires
+/// at least two arguments, empty sums and sums with one term are special
+/// cased.
+fn write_sum<W, LI>(writer: &mut W, lits: LI) -> Result<(), io::Error>
+where
+    W: Write,
+    LI: IntoIterator<Item = (Lit, usize)>,
+{
+    let mut lits = lits.into_iter().peekable();
+    let Some(first) = lits.next() else {
+        return write!(writer, "0");
+    };
+    if lits.peek().is_none() {
+        return write_term(writer, first);
+    }
+    write!(writer, "(+ ")?;
+    write_term(writer, first)?;
+    lits.try_for_each(|wl| {
+        write!(writer, " ")?;
+        write_term(writer, wl)
+    })?;
+    write!(writer, ")")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_pb, write_sat, write_ub};
+    use crate::{
+        clause,
+        encodings::{pb::GeneralizedTotalizer, IterWeightedInputs},
+        instances::SatInstance,
+        lit,
+        types::constraints::{CardConstraint, PBConstraint},
+    };
+
+    /// S-expressions for checking that the written SMT-LIB is well-formed
+    #[derive(Debug, PartialEq, Eq)]
+    enum SExpr {
+        Atom(String),
+        List(Vec<SExpr>),
+    }
+
+    fn tokenize(input: &str) -> Vec<String> {
+        input
+            .lines()
+            .filter(|line| !line.starts_with(';'))
+            .flat_map(|line| {
+                line.replace('(', " ( ")
+                    .replace(')', " ) ")
+                    .split_whitespace()
+                    .map(String::from)
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn parse(tokens: &[String]) -> Vec<SExpr> {
+        let mut stack = vec![vec![]];
+        for tok in tokens {
+            match tok.as_str() {
+                "(" => stack.push(vec![]),
+                ")" => {
+                    let list = stack.pop().unwrap();
+                    stack
+                        .last_mut()
+                        .expect("unbalanced parentheses")
+                        .push(SExpr::List(list));
+                }
+                atom => stack.last_mut().unwrap().push(SExpr::Atom(atom.into())),
+            }
+        }
+        assert_eq!(stack.len(), 1, "unbalanced parentheses");
+        stack.pop().unwrap()
+    }
+
+    fn atom(s: &str) -> SExpr {
+        SExpr::Atom(s.into())
+    }
+
+    fn term(w: usize, var: &str, t: &str, f: &str) -> SExpr {
+        let ite = SExpr::List(vec![atom("ite"), atom(var), atom(t), atom(f)]);
+        if w == 1 {
+            return ite;
+        }
+        SExpr::List(vec![atom("*"), atom(&w.to_string()), ite])
+    }
+
+    #[test]
+    fn gte_ub_round_trip() {
+        let gte = GeneralizedTotalizer::from_iter([(lit![0], 5), (!lit![1], 3), (lit![2], 1)]);
+        let mut buf = Vec::new();
+        write_ub(&mut buf, gte.iter(), 6).unwrap();
+        let exprs = parse(&tokenize(std::str::from_utf8(&buf).unwrap()));
+        let mut terms = vec![atom("+")];
+        terms.extend(
+            [
+                (5, "x1", "1", "0"),
+                (3, "x2", "0", "1"),
+                (1, "x3", "1", "0"),
+            ]
+            .into_iter()
+            .map(|(w, v, t, f)| term(w, v, t, f)),
+        );
+        let SExpr::List(assert) = &exprs[0] else {
+            panic!("expected assertion")
+        };
+        assert_eq!(assert[0], atom("assert"));
+        let SExpr::List(leq) = &assert[1] else {
+            panic!("expected constraint")
+        };
+        assert_eq!(leq[0], atom("<="));
+        let SExpr::List(sum) = &leq[1] else {
+            panic!("expected sum")
+        };
+        // Order of inputs is not guaranteed
+        assert_eq!(sum.len(), terms.len());
+        assert!(terms.iter().all(|t| sum.contains(t)));
+        assert_eq!(leq[2], atom("6"));
+    }
+
+    #[test]
+    fn pb_negative_weight() {
+        let pb = PBConstraint::new_lb([(lit![0], 2), (lit![1], -3)], -1);
+        let mut buf = Vec::new();
+        write_pb(&mut buf, &pb).unwrap();
+        let exprs = parse(&tokenize(std::str::from_utf8(&buf).unwrap()));
+        assert_eq!(exprs.len(), 1);
+    }
+
+    #[test]
+    fn sat_well_formed() {
+        let mut inst: SatInstance = SatInstance::new();
+        inst.add_clause(clause![lit![0], !lit![1]]);
+        inst.add_unit(lit![2]);
+        inst.add_card_constr(CardConstraint::new_ub([lit![0], lit![1], lit![2]], 2));
+        inst.add_pb_constr(PBConstraint::new_eq([(lit![0], 3), (lit![3], 1)], 3));
+        let mut buf = Vec::new();
+        write_sat(&mut buf, &inst).unwrap();
+        let exprs = parse(&tokenize(std::str::from_utf8(&buf).unwrap()));
+        let n_decls = exprs
+            .iter()
+            .filter(|e| matches!(e, SExpr::List(l) if l[0] == atom("declare-const")))
+            .count();
+        let n_asserts = exprs
+            .iter()
+            .filter(|e| matches!(e, SExpr::List(l) if l[0] == atom("assert")))
+            .count();
+        assert_eq!(n_decls, 4);
+        assert_eq!(n_asserts, 4);
+    }
+}
@@ -494,7 +494,7 @@ impl<VM: ManageVars + Default> MultiOptInstance<VM> {
 
     /// Parses an OPB instance from a file path. For more details see
     /// [`MultiOptInstance::from_opb`]. With feature `compression` supports
-    /// bzip2 and gzip compression, detected by the file extension.
+    /// bzip2, gzip, and xz compression, detected by the file extension.
     pub fn from_opb_path<P: AsRef<Path>>(path: P, opts: fio::opb::Options) -> anyhow::Result<Self> {
         let reader = fio::open_compressed_uncompressed_read(path)?;
         Self::from_opb(reader, opts)
@@ -1378,7 +1378,7 @@ impl<VM: ManageVars + Default> OptInstance<VM> {
 
     /// Parses a DIMACS instance from a file path. For more details see
     /// [`OptInstance::from_dimacs`]. With feature `compression` supports
-    /// bzip2 and gzip compression, detected by the file extension.
+    /// bzip2, gzip, and xz compression, detected by the file extension.
     pub fn from_dimacs_path<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
         let reader = fio::open_compressed_uncompressed_read(path)?;
         Self::from_dimacs(reader)
@@ -1386,7 +1386,7 @@ impl<VM: ManageVars + Default> OptInstance<VM> {
 
     /// Parses a DIMACS instance from a file path. For more details see
     /// [`OptInstance::from_dimacs_with_idx`]. With feature `compression` supports
-    /// bzip2 and gzip compression, detected by the file extension.
+    /// bzip2, gzip, and xz compression, detected by the file extension.
     pub fn from_dimacs_path_with_idx<P: AsRef<Path>>(
         path: P,
         obj_idx: usize,
@@ -1419,7 +1419,7 @@ impl<VM: ManageVars + Default> OptInstance<VM> {
 
     /// Parses an OPB instance from a file path. For more details see
     /// [`OptInstance::from_opb`]. With feature `compression` supports
-    /// bzip2 and gzip compression, detected by the file extension.
+    /// bzip2, gzip, and xz compression, detected by the file extension.
     pub fn from_opb_path<P: AsRef<Path>>(path: P, opts: fio::opb::Options) -> anyhow::Result<Self> {
         let reader = fio::open_compressed_uncompressed_read(path)?;
         Self::from_opb(reader, opts)
@@ -1428,7 +1428,7 @@ impl<VM: ManageVars + Default> OptInstance<VM> {
     /// Parses an OPB instance from a file path, selecting the objective with
     /// index `obj_idx` if multiple are available. The index starts at 0. For
     /// more details see [`OptInstance::from_opb`]. With feature
-    /// `compression` supports bzip2 and gzip compression, detected by the file
+    /// `compression` supports bzip2, gzip, and xz compression, detected by the file
     /// extension.
     pub fn from_opb_path_with_idx<P: AsRef<Path>>(
         path: P,
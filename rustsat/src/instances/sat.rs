@@ -8,13 +8,14 @@ use crate::{
     lit,
     types::{
         constraints::{CardConstraint, PBConstraint},
-        Assignment, Clause, Lit, Var,
+        Assignment, Clause, Lit, RsHashMap, TernaryVal, Var,
     },
     utils::LimitedIter,
     RequiresClausal,
 };
 
 use anyhow::Context;
+use thiserror::Error;
 
 use super::{
     fio::{self, dimacs::CnfLine},
@@ -36,6 +37,18 @@ impl std::fmt::Debug for Cnf {
     }
 }
 
+/// Formats the CNF as DIMACS clauses without a header, one clause per line.
+/// Use [`Cnf::write_dimacs`] for a full DIMACS CNF file.
+impl std::fmt::Display for Cnf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.iter().try_for_each(|cl| {
+            cl.iter()
+                .try_for_each(|l| write!(f, "{} ", l.to_ipasir()))?;
+            writeln!(f, "0")
+        })
+    }
+}
+
 impl Cnf {
     /// Creates a new [`Cnf`]
     pub fn new() -> Cnf {
@@ -132,10 +145,26 @@ impl Cnf {
     /// Joins the current CNF with another one. Like [`Cnf::extend`] but
     /// consumes the object and returns a new object.
     pub fn join(mut self, other: Cnf) -> Cnf {
-        self.extend(other);
+        self.extend_from_cnf(other);
         self
     }
 
+    /// Moves all clauses of another CNF to the end of this one. Other than
+    /// [`Cnf::extend`], this reserves the required capacity upfront.
+    pub fn extend_from_cnf(&mut self, mut other: Cnf) {
+        self.clauses.reserve(other.len());
+        self.clauses.append(&mut other.clauses);
+    }
+
+    /// Concatenates multiple CNFs into one. The total number of clauses is
+    /// computed first so that memory is only allocated once.
+    pub fn concat<I: IntoIterator<Item = Cnf>>(cnfs: I) -> Cnf {
+        let cnfs: Vec<Cnf> = cnfs.into_iter().collect();
+        let mut joined = Cnf::with_capacity(cnfs.iter().fold(0, |len, cnf| len + cnf.len()));
+        cnfs.into_iter().for_each(|cnf| joined.extend_from_cnf(cnf));
+        joined
+    }
+
     /// Returns an iterator over references to the clauses
     pub fn iter(&self) -> std::slice::Iter<'_, Clause> {
         self.clauses.iter()
@@ -162,10 +191,21 @@ impl Cnf {
 
     /// Sanitizes the CNF by removing tautologies, removing redundant literals,
     /// etc.
-    pub fn sanitize(self) -> Self {
-        Self {
-            clauses: self.into_iter().filter_map(|cl| cl.sanitize()).collect(),
-        }
+    pub fn sanitize(mut self) -> Self {
+        self.sanitize_in_place();
+        self
+    }
+
+    /// Sanitizes the CNF in place like [`Cnf::sanitize`] and returns the
+    /// number of removed tautological clauses. Unit clauses and the empty
+    /// clause are kept.
+    pub fn sanitize_in_place(&mut self) -> usize {
+        let n_before = self.len();
+        self.clauses = std::mem::take(&mut self.clauses)
+            .into_iter()
+            .filter_map(Clause::sanitize)
+            .collect();
+        n_before - self.len()
     }
 
     #[cfg(feature = "rand")]
@@ -198,13 +238,72 @@ impl Cnf {
         self.add_clause(clause![lit1, lit2, lit3])
     }
 
+    /// Evaluates the CNF under a (partial) assignment. Returns
+    /// [`TernaryVal::False`] if any clause is falsified, [`TernaryVal::True`]
+    /// if all clauses are satisfied, and [`TernaryVal::DontCare`] otherwise.
+    pub fn evaluate(&self, assignment: &Assignment) -> TernaryVal {
+        self.iter()
+            .fold(TernaryVal::True, |val, cl| val & cl.evaluate(assignment))
+    }
+
+    /// Checks the CNF against assignments that are known to be models and
+    /// assignments that are known to not be models. Every assignment in `sat`
+    /// must satisfy all clauses and every assignment in `unsat` must falsify at
+    /// least one clause.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CheckError`] indicating the first offending assignment.
+    pub fn check_assignments(
+        &self,
+        sat: &[Assignment],
+        unsat: &[Assignment],
+    ) -> Result<(), CheckError> {
+        for (assign_idx, assign) in sat.iter().enumerate() {
+            if let Some(clause_idx) = self.iter().position(|cl| !cl.is_sat(assign)) {
+                return Err(CheckError::ModelViolates {
+                    assignment: assign_idx,
+                    clause: self[clause_idx].clone(),
+                });
+            }
+        }
+        for (assign_idx, assign) in unsat.iter().enumerate() {
+            if !self
+                .iter()
+                .any(|cl| cl.evaluate(assign) == TernaryVal::False)
+            {
+                return Err(CheckError::NonModelSatisfies {
+                    assignment: assign_idx,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Gets the variable with the highest index referenced in any clause, or
+    /// [`None`] if no clause contains a literal
+    #[must_use]
+    pub fn max_var(&self) -> Option<Var> {
+        self.iter().flat_map(|cl| cl.iter()).map(Lit::var).max()
+    }
+
+    /// Gets the number of variables needed to represent the CNF, i.e., one
+    /// more than the index of [`Cnf::max_var`]. This can be used as the
+    /// variable count of [`Cnf::write_dimacs`] for a CNF that was not built
+    /// with a variable manager.
+    #[must_use]
+    pub fn n_vars(&self) -> u32 {
+        self.max_var().map_or(0, |var| var.idx32() + 1)
+    }
+
     /// Writes the CNF to a DIMACS CNF file at a path
     pub fn write_dimacs_path<P: AsRef<Path>>(&self, path: P, n_vars: u32) -> Result<(), io::Error> {
         let mut writer = fio::open_compressed_uncompressed_write(path)?;
         self.write_dimacs(&mut writer, n_vars)
     }
 
-    /// Writes the CNF to DIMACS CNF
+    /// Writes the CNF to DIMACS CNF. If no variable manager is available,
+    /// [`Cnf::n_vars`] gives the number of variables for the header.
     ///
     /// # Performance
     ///
@@ -214,6 +313,25 @@ impl Cnf {
     }
 }
 
+/// Errors returned by [`Cnf::check_assignments`]
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CheckError {
+    /// An assignment expected to be a model does not satisfy a clause
+    #[error("model {assignment} does not satisfy clause {clause}")]
+    ModelViolates {
+        /// The index of the assignment in the models
+        assignment: usize,
+        /// The clause that is not satisfied
+        clause: Clause,
+    },
+    /// An assignment expected to not be a model does not falsify any clause
+    #[error("non-model {assignment} does not falsify any clause")]
+    NonModelSatisfies {
+        /// The index of the assignment in the non-models
+        assignment: usize,
+    },
+}
+
 impl CollectClauses for Cnf {
     fn n_clauses(&self) -> usize {
         self.clauses.len()
@@ -275,6 +393,18 @@ impl Extend<Clause> for Cnf {
     }
 }
 
+impl FromIterator<Vec<Lit>> for Cnf {
+    fn from_iter<T: IntoIterator<Item = Vec<Lit>>>(iter: T) -> Self {
+        Self::from_iter(iter.into_iter().map(Clause::from_iter))
+    }
+}
+
+impl Extend<Vec<Lit>> for Cnf {
+    fn extend<Iter: IntoIterator<Item = Vec<Lit>>>(&mut self, iter: Iter) {
+        self.clauses.extend(iter.into_iter().map(Clause::from_iter))
+    }
+}
+
 impl Index<usize> for Cnf {
     type Output = Clause;
 
@@ -644,6 +774,33 @@ impl<VM: ManageVars> SatInstance<VM> {
         self.var_manager.combine(other.var_manager);
     }
 
+    /// Extends the instance by another instance whose variables are shifted
+    /// by `offset`, i.e., variable `x_i` of `other` becomes `x_{i+offset}`.
+    /// The variable manager of `other` is discarded and the shifted
+    /// variables of `other` are marked as used in the variable manager of
+    /// this instance. Choose `offset` larger than the maximum variable of
+    /// this instance to avoid collisions.
+    pub fn append_shifted<VM2: ManageVars>(&mut self, other: SatInstance<VM2>, offset: Var) {
+        let offset = offset.idx32();
+        if let Some(max_var) = other.max_var() {
+            self.var_manager.mark_used(max_var + offset);
+        }
+        self.cnf.extend(
+            other
+                .cnf
+                .into_iter()
+                .map(|cl| cl.into_iter().map(|l| l + offset).collect::<Clause>()),
+        );
+        self.cards.extend(other.cards.into_iter().map(|mut card| {
+            card.iter_mut().for_each(|l| *l = *l + offset);
+            card
+        }));
+        self.pbs.extend(other.pbs.into_iter().map(|mut pb| {
+            pb.iter_mut().for_each(|(l, _)| *l = *l + offset);
+            pb
+        }));
+    }
+
     /// Reindexes all variables in the instance with a reindexing variable manager
     pub fn reindex<R: ReindexVars>(mut self, mut reindexer: R) -> SatInstance<R> {
         self.cnf
@@ -664,6 +821,50 @@ impl<VM: ManageVars> SatInstance<VM> {
         }
     }
 
+    /// Renumbers the variables in the constraints to the dense range starting
+    /// at `var![0]`, keeping their order, and returns the mapping from old to
+    /// new variables. The variable manager is reset to the dense range, i.e.,
+    /// variables not occurring in any constraint are forgotten. Variables
+    /// associated with objects by the variable manager are _not_ renumbered.
+    pub fn compact_vars(&mut self) -> RsHashMap<Var, Var> {
+        let mut used: Vec<Var> = self
+            .cnf
+            .iter()
+            .flat_map(|cl| cl.iter().map(|l| l.var()))
+            .chain(
+                self.cards
+                    .iter()
+                    .flat_map(|card| card.iter().map(|l| l.var())),
+            )
+            .chain(
+                self.pbs
+                    .iter()
+                    .flat_map(|pb| pb.iter().map(|(l, _)| l.var())),
+            )
+            .collect();
+        used.sort_unstable();
+        used.dedup();
+        let map: RsHashMap<Var, Var> = used
+            .iter()
+            .enumerate()
+            .map(|(idx, &v)| (v, Var::new(idx as u32)))
+            .collect();
+        let remap = |l: &mut Lit| *l = Lit::new(map[&l.var()].idx32(), l.is_neg());
+        self.cnf
+            .iter_mut()
+            .for_each(|cl| cl.iter_mut().for_each(remap));
+        self.cards
+            .iter_mut()
+            .for_each(|card| card.iter_mut().for_each(remap));
+        self.pbs
+            .iter_mut()
+            .for_each(|pb| pb.iter_mut().for_each(|(l, _)| remap(l)));
+        let n_vars = Var::new(used.len() as u32);
+        self.var_manager.forget_from(n_vars);
+        self.var_manager.increase_next_free(n_vars);
+        map
+    }
+
     #[cfg(feature = "rand")]
     /// Randomly shuffles the order of constraints.
     pub fn shuffle(mut self) -> Self {
@@ -811,6 +1012,22 @@ impl<VM: ManageVars> SatInstance<VM> {
         fio::opb::write_sat(writer, self, opts)
     }
 
+    /// Writes the instance to an SMT-LIB 2 file at a path
+    pub fn write_smtlib_path<P: AsRef<Path>>(&self, path: P) -> Result<(), io::Error> {
+        let mut writer = fio::open_compressed_uncompressed_write(path)?;
+        self.write_smtlib(&mut writer)
+    }
+
+    /// Writes the instance to an SMT-LIB 2 file, e.g., for cross-checking
+    /// with an SMT solver
+    ///
+    /// # Performance
+    ///
+    /// For performance, consider using a [`std::io::BufWriter`] instance.
+    pub fn write_smtlib<W: io::Write>(&self, writer: &mut W) -> Result<(), io::Error> {
+        fio::smtlib::write_sat(writer, self)
+    }
+
     /// Sanitizes the constraints, i.e., for example a cardinality
     /// constraint of form `x + y >= 1` will be converted to a clause and
     /// tautologies will be removed.
@@ -932,6 +1149,10 @@ impl<VM: ManageVars + Default> SatInstance<VM> {
     /// the SAT competition since 2011. For details on the file format see
     /// [here](http://www.satcompetition.org/2011/format-benchmarks2011.html).
     ///
+    /// Clauses can span multiple lines and a line can contain multiple
+    /// clauses, each terminated by `0`. The `0` after the last clause may be
+    /// omitted.
+    ///
     /// If a DIMACS WCNF or MCNF file is parsed with this method, the objectives
     /// are ignored and only the constraints returned.
     pub fn from_dimacs<R: io::BufRead>(reader: R) -> anyhow::Result<Self> {
@@ -940,7 +1161,7 @@ impl<VM: ManageVars + Default> SatInstance<VM> {
 
     /// Parses a DIMACS instance from a file path. For more details see
     /// [`SatInstance::from_dimacs`]. With feature `compression` supports
-    /// bzip2 and gzip compression, detected by the file extension.
+    /// bzip2, gzip, and xz compression, detected by the file extension.
     pub fn from_dimacs_path<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
         let reader =
             fio::open_compressed_uncompressed_read(path).context("failed to open reader")?;
@@ -960,7 +1181,7 @@ impl<VM: ManageVars + Default> SatInstance<VM> {
 
     /// Parses an OPB instance from a file path. For more details see
     /// [`SatInstance::from_opb`]. With feature `compression` supports
-    /// bzip2 and gzip compression, detected by the file extension.
+    /// bzip2, gzip, and xz compression, detected by the file extension.
     pub fn from_opb_path<P: AsRef<Path>>(path: P, opts: fio::opb::Options) -> anyhow::Result<Self> {
         let reader =
             fio::open_compressed_uncompressed_read(path).context("failed to open reader")?;
@@ -1023,3 +1244,85 @@ impl CollectClauses for SatInstance {
         self.cnf.extend_clauses(cl_iter)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Cnf, SatInstance};
+    use crate::{
+        clause,
+        instances::ManageVars,
+        lit,
+        types::{constraints::CardConstraint, Assignment, Lit, TernaryVal},
+        var,
+    };
+
+    #[test]
+    fn cnf_max_var() {
+        let mut cnf = Cnf::new();
+        assert_eq!(cnf.max_var(), None);
+        assert_eq!(cnf.n_vars(), 0);
+        cnf.add_clause(clause![lit![0], !lit![4]]);
+        cnf.add_clause(clause![!lit![0]]);
+        assert_eq!(cnf.max_var(), Some(var![4]));
+        assert_eq!(cnf.n_vars(), 5);
+        let mut out = Vec::new();
+        cnf.write_dimacs(&mut out, cnf.n_vars()).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains("p cnf 5 2"));
+    }
+
+    #[test]
+    fn compact_vars() {
+        let mut inst: SatInstance = SatInstance::new();
+        inst.add_clause(clause![lit![0], !lit![2]]);
+        inst.add_clause(clause![!lit![5]]);
+        inst.add_card_constr(CardConstraint::new_ub([lit![2], !lit![5]], 1));
+        let map = inst.compact_vars();
+        assert_eq!(map.len(), 3);
+        assert_eq!(map[&var![0]], var![0]);
+        assert_eq!(map[&var![2]], var![1]);
+        assert_eq!(map[&var![5]], var![2]);
+        let cnf = inst.cnf();
+        assert_eq!(cnf[0], clause![lit![0], !lit![1]]);
+        assert_eq!(cnf[1], clause![!lit![2]]);
+        assert_eq!(inst.var_manager_ref().max_var(), Some(var![2]));
+
+        // Translate a model of the compacted instance back
+        let model = Assignment::from(vec![TernaryVal::True, TernaryVal::False, TernaryVal::False]);
+        let mut orig = Assignment::default();
+        map.iter()
+            .for_each(|(&old, &new)| orig.assign_var(old, model.var_value(new)));
+        assert_eq!(orig.var_value(var![0]), TernaryVal::True);
+        assert_eq!(orig.var_value(var![2]), TernaryVal::False);
+        assert_eq!(orig.var_value(var![5]), TernaryVal::False);
+        assert_eq!(orig.var_value(var![1]), TernaryVal::DontCare);
+    }
+
+    #[test]
+    fn append_shifted() {
+        let mut inst: SatInstance = SatInstance::new();
+        inst.add_clause(clause![lit![0], lit![1]]);
+        inst.add_card_constr(CardConstraint::new_ub([lit![0], lit![1]], 1));
+        let mut other: SatInstance = SatInstance::new();
+        other.add_clause(clause![!lit![0], lit![1]]);
+        other.add_clause(clause![lit![0]]);
+        other.add_card_constr(CardConstraint::new_lb([!lit![1], lit![2]], 1));
+        inst.append_shifted(other, var![2]);
+        assert_eq!(inst.n_vars(), 5);
+        assert_eq!(inst.cnf()[1], clause![!lit![2], lit![3]]);
+        assert_eq!(inst.cnf()[2], clause![lit![2]]);
+
+        let assign = |vals: [bool; 5]| -> Assignment {
+            vals.iter()
+                .enumerate()
+                .map(|(idx, &val)| Lit::new(idx as u32, !val))
+                .collect()
+        };
+        assert!(inst.is_sat(&assign([true, false, true, true, true])));
+        // Violates the shifted clause
+        assert!(!inst.is_sat(&assign([true, false, true, false, true])));
+        // Violates the shifted cardinality constraint
+        assert!(!inst.is_sat(&assign([false, true, true, true, false])));
+        // Violates the original cardinality constraint
+        assert!(!inst.is_sat(&assign([true, true, true, true, true])));
+    }
+}
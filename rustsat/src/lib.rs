@@ -52,9 +52,11 @@
 //! | `compression` | Enable parsing and writing compressed input. |
 //! | `fxhash` | Use the faster firefox hash function from `rustc-hash` in `rustsat`. |
 //! | `rand` | Enable randomization features. (Shuffling clauses etc.) |
+//! | `proof` | Enable emitting DRAT proofs from solvers that support it. |
 //! | `ipasir-display` | Changes `Display` trait for `Lit` and `Var` types to follow IPASIR variables indexing. |
 //! | `bench` | Enable benchmark tests. Behind feature flag since it requires unstable Rust. |
 //! | `internals` | Make some internal data structures for e.g. encodings public. This is useful when basing a more complex encoding on the `rustsat` implementation of another encoding. Note that the internal API might change between releases. |
+//! | `parallel` | Build large generalized totalizer encodings on multiple threads. |
 //!
 //! ## Examples
 //!
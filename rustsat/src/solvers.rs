@@ -93,6 +93,8 @@ use core::time::Duration;
 use std::fmt;
 use thiserror::Error;
 
+pub mod optimize;
+
 /// Trait for all SAT solvers in this library.
 /// Solvers outside of this library can also implement this trait to be able to
 /// use them with this library.
@@ -117,6 +119,33 @@ pub trait Solve: Extend<Clause> + for<'a> Extend<&'a Clause> {
     /// debug_assert_eq!(res, SolverResult::Sat);
     /// ```
     fn solve(&mut self) -> anyhow::Result<SolverResult>;
+    /// Solves the internal CNF formula under assumptions, for generic code
+    /// that does not require [`SolveIncremental`]. Incremental solvers
+    /// implement this with [`SolveIncremental::solve_assumps`]. Without
+    /// assumptions, this is the same as [`Solve::solve`].
+    ///
+    /// # Errors
+    ///
+    /// - [`NotIncremental`] if assumptions are given and the solver does not
+    ///   support solving under assumptions, which is the default
+    /// - A specific implementation might return other errors
+    fn try_solve_assumps(&mut self, assumps: &[Lit]) -> anyhow::Result<SolverResult> {
+        if assumps.is_empty() {
+            return self.solve();
+        }
+        Err(NotIncremental.into())
+    }
+    /// Resets the solver to the state of a newly constructed solver, i.e.,
+    /// removes all clauses, learned clauses, and statistics, so that the
+    /// solver can be used for an unrelated instance. The default
+    /// implementation replaces the solver with [`Default::default`],
+    /// implementations that can reuse their allocations may override this.
+    fn reset(&mut self)
+    where
+        Self: Default + Sized,
+    {
+        *self = Self::default();
+    }
     /// Gets a solution found by the solver up to a specified highest variable.
     ///
     /// # Errors
@@ -208,8 +237,75 @@ pub trait Solve: Extend<Clause> + for<'a> Extend<&'a Clause> {
     fn add_cnf_ref(&mut self, cnf: &Cnf) -> anyhow::Result<()> {
         cnf.iter().try_for_each(|cl| self.add_clause_ref(cl))
     }
+    /// Lazily enumerates all models of the internal CNF formula over the
+    /// variables up to `high_var`. See [`Models`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rustsat::{lit, solvers::Solve};
+    /// // any other solver crate works the same way
+    /// let mut solver = rustsat_minisat::core::Minisat::default();
+    /// solver.add_binary(lit![0], lit![1]).unwrap();
+    /// let models: Vec<_> = solver.solve_all(lit![1].var()).collect();
+    /// debug_assert_eq!(models.len(), 3);
+    /// ```
+    fn solve_all(&mut self, high_var: Var) -> Models<'_, Self>
+    where
+        Self: Sized,
+    {
+        Models {
+            solver: self,
+            high_var,
+            done: false,
+        }
+    }
+}
+
+/// Lazy iterator over the models of a solver, created by [`Solve::solve_all`].
+/// The solver is only called when the next model is requested. After each
+/// model, a clause blocking the model is added to the solver. The blocking
+/// clauses remain in the solver when the iterator is dropped, but the solver
+/// can otherwise be used as usual.
+///
+/// The iterator ends when no further models exist or the solver is
+/// interrupted. Errors returned by the solver are yielded and also end the
+/// iteration.
+pub struct Models<'solver, S: Solve> {
+    solver: &'solver mut S,
+    high_var: Var,
+    done: bool,
+}
+
+impl<S: Solve> Models<'_, S> {
+    fn next_model(&mut self) -> anyhow::Result<Option<Assignment>> {
+        if self.solver.solve()? != SolverResult::Sat {
+            return Ok(None);
+        }
+        let sol = self.solver.solution(self.high_var)?;
+        self.solver
+            .add_clause(sol.clone().into_iter().map(|l| !l).collect())?;
+        Ok(Some(sol))
+    }
+}
+
+impl<S: Solve> Iterator for Models<'_, S> {
+    type Item = anyhow::Result<Assignment>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let res = self.next_model().transpose();
+        if !matches!(res, Some(Ok(_))) {
+            self.done = true;
+        }
+        res
+    }
 }
 
+impl<S: Solve> std::iter::FusedIterator for Models<'_, S> {}
+
 /// Trait for all SAT solvers in this library.
 /// Solvers outside of this library can also implement this trait to be able to
 /// use them with this library.
@@ -220,6 +316,62 @@ pub trait SolveIncremental: Solve {
     /// A core is a clause entailed by the formula that contains only inverted
     /// literals of the assumptions.
     fn core(&mut self) -> anyhow::Result<Vec<Lit>>;
+    /// Adds a clause to the solver only if it is not already entailed by the
+    /// internal CNF formula. Entailment is checked by a call to
+    /// [`SolveIncremental::solve_assumps`] under the negation of the clause
+    /// that is limited to two conflicts, which mostly amounts to unit
+    /// propagation. If the check is inconclusive, the clause is added.
+    /// Returns whether the clause was added.
+    ///
+    /// The check replaces the solution or core of the previous call and
+    /// removes any conflict limit set with [`LimitConflicts::limit_conflicts`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rustsat::{clause, lit, solvers::{Solve, SolveIncremental}};
+    /// // any other solver crate works the same way
+    /// let mut solver = rustsat_minisat::core::Minisat::default();
+    /// solver.add_unit(lit![0]).unwrap();
+    /// debug_assert!(!solver.add_clause_if_useful(clause![lit![0], lit![1]]).unwrap());
+    /// debug_assert!(solver.add_clause_if_useful(clause![lit![1], lit![2]]).unwrap());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If limiting, solving or adding the clause fails.
+    fn add_clause_if_useful(&mut self, clause: Clause) -> anyhow::Result<bool>
+    where
+        Self: LimitConflicts,
+    {
+        let assumps: Vec<Lit> = clause.iter().map(|&l| !l).collect();
+        // The solver stops at the first decision after reaching the limit, so
+        // a conflict from propagating the assumptions needs a limit of two to
+        // be turned into a core
+        self.limit_conflicts(Some(2))?;
+        let res = self.solve_assumps(&assumps);
+        self.limit_conflicts(None)?;
+        if res? == SolverResult::Unsat {
+            return Ok(false);
+        }
+        self.add_clause(clause)?;
+        Ok(true)
+    }
+    /// Sets a writer that the solver emits a DRAT proof to. After an
+    /// unsatisfiable call to [`Solve::solve`], the written proof derives the
+    /// empty clause from the clauses added to the solver. The default
+    /// implementation returns [`UnsupportedProof`].
+    ///
+    /// # Errors
+    ///
+    /// If the solver does not support emitting DRAT proofs.
+    #[cfg(feature = "proof")]
+    fn set_drat_proof<W: std::io::Write + 'static>(&mut self, _writer: W) -> anyhow::Result<()>
+    where
+        Self: Sized,
+    {
+        Err(UnsupportedProof("DRAT").into())
+    }
 }
 
 /// Trait for all solvers that can be terminated by a termination callback.
@@ -469,6 +621,18 @@ impl fmt::Display for StateError {
     }
 }
 
+/// Error returned by solvers that do not support solving under assumptions
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("the solver does not support solving under assumptions")]
+pub struct NotIncremental;
+
+/// Error returned by solvers that do not support emitting a requested proof
+/// format
+#[cfg(feature = "proof")]
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("the solver does not support emitting {0} proofs")]
+pub struct UnsupportedProof(pub &'static str);
+
 macro_rules! pass_oom_or_panic {
     ($result:expr) => {{
         match $result {
@@ -0,0 +1,500 @@
+//! # Solution Improving Search
+//!
+//! Solver-agnostic drivers for minimizing a pseudo-boolean objective with an
+//! incremental SAT solver, either by solution improving search with
+//! [`linear_sat_unsat`] or by core-guided search with [`unsat_sat`]. An
+//! initial upper bound for the search can be computed with [`greedy_ub`].
+//!
+//! Additionally contains building blocks for implicit hitting set (IHS) based
+//! MaxSAT solving. In an IHS approach, unsatisfiable cores over the soft
+//! literals are accumulated and the cost of a minimum-weight hitting set of
+//! the cores, computed with [`min_hitting_set`], is a lower bound on the
+//! optimal MaxSAT cost. For stratified solving, the soft literals can be
+//! partitioned into strata of descending weight with [`stratify`].
+
+use thiserror::Error;
+
+use super::{SolveIncremental, SolveStats, SolverResult};
+use crate::{
+    encodings::{
+        pb::{BoundUpper, BoundUpperIncremental, Encode, GeneralizedTotalizer},
+        IterWeightedInputs,
+    },
+    instances::{Cnf, ManageVars},
+    types::{Assignment, Lit, RsHashMap, TernaryVal, WLitIter},
+};
+
+#[cfg(feature = "optimization")]
+use super::Solve;
+#[cfg(feature = "optimization")]
+use crate::instances::Objective;
+
+/// Error returned by [`linear_sat_unsat`] and [`unsat_sat`] if the solver is
+/// interrupted before the optimum is proven
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("the solver was interrupted before the optimum was proven")]
+pub struct Interrupted;
+
+/// Computes an initial upper bound on the optimal cost for seeding a solution
+/// improving search, so that the objective only needs to be encoded for
+/// bounds below it. The hard clauses are added to the solver, which is then
+/// called _once_. Returns the cost of the found model without the offset of
+/// the objective, together with the model, or [`None`] if no model was found,
+/// i.e., the solver returned unsatisfiable or was interrupted.
+///
+/// # Errors
+///
+/// If adding the clauses or the solver call fails.
+#[cfg(feature = "optimization")]
+pub fn greedy_ub<S>(
+    hard: &Cnf,
+    objective: &Objective,
+    solver: &mut S,
+) -> anyhow::Result<Option<(usize, Assignment)>>
+where
+    S: Solve + SolveStats,
+{
+    solver.add_cnf_ref(hard)?;
+    if solver.solve()? != SolverResult::Sat {
+        return Ok(None);
+    }
+    let sol = solver.full_solution()?;
+    Ok(Some((objective.evaluate_no_offset(&sol), sol)))
+}
+
+/// Minimizes the objective `obj` subject to the clauses in the solver with a
+/// linear SAT-UNSAT search. After each model, the upper bound is tightened to
+/// one less than the objective value of the model by incrementally encoding
+/// the bound with [`GeneralizedTotalizer::tighten_ub`] and enforcing it
+/// through assumptions, until the solver returns unsatisfiable. Returns the
+/// optimal objective value together with an optimal model, or [`None`] if the
+/// clauses in the solver are unsatisfiable.
+///
+/// The clauses of the encoding remain in the solver, while the bound is only
+/// enforced by assumptions.
+///
+/// # Errors
+///
+/// - [`Interrupted`] if the solver is interrupted
+/// - If the solver or the encoding returns an error
+pub fn linear_sat_unsat<S>(
+    solver: &mut S,
+    obj: &mut GeneralizedTotalizer,
+    var_manager: &mut dyn ManageVars,
+) -> anyhow::Result<Option<(usize, Assignment)>>
+where
+    S: SolveIncremental + SolveStats,
+{
+    match solver.solve()? {
+        SolverResult::Sat => (),
+        SolverResult::Unsat => return Ok(None),
+        SolverResult::Interrupted => return Err(Interrupted.into()),
+    }
+    loop {
+        let sol = solver.full_solution()?;
+        let cost: usize = obj
+            .iter()
+            .filter(|&(l, _)| sol.lit_value(l) == TernaryVal::True)
+            .map(|(_, w)| w)
+            .sum();
+        if cost == 0 {
+            return Ok(Some((cost, sol)));
+        }
+        let (cnf, assumps) = obj.tighten_ub(cost - 1, var_manager)?;
+        solver.add_cnf(cnf)?;
+        match solver.solve_assumps(&assumps)? {
+            SolverResult::Sat => (),
+            SolverResult::Unsat => return Ok(Some((cost, sol))),
+            SolverResult::Interrupted => return Err(Interrupted.into()),
+        }
+    }
+}
+
+/// Minimizes the objective given by the weighted literals `obj` subject to the
+/// clauses in the solver with a core-guided UNSAT-SAT search. The solver is
+/// first called assuming that no objective literal is true. For every core
+/// returned by the solver, the lower bound is increased by the smallest weight
+/// in the core and the core is relaxed by a [`GeneralizedTotalizer`] over its
+/// literals, whose outputs are assumed in the following calls. Weights are
+/// split between cores as in the OLL algorithm \[1\]. The first model found
+/// is optimal. Returns the optimal objective value together with an optimal
+/// model, or [`None`] if the clauses in the solver are unsatisfiable.
+///
+/// # Errors
+///
+/// - [`Interrupted`] if the solver is interrupted
+/// - If the solver or the encoding returns an error
+///
+/// # References
+///
+/// - \[1\] Antonio Morgado and Carmine Dodaro and Joao Marques-Silva:
+///   _Core-Guided MaxSAT with Soft Cardinality Constraints_, CP 2014.
+pub fn unsat_sat<S, I>(
+    solver: &mut S,
+    obj: I,
+    var_manager: &mut dyn ManageVars,
+) -> anyhow::Result<Option<(usize, Assignment)>>
+where
+    S: SolveIncremental + SolveStats,
+    I: WLitIter,
+{
+    let obj: Vec<(Lit, usize)> = obj.into_iter().filter(|&(_, w)| w > 0).collect();
+    let mut state = CoreGuided::default();
+    for &(l, w) in &obj {
+        *state.assumps.entry(!l).or_insert(0) += w;
+    }
+    loop {
+        let mut assumps: Vec<Lit> = state.assumps.keys().copied().collect();
+        // Sort for a deterministic search
+        assumps.sort_unstable();
+        match solver.solve_assumps(&assumps)? {
+            SolverResult::Sat => break,
+            SolverResult::Unsat => (),
+            SolverResult::Interrupted => return Err(Interrupted.into()),
+        }
+        let mut core: Vec<Lit> = solver.core()?.into_iter().map(|l| !l).collect();
+        if core.is_empty() {
+            return Ok(None);
+        }
+        core.sort_unstable();
+        core.dedup();
+        state.relax_core(&core, solver, var_manager)?;
+    }
+    let sol = solver.full_solution()?;
+    let cost: usize = obj
+        .iter()
+        .filter(|&&(l, _)| sol.lit_value(l) == TernaryVal::True)
+        .map(|(_, w)| w)
+        .sum();
+    debug_assert_eq!(cost, state.lb);
+    Ok(Some((cost, sol)))
+}
+
+/// State of the core-guided search in [`unsat_sat`]
+#[derive(Default)]
+struct CoreGuided {
+    /// The assumptions with their remaining weight
+    assumps: RsHashMap<Lit, usize>,
+    /// The totalizers relaxing the cores found so far
+    totalizers: Vec<GeneralizedTotalizer>,
+    /// For assumptions enforcing a bound on a totalizer, the index of the
+    /// totalizer and the bound
+    sums: RsHashMap<Lit, (usize, usize)>,
+    /// The current lower bound
+    lb: usize,
+}
+
+impl CoreGuided {
+    /// Increases the lower bound by the smallest weight in the core and
+    /// relaxes the core. `core` must be free of duplicates.
+    fn relax_core<S>(
+        &mut self,
+        core: &[Lit],
+        solver: &mut S,
+        var_manager: &mut dyn ManageVars,
+    ) -> anyhow::Result<()>
+    where
+        S: SolveIncremental,
+    {
+        let min_weight = core.iter().map(|a| self.assumps[a]).min().unwrap();
+        self.lb += min_weight;
+        for a in core {
+            let weight = self.assumps.get_mut(a).unwrap();
+            *weight -= min_weight;
+            if *weight == 0 {
+                self.assumps.remove(a);
+            }
+            // Relaxing a bound of a totalizer allows one more violation
+            if let Some(&(tot_idx, bound)) = self.sums.get(a) {
+                self.enforce_sum(tot_idx, bound + 1, min_weight, solver, var_manager)?;
+            }
+        }
+        if core.len() > 1 {
+            // At least one assumption in the core is violated, assume that no
+            // other one is
+            self.totalizers
+                .push(core.iter().map(|&a| (!a, 1)).collect());
+            let tot_idx = self.totalizers.len() - 1;
+            self.enforce_sum(tot_idx, 1, min_weight, solver, var_manager)?;
+        }
+        Ok(())
+    }
+
+    /// Encodes `bound` for the totalizer with index `tot_idx` and adds the
+    /// assumption enforcing it with weight `weight`
+    fn enforce_sum<S>(
+        &mut self,
+        tot_idx: usize,
+        bound: usize,
+        weight: usize,
+        solver: &mut S,
+        var_manager: &mut dyn ManageVars,
+    ) -> anyhow::Result<()>
+    where
+        S: SolveIncremental,
+    {
+        let tot = &mut self.totalizers[tot_idx];
+        if bound >= Encode::weight_sum(tot) {
+            // The bound is trivially satisfied
+            return Ok(());
+        }
+        // Relaxing a core loosens the bound, so it can not be tightened
+        let mut cnf = Cnf::new();
+        tot.encode_ub_change(bound..=bound, &mut cnf, var_manager)?;
+        let enforce = tot.enforce_ub(bound)?;
+        solver.add_cnf(cnf)?;
+        // With unit weights, the bound is enforced by a single output literal
+        debug_assert_eq!(enforce.len(), 1);
+        let a = enforce[0];
+        *self.assumps.entry(a).or_insert(0) += weight;
+        self.sums.insert(a, (tot_idx, bound));
+        Ok(())
+    }
+}
+
+/// A core over the soft literals of a MaxSAT instance, i.e., a set of
+/// literals of which at least one has to be set to true. Each literal is
+/// paired with the weight that setting it to true incurs.
+pub type WeightedCore = Vec<(Lit, usize)>;
+
+/// Computes the cost of a minimum-weight hitting set of the given cores, which
+/// is a lower bound on the optimal cost of the MaxSAT instance the cores were
+/// extracted from. Returns [`None`] if one of the cores is empty and can
+/// therefore not be hit.
+///
+/// The hitting set is computed exactly by branch and bound and is meant for
+/// small numbers of cores. The weight of a literal is expected to be equal in
+/// all cores it appears in, the weight of its first occurrence is used.
+///
+/// ```
+/// # use rustsat::{lit, solvers::optimize::min_hitting_set};
+/// let cores = [
+///     vec![(lit![0], 1), (lit![1], 3)],
+///     vec![(lit![1], 3), (lit![2], 2)],
+/// ];
+/// assert_eq!(min_hitting_set(&cores), Some(3));
+/// ```
+#[must_use]
+pub fn min_hitting_set(cores: &[WeightedCore]) -> Option<usize> {
+    // Map literals to indices
+    let mut idx_map: RsHashMap<Lit, usize> = RsHashMap::default();
+    let mut weights = vec![];
+    let mut idx_cores: Vec<Vec<usize>> = Vec::with_capacity(cores.len());
+    for core in cores {
+        if core.is_empty() {
+            return None;
+        }
+        let mut idx_core: Vec<usize> = core
+            .iter()
+            .map(|&(lit, weight)| {
+                *idx_map.entry(lit).or_insert_with(|| {
+                    weights.push(weight);
+                    weights.len() - 1
+                })
+            })
+            .collect();
+        // Branch on cheap literals first, sorting by index as well so that
+        // duplicates are adjacent
+        idx_core.sort_unstable_by_key(|&idx| (weights[idx], idx));
+        idx_core.dedup();
+        idx_cores.push(idx_core);
+    }
+    let mut hitting_set = vec![false; weights.len()];
+    let mut best = usize::MAX;
+    branch(&idx_cores, &weights, &mut hitting_set, 0, &mut best);
+    Some(best)
+}
+
+/// Recursive branch and bound for [`min_hitting_set`]
+fn branch(
+    cores: &[Vec<usize>],
+    weights: &[usize],
+    hitting_set: &mut [bool],
+    cost: usize,
+    best: &mut usize,
+) {
+    let mut branch_core: Option<&Vec<usize>> = None;
+    // Cost that has to be added at least to hit all remaining cores
+    let mut bound = 0;
+    for core in cores {
+        if core.iter().any(|&idx| hitting_set[idx]) {
+            continue;
+        }
+        // Cores are sorted by weight
+        bound = std::cmp::max(bound, weights[core[0]]);
+        if branch_core.map_or(true, |bc| core.len() < bc.len()) {
+            branch_core = Some(core);
+        }
+    }
+    if cost + bound >= *best {
+        return;
+    }
+    let Some(branch_core) = branch_core else {
+        // All cores are hit
+        *best = cost;
+        return;
+    };
+    for &idx in branch_core {
+        hitting_set[idx] = true;
+        branch(cores, weights, hitting_set, cost + weights[idx], best);
+        hitting_set[idx] = false;
+    }
+}
+
+/// Options for [`stratify_with`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct StratOptions {
+    /// Adjacent weights are merged into the same stratum as long as the
+    /// largest weight in the stratum is at most `merge_factor` times the
+    /// weight of the soft literal to add. With a factor of `1`, every distinct
+    /// weight forms its own stratum. Must be at least `1`, since a factor of
+    /// `0` would split literals of equal weight.
+    pub merge_factor: usize,
+}
+
+impl Default for StratOptions {
+    fn default() -> Self {
+        Self { merge_factor: 1 }
+    }
+}
+
+/// Partitions the weighted soft literals into strata of descending weight
+/// with default options, i.e., one stratum per distinct weight. See
+/// [`stratify_with`].
+///
+/// ```
+/// # use rustsat::{lit, solvers::optimize::stratify};
+/// let strata = stratify(&[(lit![0], 1), (lit![1], 5), (lit![2], 1)]);
+/// assert_eq!(strata, vec![vec![(lit![1], 5)], vec![(lit![0], 1), (lit![2], 1)]]);
+/// ```
+#[must_use]
+pub fn stratify<L: Copy>(soft: &[(L, usize)]) -> Vec<Vec<(L, usize)>> {
+    stratify_with(soft, StratOptions::default())
+}
+
+/// Partitions the weighted soft literals into strata of descending weight for
+/// stratified MaxSAT solving. Every weight in a stratum is larger than all
+/// weights in later strata and soft literals of equal weight are kept in their
+/// original order. Which adjacent weights are merged into one stratum is
+/// controlled by [`StratOptions::merge_factor`].
+///
+/// # Panics
+///
+/// If [`StratOptions::merge_factor`] is `0`.
+#[must_use]
+pub fn stratify_with<L: Copy>(soft: &[(L, usize)], opts: StratOptions) -> Vec<Vec<(L, usize)>> {
+    assert!(opts.merge_factor > 0, "merge factor must be at least 1");
+    let mut sorted = soft.to_vec();
+    sorted.sort_by(|(_, w1), (_, w2)| w2.cmp(w1));
+    let mut strata: Vec<Vec<(L, usize)>> = vec![];
+    let mut stratum_max = 0;
+    for (lit, w) in sorted {
+        match strata.last_mut() {
+            Some(stratum) if stratum_max <= w.saturating_mul(opts.merge_factor) => {
+                stratum.push((lit, w));
+            }
+            _ => {
+                stratum_max = w;
+                strata.push(vec![(lit, w)]);
+            }
+        }
+    }
+    strata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{min_hitting_set, stratify, stratify_with, StratOptions};
+    use crate::{lit, types::Lit};
+
+    #[test]
+    fn small_cores() {
+        let cores = [
+            vec![(lit![0], 1), (lit![1], 3)],
+            vec![(lit![1], 3), (lit![2], 3)],
+            vec![(lit![2], 3), (lit![3], 1)],
+        ];
+        assert_eq!(min_hitting_set(&cores), Some(4));
+        let cores = [
+            vec![(lit![0], 2), (lit![1], 2)],
+            vec![(lit![0], 2), (lit![2], 2)],
+            vec![(lit![1], 2), (lit![2], 2)],
+            vec![(lit![3], 5)],
+        ];
+        assert_eq!(min_hitting_set(&cores), Some(9));
+    }
+
+    #[test]
+    fn duplicate_lits() {
+        let cores = [vec![
+            (lit![0], 1),
+            (lit![1], 1),
+            (lit![0], 1),
+            (lit![2], 1),
+            (lit![1], 1),
+        ]];
+        assert_eq!(min_hitting_set(&cores), Some(1));
+    }
+
+    #[test]
+    fn trivial_cores() {
+        assert_eq!(min_hitting_set(&[]), Some(0));
+        assert_eq!(min_hitting_set(&[vec![(lit![0], 1)], vec![]]), None);
+    }
+
+    fn softs() -> Vec<(Lit, usize)> {
+        [7, 1, 3, 7, 2, 12, 1, 4]
+            .into_iter()
+            .enumerate()
+            .map(|(idx, w)| (lit![idx as u32], w))
+            .collect()
+    }
+
+    fn check_strata(soft: &[(Lit, usize)], strata: &[Vec<(Lit, usize)>]) {
+        // Covers all soft literals
+        let mut flat: Vec<_> = strata.iter().flatten().copied().collect();
+        flat.sort_unstable();
+        let mut soft = soft.to_vec();
+        soft.sort_unstable();
+        assert_eq!(flat, soft);
+        // Strata are in descending weight order
+        for win in strata.windows(2) {
+            let min = win[0].iter().map(|(_, w)| *w).min().unwrap();
+            let max = win[1].iter().map(|(_, w)| *w).max().unwrap();
+            assert!(min > max);
+        }
+    }
+
+    #[test]
+    fn distinct_weights() {
+        let soft = softs();
+        let strata = stratify(&soft);
+        check_strata(&soft, &strata);
+        let weights: Vec<usize> = strata.iter().map(|s| s[0].1).collect();
+        assert_eq!(weights, vec![12, 7, 4, 3, 2, 1]);
+        assert_eq!(strata[1], vec![(lit![0], 7), (lit![3], 7)]);
+    }
+
+    #[test]
+    fn merged_weights() {
+        let soft = softs();
+        let strata = stratify_with(&soft, StratOptions { merge_factor: 2 });
+        check_strata(&soft, &strata);
+        let weights: Vec<Vec<usize>> = strata
+            .iter()
+            .map(|s| s.iter().map(|(_, w)| *w).collect())
+            .collect();
+        assert_eq!(weights, vec![vec![12, 7, 7], vec![4, 3, 2], vec![1, 1]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "merge factor")]
+    fn zero_merge_factor() {
+        let _ = stratify_with(&softs(), StratOptions { merge_factor: 0 });
+    }
+
+    #[test]
+    fn empty() {
+        assert!(stratify::<Lit>(&[]).is_empty());
+    }
+}
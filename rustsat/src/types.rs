@@ -4,7 +4,7 @@
 
 use core::ffi::c_int;
 use std::{
-    fmt,
+    fmt, io,
     ops::{self, Index, IndexMut},
     path::Path,
 };
@@ -15,7 +15,16 @@ use thiserror::Error;
 pub mod constraints;
 pub use constraints::Clause;
 
-use crate::instances::fio::{self, SolverOutput};
+pub mod block;
+pub use block::VarBlock;
+
+pub mod iter;
+pub use iter::{LitIterExt, WLitIterExt};
+
+use crate::instances::{
+    fio::{self, SolverOutput},
+    Cnf,
+};
 
 /// The hash map to use throughout the library
 #[cfg(feature = "fxhash")]
@@ -219,6 +228,26 @@ impl fmt::Debug for Var {
     }
 }
 
+/// Converts a positive [DIMACS](http://www.satcompetition.org/2011/format-benchmarks2011.html)
+/// integer to a variable with index `val - 1`
+///
+/// ```
+/// # use rustsat::{var, types::Var};
+/// assert_eq!(Var::try_from(5).unwrap(), var![4]);
+/// assert!(Var::try_from(-5).is_err());
+/// ```
+impl TryFrom<i32> for Var {
+    type Error = TypeError;
+
+    fn try_from(val: i32) -> Result<Self, Self::Error> {
+        match val.cmp(&0) {
+            std::cmp::Ordering::Less => Err(TypeError::IpasirNegVar(val)),
+            std::cmp::Ordering::Equal => Err(TypeError::IpasirZero),
+            std::cmp::Ordering::Greater => Var::new_with_error(val.unsigned_abs() - 1),
+        }
+    }
+}
+
 /// More easily creates variables. Mainly used in tests.
 ///
 /// # Examples
@@ -376,6 +405,23 @@ impl Lit {
         Var::new_unchecked(self.vidx32())
     }
 
+    /// Negates the literal if `cond` is true, otherwise returns it unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustsat::lit;
+    /// assert_eq!(lit![2].flip_if(true), !lit![2]);
+    /// assert_eq!(lit![2].flip_if(false), lit![2]);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn flip_if(self, cond: bool) -> Lit {
+        Lit {
+            lidx: self.lidx ^ u32::from(cond),
+        }
+    }
+
     /// True if the literal is positive.
     #[inline]
     pub fn is_pos(&self) -> bool {
@@ -420,6 +466,22 @@ impl Lit {
         };
         Ok(if negated { -idx } else { idx })
     }
+
+    /// Converts the literal to a [DIMACS](http://www.satcompetition.org/2011/format-benchmarks2011.html)
+    /// integer. This is the inverse of [`Lit::try_from`] for `i32`.
+    ///
+    /// ```
+    /// # use rustsat::{lit, types::Lit};
+    /// assert_eq!(lit![4].to_dimacs(), 5);
+    /// assert_eq!((!lit![2]).to_dimacs(), -3);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the literal does not fit into an `i32`.
+    pub fn to_dimacs(self) -> i32 {
+        self.to_ipasir()
+    }
 }
 
 /// Trait implementation allowing for negating literals with the `!` operator.
@@ -434,6 +496,17 @@ impl ops::Not for Lit {
     }
 }
 
+/// Trait implementation allowing for negating literal references with the `!`
+/// operator.
+impl ops::Not for &Lit {
+    type Output = Lit;
+
+    #[inline]
+    fn not(self) -> Lit {
+        !*self
+    }
+}
+
 /// Trait implementation allowing for negating literals with the unary `-` operator.
 impl ops::Neg for Lit {
     type Output = Lit;
@@ -485,6 +558,30 @@ impl fmt::Debug for Lit {
     }
 }
 
+/// Converts a [DIMACS](http://www.satcompetition.org/2011/format-benchmarks2011.html) integer to
+/// a literal of the variable with index `|val| - 1` that is negated if `val` is negative.
+/// `i32::MIN` is rejected since its negation can not be represented, i.e., the literal could not
+/// be converted back with [`Lit::to_dimacs`].
+///
+/// ```
+/// # use rustsat::{lit, types::Lit};
+/// assert_eq!(Lit::try_from(-3).unwrap(), !lit![2]);
+/// assert!(Lit::try_from(0).is_err());
+/// ```
+impl TryFrom<i32> for Lit {
+    type Error = TypeError;
+
+    fn try_from(val: i32) -> Result<Self, Self::Error> {
+        if val == i32::MIN {
+            return Err(TypeError::IdxTooHigh(
+                val.unsigned_abs() - 1,
+                i32::MAX.unsigned_abs() - 1,
+            ));
+        }
+        Lit::from_ipasir(val)
+    }
+}
+
 /// More easily creates literals. Mainly used in tests.
 ///
 /// # Examples
@@ -585,6 +682,36 @@ impl ops::Not for TernaryVal {
     }
 }
 
+/// Conjunction in three-valued Kleene logic, i.e., [`TernaryVal::False`] if
+/// either value is false and [`TernaryVal::DontCare`] if neither is false but
+/// one is "don't care"
+impl ops::BitAnd for TernaryVal {
+    type Output = TernaryVal;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        match (self, rhs) {
+            (TernaryVal::False, _) | (_, TernaryVal::False) => TernaryVal::False,
+            (TernaryVal::True, TernaryVal::True) => TernaryVal::True,
+            _ => TernaryVal::DontCare,
+        }
+    }
+}
+
+/// Disjunction in three-valued Kleene logic, i.e., [`TernaryVal::True`] if
+/// either value is true and [`TernaryVal::DontCare`] if neither is true but
+/// one is "don't care"
+impl ops::BitOr for TernaryVal {
+    type Output = TernaryVal;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        match (self, rhs) {
+            (TernaryVal::True, _) | (_, TernaryVal::True) => TernaryVal::True,
+            (TernaryVal::False, TernaryVal::False) => TernaryVal::False,
+            _ => TernaryVal::DontCare,
+        }
+    }
+}
+
 impl ops::Neg for TernaryVal {
     type Output = TernaryVal;
 
@@ -742,6 +869,32 @@ impl Assignment {
         anyhow::ensure!(!lines.is_empty(), InvalidVLine::EmptyLine);
         Ok(())
     }
+
+    /// Writes the assignment as a SAT solver value line in the format of the
+    /// [SAT competition](http://www.satcompetition.org/2011/format-benchmarks2011.html), i.e., as
+    /// `v` followed by the DIMACS literals of all assigned variables and `0`. Variables that are
+    /// not assigned are skipped. The output can be read with [`Assignment::from_vline`].
+    ///
+    /// # Errors
+    ///
+    /// If writing fails.
+    pub fn write_vline<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "v")?;
+        for (idx, &val) in self.assignment.iter().enumerate() {
+            let var = var![idx as u32];
+            match val {
+                TernaryVal::True => write!(writer, " {}", var.pos_lit().to_ipasir())?,
+                TernaryVal::False => write!(writer, " {}", var.neg_lit().to_ipasir())?,
+                TernaryVal::DontCare => (),
+            }
+        }
+        writeln!(writer, " 0")
+    }
+
+    /// Checks whether the assignment satisfies all clauses of a CNF
+    pub fn satisfies(&self, cnf: &Cnf) -> bool {
+        cnf.iter().all(|cl| cl.is_sat(self))
+    }
 }
 
 impl fmt::Debug for Assignment {
@@ -819,6 +972,9 @@ pub enum TypeError {
     /// IPASIR/DIMACS index is zero
     #[error("zero is an invalid IPASIR/DIMACS literal")]
     IpasirZero,
+    /// IPASIR/DIMACS variable is negative
+    #[error("negative value {0} is an invalid IPASIR/DIMACS variable")]
+    IpasirNegVar(i32),
 }
 
 /// An iterator over literals
@@ -843,6 +999,7 @@ mod tests {
     use std::{mem::size_of, num::ParseIntError};
 
     use super::{Assignment, InvalidVLine, Lit, TernaryVal, Var};
+    use crate::{clause, instances::Cnf};
 
     #[test]
     fn var_index() {
@@ -911,6 +1068,24 @@ mod tests {
         assert_eq!(lit1.var(), lit2.var());
     }
 
+    #[test]
+    fn lit_ref_negation() {
+        let lit = Lit::positive(3);
+        assert_eq!(!&lit, !lit);
+        assert_eq!(!&!&lit, lit);
+        let lits = [Lit::positive(0), Lit::negative(1)];
+        let negated: Vec<Lit> = lits.iter().map(|l| !l).collect();
+        assert_eq!(negated, vec![Lit::negative(0), Lit::positive(1)]);
+    }
+
+    #[test]
+    fn lit_flip_if() {
+        let lit = Lit::negative(4);
+        assert_eq!(lit.flip_if(false), lit);
+        assert_eq!(lit.flip_if(true), !lit);
+        assert_eq!(lit.flip_if(true).flip_if(true), lit);
+    }
+
     #[test]
     fn ipasir_lit_not_zero() {
         let lit = Lit::positive(0);
@@ -928,6 +1103,23 @@ mod tests {
         assert_eq!(lit.to_ipasir(), -(idx as i32 + 1));
     }
 
+    #[test]
+    fn dimacs_lit_try_from() {
+        assert_eq!(Lit::try_from(-3).unwrap(), !lit![2]);
+        assert_eq!(Lit::try_from(5).unwrap(), lit![4]);
+        assert!(Lit::try_from(0).is_err());
+        assert!(Lit::try_from(i32::MIN).is_err());
+        assert_eq!(Lit::try_from(i32::MAX).unwrap().to_dimacs(), i32::MAX);
+        assert_eq!(Lit::try_from(-7).unwrap().to_dimacs(), -7);
+    }
+
+    #[test]
+    fn dimacs_var_try_from() {
+        assert_eq!(Var::try_from(5).unwrap(), var![4]);
+        assert!(Var::try_from(0).is_err());
+        assert!(Var::try_from(-5).is_err());
+    }
+
     #[test]
     fn ternary_var_true() {
         let tv = TernaryVal::True;
@@ -949,6 +1141,26 @@ mod tests {
         assert!(!tv.to_bool_with_def(false));
     }
 
+    #[test]
+    fn ternary_kleene() {
+        use TernaryVal::{DontCare, False, True};
+        let vals = [True, False, DontCare];
+        for a in vals {
+            for b in vals {
+                // De Morgan holds in Kleene logic
+                assert_eq!(!(a & b), !a | !b);
+                assert_eq!(a & b, b & a);
+                assert_eq!(a | b, b | a);
+            }
+            assert_eq!(a & True, a);
+            assert_eq!(a & False, False);
+            assert_eq!(a | False, a);
+            assert_eq!(a | True, True);
+        }
+        assert_eq!(DontCare & DontCare, DontCare);
+        assert_eq!(DontCare | DontCare, DontCare);
+    }
+
     #[test]
     fn sol_var_val() {
         let sol = Assignment::from(vec![
@@ -1027,6 +1239,41 @@ mod tests {
         assert_eq!(size_of::<TernaryVal>(), 1);
     }
 
+    #[test]
+    fn write_vline() {
+        let assignment = Assignment::from(vec![
+            TernaryVal::True,
+            TernaryVal::False,
+            TernaryVal::DontCare,
+            TernaryVal::True,
+        ]);
+        let mut vline = vec![];
+        assignment.write_vline(&mut vline).unwrap();
+        let vline = String::from_utf8(vline).unwrap();
+        assert_eq!(vline, "v 1 -2 4 0\n");
+        assert_eq!(
+            Assignment::from_vline(vline.trim_end()).unwrap(),
+            assignment
+        );
+    }
+
+    #[test]
+    fn assignment_satisfies() {
+        let cnf: Cnf = [
+            clause![lit![0], lit![1]],
+            clause![!lit![1], lit![2]],
+            clause![lit![3]],
+        ]
+        .into_iter()
+        .collect();
+        let mut assignment: Assignment =
+            [lit![0], !lit![1], lit![2], lit![3]].into_iter().collect();
+        assert!(assignment.satisfies(&cnf));
+        // Flipping the literal of the unit clause falsifies it
+        assignment.assign_lit(!lit![3]);
+        assert!(!assignment.satisfies(&cnf));
+    }
+
     #[test]
     fn parse_vline() {
         let vline = "v 1 -2 4 -5 6 0";
@@ -0,0 +1,148 @@
+//! # Variable Blocks
+//!
+//! Contiguous blocks of variables that are addressed by an offset, e.g., for
+//! encodings with one variable per element of some set, instead of computing
+//! variable indices by hand.
+
+use std::ops::Range;
+
+use super::{Lit, Var};
+use crate::instances::ManageVars;
+
+/// A contiguous block of variables, addressed by offsets starting at zero
+///
+/// ```
+/// # use rustsat::{instances::{BasicVarManager, ManageVars}, types::VarBlock, var};
+/// let mut var_manager = BasicVarManager::default();
+/// var_manager.increase_next_free(var![3]);
+/// let block = VarBlock::alloc(4, &mut var_manager);
+/// assert_eq!(block.var(0), var![3]);
+/// assert!(block.contains(var![6]));
+/// assert!(!block.contains(var![7]));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VarBlock {
+    /// The first variable in the block
+    start: Var,
+    /// The number of variables in the block
+    len: u32,
+}
+
+impl VarBlock {
+    /// Allocates a block of `n` new variables with [`ManageVars::new_vars`]
+    pub fn alloc(n: usize, var_manager: &mut dyn ManageVars) -> Self {
+        Self::from(var_manager.new_vars(n))
+    }
+
+    /// Gets the number of variables in the block
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Checks whether the block is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Gets the variable at offset `i` in the block
+    ///
+    /// # Panics
+    ///
+    /// If `i` is not smaller than the length of the block.
+    #[must_use]
+    pub fn var(&self, i: usize) -> Var {
+        assert!(
+            i < self.len(),
+            "offset {i} out of range for block of {} variables",
+            self.len
+        );
+        self.start + i as u32
+    }
+
+    /// Gets the positive literal of the variable at offset `i` in the block
+    ///
+    /// # Panics
+    ///
+    /// If `i` is not smaller than the length of the block.
+    #[must_use]
+    pub fn lit(&self, i: usize) -> Lit {
+        self.var(i).pos_lit()
+    }
+
+    /// Checks whether a variable is in the block
+    #[must_use]
+    pub fn contains(&self, var: Var) -> bool {
+        var >= self.start && var.idx32() - self.start.idx32() < self.len
+    }
+
+    /// Gets the offset of a variable in the block, if it is in the block
+    #[must_use]
+    pub fn offset(&self, var: Var) -> Option<usize> {
+        if self.contains(var) {
+            Some((var.idx32() - self.start.idx32()) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Gets an iterator over the variables in the block
+    pub fn iter(&self) -> impl Iterator<Item = Var> {
+        let start = self.start;
+        (0..self.len).map(move |i| start + i)
+    }
+}
+
+impl From<Range<Var>> for VarBlock {
+    fn from(range: Range<Var>) -> Self {
+        Self {
+            start: range.start,
+            len: range.end.idx32().saturating_sub(range.start.idx32()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VarBlock;
+    use crate::{
+        instances::{BasicVarManager, ManageVars},
+        lit, var,
+    };
+
+    #[test]
+    fn alloc() {
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![2]);
+        let block = VarBlock::alloc(3, &mut var_manager);
+        assert_eq!(block.len(), 3);
+        assert_eq!(var_manager.new_var(), var![5]);
+        assert_eq!(block.lit(1), lit![3]);
+        assert_eq!(
+            block.iter().collect::<Vec<_>>(),
+            vec![var![2], var![3], var![4]]
+        );
+        assert_eq!(block.offset(var![4]), Some(2));
+        assert_eq!(block.offset(var![5]), None);
+    }
+
+    #[test]
+    fn contains_boundaries() {
+        let block = VarBlock::from(var![4]..var![8]);
+        assert!(!block.contains(var![3]));
+        assert!(block.contains(var![4]));
+        assert!(block.contains(var![7]));
+        assert!(!block.contains(var![8]));
+        let empty = VarBlock::from(var![4]..var![4]);
+        assert!(empty.is_empty());
+        assert!(!empty.contains(var![4]));
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn out_of_range() {
+        let block = VarBlock::from(var![4]..var![8]);
+        let _ = block.var(4);
+    }
+}
@@ -13,7 +13,14 @@ use thiserror::Error;
 
 use super::{Assignment, IWLitIter, Lit, LitIter, RsHashSet, TernaryVal, WLitIter};
 
-use crate::RequiresClausal;
+use crate::{
+    encodings::{
+        card::{self, Totalizer},
+        pb::{self, DoubleGeneralizedTotalizer},
+    },
+    instances::{Cnf, ManageVars},
+    RequiresClausal,
+};
 
 /// Type representing a clause.
 /// Wrapper around a std collection to allow for changing the data structure.
@@ -311,6 +318,51 @@ macro_rules! clause {
     };
 }
 
+/// The relational operator of a constraint
+#[derive(Hash, Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Comparator {
+    /// Less than or equal (`<=`)
+    Leq,
+    /// Greater than or equal (`>=`)
+    Geq,
+    /// Equal (`=`)
+    Eq,
+}
+
+impl Comparator {
+    /// Gets the comparator for the constraint with both sides swapped, i.e.,
+    /// [`Comparator::Leq`] and [`Comparator::Geq`] are exchanged
+    #[must_use]
+    pub fn flip(self) -> Self {
+        match self {
+            Comparator::Leq => Comparator::Geq,
+            Comparator::Geq => Comparator::Leq,
+            Comparator::Eq => Comparator::Eq,
+        }
+    }
+
+    /// Parses a comparator from its representation in the OPB format
+    #[must_use]
+    pub fn from_opb_str(op: &str) -> Option<Self> {
+        match op {
+            "<=" => Some(Comparator::Leq),
+            ">=" => Some(Comparator::Geq),
+            "=" => Some(Comparator::Eq),
+            _ => None,
+        }
+    }
+
+    /// Gets the representation of the comparator in the OPB format
+    #[must_use]
+    pub fn as_opb_str(&self) -> &'static str {
+        match self {
+            Comparator::Leq => "<=",
+            Comparator::Geq => ">=",
+            Comparator::Eq => "=",
+        }
+    }
+}
+
 /// Type representing a cardinality constraint.
 #[derive(Hash, Eq, PartialEq, Clone, Debug)]
 pub enum CardConstraint {
@@ -323,6 +375,15 @@ pub enum CardConstraint {
 }
 
 impl CardConstraint {
+    /// Constructs a new cardinality constraint with the given comparator
+    pub fn new<LI: LitIter>(lits: LI, cmp: Comparator, b: usize) -> Self {
+        match cmp {
+            Comparator::Leq => CardConstraint::new_ub(lits, b),
+            Comparator::Geq => CardConstraint::new_lb(lits, b),
+            Comparator::Eq => CardConstraint::new_eq(lits, b),
+        }
+    }
+
     /// Constructs a new upper bound cardinality constraint (`sum of lits <= b`)
     pub fn new_ub<LI: LitIter>(lits: LI, b: usize) -> Self {
         CardConstraint::UB(CardUBConstr {
@@ -347,6 +408,15 @@ impl CardConstraint {
         })
     }
 
+    /// Gets the comparator of the constraint
+    pub fn comparator(&self) -> Comparator {
+        match self {
+            CardConstraint::UB(_) => Comparator::Leq,
+            CardConstraint::LB(_) => Comparator::Geq,
+            CardConstraint::EQ(_) => Comparator::Eq,
+        }
+    }
+
     /// Adds literals to the cardinality constraint
     pub fn add<LI: LitIter>(&mut self, lits: LI) {
         match self {
@@ -493,6 +563,18 @@ impl CardConstraint {
             CardConstraint::EQ(constr) => count == constr.b,
         }
     }
+
+    /// Encodes the cardinality constraint to CNF with the [`Totalizer`]. For
+    /// an equality constraint, both the upper and the lower bound are encoded.
+    ///
+    /// # Errors
+    ///
+    /// If the clauses can not be collected, returns [`crate::OutOfMemory`].
+    pub fn encode(&self, var_manager: &mut dyn ManageVars) -> Result<Cnf, crate::OutOfMemory> {
+        let mut cnf = Cnf::new();
+        card::encode_cardinality_constraint::<Totalizer, _>(self.clone(), &mut cnf, var_manager)?;
+        Ok(cnf)
+    }
 }
 
 /// An upper bound cardinality constraint (`sum of lits <= b`)
@@ -650,6 +732,15 @@ impl PBConstraint {
         (lits, weight_sum, b_add)
     }
 
+    /// Constructs a new pseudo-boolean constraint with the given comparator
+    pub fn new<LI: IWLitIter>(lits: LI, cmp: Comparator, b: isize) -> Self {
+        match cmp {
+            Comparator::Leq => PBConstraint::new_ub(lits, b),
+            Comparator::Geq => PBConstraint::new_lb(lits, b),
+            Comparator::Eq => PBConstraint::new_eq(lits, b),
+        }
+    }
+
     /// Constructs a new upper bound pseudo-boolean constraint (`weighted sum of lits <= b`)
     pub fn new_ub<LI: IWLitIter>(lits: LI, b: isize) -> Self {
         let (lits, weight_sum, b_add) = PBConstraint::convert_input_lits(lits);
@@ -689,6 +780,15 @@ impl PBConstraint {
         }
     }
 
+    /// Gets the comparator of the constraint
+    pub fn comparator(&self) -> Comparator {
+        match self {
+            PBConstraint::UB(_) => Comparator::Leq,
+            PBConstraint::LB(_) => Comparator::Geq,
+            PBConstraint::EQ(_) => Comparator::Eq,
+        }
+    }
+
     /// Adds literals to the cardinality constraint
     pub fn add<LI: IWLitIter>(&mut self, lits: LI) {
         let (lits, add_weight_sum, b_add) = PBConstraint::convert_input_lits(lits);
@@ -939,6 +1039,27 @@ impl PBConstraint {
             }
         }
     }
+
+    /// Encodes the pseudo-boolean constraint to CNF with the
+    /// [`GeneralizedTotalizer`] for upper bounds and the
+    /// [`InvertedGeneralizedTotalizer`] for lower bounds. For an equality
+    /// constraint, both bounds are encoded.
+    ///
+    /// # Errors
+    ///
+    /// If the clauses can not be collected, returns [`crate::OutOfMemory`].
+    ///
+    /// [`GeneralizedTotalizer`]: crate::encodings::pb::GeneralizedTotalizer
+    /// [`InvertedGeneralizedTotalizer`]: crate::encodings::pb::InvertedGeneralizedTotalizer
+    pub fn encode(&self, var_manager: &mut dyn ManageVars) -> Result<Cnf, crate::OutOfMemory> {
+        let mut cnf = Cnf::new();
+        pb::encode_pb_constraint::<DoubleGeneralizedTotalizer, _>(
+            self.clone(),
+            &mut cnf,
+            var_manager,
+        )?;
+        Ok(cnf)
+    }
 }
 
 /// An upper bound pseudo-boolean constraint (`weighted sum of lits <= b`)
@@ -1153,8 +1274,13 @@ impl PBEQConstr {
 
 #[cfg(test)]
 mod tests {
-    use super::{CardConstraint, PBConstraint};
-    use crate::{lit, types::Assignment, var};
+    use super::{CardConstraint, Comparator, PBConstraint};
+    use crate::{
+        instances::{BasicVarManager, Cnf, ManageVars},
+        lit,
+        types::{Assignment, Lit, TernaryVal},
+        var,
+    };
 
     #[test]
     fn clause_remove() {
@@ -1221,6 +1347,44 @@ mod tests {
         assert!(cl.is_sat(&assign!(0b111)));
     }
 
+    #[test]
+    fn card_is_sat() {
+        let lits = vec![lit![0], !lit![1], lit![2]];
+        let ub = CardConstraint::new_ub(lits.clone(), 1);
+        assert!(ub.is_sat(&assign!(0b010)));
+        assert!(ub.is_sat(&assign!(0b011)));
+        assert!(!ub.is_sat(&assign!(0b001)));
+        assert!(!ub.is_sat(&assign!(0b101)));
+        let lb = CardConstraint::new_lb(lits.clone(), 2);
+        assert!(lb.is_sat(&assign!(0b101)));
+        assert!(lb.is_sat(&assign!(0b001)));
+        assert!(!lb.is_sat(&assign!(0b010)));
+        assert!(!lb.is_sat(&assign!(0b011)));
+        let eq = CardConstraint::new_eq(lits, 3);
+        assert!(eq.is_sat(&assign!(0b101)));
+        assert!(!eq.is_sat(&assign!(0b111)));
+    }
+
+    #[test]
+    fn pb_is_sat() {
+        let lits = vec![(lit![0], 3), (lit![1], -2), (lit![2], 1)];
+        let ub = PBConstraint::new_ub(lits.clone(), 2);
+        assert!(ub.is_sat(&assign!(0b011)));
+        assert!(ub.is_sat(&assign!(0b100)));
+        assert!(!ub.is_sat(&assign!(0b001)));
+        assert!(ub.is_sat(&assign!(0b111)));
+        let lb = PBConstraint::new_lb(lits.clone(), 2);
+        assert!(lb.is_sat(&assign!(0b001)));
+        assert!(lb.is_sat(&assign!(0b111)));
+        assert!(!lb.is_sat(&assign!(0b010)));
+        assert!(!lb.is_sat(&assign!(0b110)));
+        let eq = PBConstraint::new_eq(lits, 1);
+        assert!(eq.is_sat(&assign!(0b011)));
+        assert!(eq.is_sat(&assign!(0b100)));
+        assert!(!eq.is_sat(&assign!(0b101)));
+        assert!(!eq.is_sat(&assign!(0b000)));
+    }
+
     #[test]
     fn card_is_tautology() {
         let lits = vec![lit![0], lit![1], lit![2]];
@@ -1332,4 +1496,101 @@ mod tests {
         assert!(!PBConstraint::new_lb(lits.clone(), 3).is_clause());
         assert!(!PBConstraint::new_eq(lits.clone(), 2).is_card());
     }
+
+    /// Checks whether the assignment of the three input variables in `inputs`
+    /// can be extended to a model of `cnf` by the auxiliary variables
+    fn has_model(cnf: &Cnf, inputs: u32, n_vars: u32) -> bool {
+        (0..1_u32 << (n_vars - 3)).any(|aux| {
+            let assign: Assignment = (0..n_vars)
+                .map(|idx| {
+                    let val = ((inputs | (aux << 3)) >> idx) & 1 == 1;
+                    Lit::new(idx, !val)
+                })
+                .collect();
+            cnf.evaluate(&assign) == TernaryVal::True
+        })
+    }
+
+    #[test]
+    fn card_encode_eq() {
+        let lits = [lit![0], lit![1], lit![2]];
+        let constr = CardConstraint::new_eq(lits, 1);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![3]);
+        let cnf = constr.encode(&mut var_manager).unwrap();
+        for inputs in 0..8_u32 {
+            assert_eq!(
+                has_model(&cnf, inputs, var_manager.n_used()),
+                inputs.count_ones() == 1
+            );
+        }
+    }
+
+    #[test]
+    fn pb_normalize_mixed_signs() {
+        let constr = PBConstraint::new_ub(
+            [(lit![0], 3), (lit![1], -2), (lit![0], 2), (lit![1], -1)],
+            1,
+        );
+        // Negative coefficients are moved to the negated literals and the
+        // bound is increased accordingly
+        let PBConstraint::UB(norm) = constr.normalize() else {
+            panic!()
+        };
+        assert_eq!(norm.decompose(), (vec![(lit![0], 5), (!lit![1], 3)], 4));
+    }
+
+    #[test]
+    fn pb_encode() {
+        let lits = vec![(lit![0], 3), (lit![1], -2), (lit![2], 1)];
+        let constrs = [
+            PBConstraint::new_ub(lits.clone(), 1),
+            PBConstraint::new_lb(lits.clone(), 1),
+            PBConstraint::new_eq(lits, 1),
+        ];
+        for constr in constrs {
+            let mut var_manager = BasicVarManager::default();
+            var_manager.increase_next_free(var![3]);
+            let cnf = constr.encode(&mut var_manager).unwrap();
+            for inputs in 0..8_u32 {
+                assert_eq!(
+                    has_model(&cnf, inputs, var_manager.n_used()),
+                    constr.is_sat(&assign!(inputs))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn comparator_opb_str() {
+        for cmp in [Comparator::Leq, Comparator::Geq, Comparator::Eq] {
+            assert_eq!(Comparator::from_opb_str(cmp.as_opb_str()), Some(cmp));
+        }
+        assert_eq!(Comparator::from_opb_str("<="), Some(Comparator::Leq));
+        assert_eq!(Comparator::from_opb_str(">="), Some(Comparator::Geq));
+        assert_eq!(Comparator::from_opb_str("="), Some(Comparator::Eq));
+        assert_eq!(Comparator::from_opb_str("<"), None);
+        assert_eq!(Comparator::from_opb_str("=="), None);
+    }
+
+    #[test]
+    fn comparator_flip() {
+        assert_eq!(Comparator::Leq.flip(), Comparator::Geq);
+        assert_eq!(Comparator::Eq.flip(), Comparator::Eq);
+        for cmp in [Comparator::Leq, Comparator::Geq, Comparator::Eq] {
+            assert_eq!(cmp.flip().flip(), cmp);
+        }
+    }
+
+    #[test]
+    fn constr_comparator() {
+        let lits = vec![(lit![0], 3), (lit![1], -2)];
+        for cmp in [Comparator::Leq, Comparator::Geq, Comparator::Eq] {
+            assert_eq!(PBConstraint::new(lits.clone(), cmp, 1).comparator(), cmp);
+            assert_eq!(
+                CardConstraint::new([lit![0], lit![1]], cmp, 1).comparator(),
+                cmp
+            );
+        }
+    }
 }
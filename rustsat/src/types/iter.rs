@@ -0,0 +1,93 @@
+//! # Literal Iterator Adaptors
+//!
+//! Extension traits adding adaptors to iterators over (weighted) literals,
+//! e.g., for building encodings over the negated input literals.
+
+use super::Lit;
+
+/// Extension trait for iterators over literals
+pub trait LitIterExt: Iterator<Item = Lit> + Sized {
+    /// Creates an iterator that yields the negation of every literal
+    fn negated(self) -> Negated<Self> {
+        Negated { iter: self }
+    }
+}
+
+impl<I: Iterator<Item = Lit>> LitIterExt for I {}
+
+/// Extension trait for iterators over weighted literals
+pub trait WLitIterExt: Iterator<Item = (Lit, usize)> + Sized {
+    /// Creates an iterator that yields the negation of every literal with its
+    /// weight unchanged
+    fn negated_weighted(self) -> NegatedWeighted<Self> {
+        NegatedWeighted { iter: self }
+    }
+}
+
+impl<I: Iterator<Item = (Lit, usize)>> WLitIterExt for I {}
+
+/// Iterator negating literals, created by [`LitIterExt::negated`]
+#[derive(Clone, Debug)]
+pub struct Negated<I> {
+    iter: I,
+}
+
+impl<I: Iterator<Item = Lit>> Iterator for Negated<I> {
+    type Item = Lit;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|l| !l)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Iterator negating weighted literals, created by
+/// [`WLitIterExt::negated_weighted`]
+#[derive(Clone, Debug)]
+pub struct NegatedWeighted<I> {
+    iter: I,
+}
+
+impl<I: Iterator<Item = (Lit, usize)>> Iterator for NegatedWeighted<I> {
+    type Item = (Lit, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(l, w)| (!l, w))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LitIterExt, WLitIterExt};
+    use crate::{lit, types::Lit};
+
+    #[test]
+    fn negated() {
+        let lits = vec![lit![0], !lit![1], lit![2]];
+        let neg: Vec<Lit> = lits.clone().into_iter().negated().collect();
+        assert_eq!(neg, vec![!lit![0], lit![1], !lit![2]]);
+        let double: Vec<Lit> = lits.clone().into_iter().negated().negated().collect();
+        assert_eq!(double, lits);
+    }
+
+    #[test]
+    fn negated_weighted() {
+        let lits = vec![(lit![0], 3), (!lit![1], 1)];
+        let neg: Vec<(Lit, usize)> = lits.clone().into_iter().negated_weighted().collect();
+        assert_eq!(neg, vec![(!lit![0], 3), (lit![1], 1)]);
+        let double: Vec<(Lit, usize)> = lits
+            .clone()
+            .into_iter()
+            .negated_weighted()
+            .negated_weighted()
+            .collect();
+        assert_eq!(double, lits);
+    }
+}
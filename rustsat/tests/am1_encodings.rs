@@ -1,6 +1,6 @@
 use rustsat::{
-    encodings::am1::{Encode, Pairwise},
-    instances::{BasicVarManager, ManageVars},
+    encodings::am1::{self, Encode, Pairwise},
+    instances::{BasicVarManager, Cnf, ManageVars},
     lit,
     solvers::{
         Solve, SolveIncremental,
@@ -38,3 +38,44 @@ fn test_am1<AM1: Encode + From<Vec<Lit>>>() {
 fn pairwise() {
     test_am1::<Pairwise>()
 }
+
+fn test_exactly_one<AM1: Encode + FromIterator<Lit>>() {
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![3]);
+
+    am1::encode_exactly_one::<AM1, _, _>(
+        [lit![0], lit![1], lit![2]],
+        &mut solver,
+        &mut var_manager,
+    )
+    .unwrap();
+
+    test_all!(
+        solver,
+        Vec::<Lit>::new(),
+        Unsat,
+        Unsat,
+        Unsat,
+        Sat,
+        Unsat,
+        Sat,
+        Sat,
+        Unsat
+    );
+}
+
+#[test]
+fn pairwise_exactly_one() {
+    test_exactly_one::<Pairwise>()
+}
+
+#[test]
+fn exactly_one_single_lit() {
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![1]);
+    let mut cnf = Cnf::new();
+    am1::default_encode_exactly_one([lit![0]], &mut cnf, &mut var_manager).unwrap();
+    assert_eq!(cnf.len(), 1);
+    assert_eq!(cnf[0].lits(), &[lit![0]]);
+}
@@ -1,16 +1,23 @@
 use rustsat::{
     clause,
-    encodings::card::{
-        simulators::{Double, Inverted},
-        BoundBoth, BoundBothIncremental, BoundUpperIncremental, DbTotalizer, Totalizer,
+    encodings::{
+        card::{
+            self,
+            simulators::{Double, Inverted},
+            BoundBoth, BoundBothIncremental, BoundUpper, BoundUpperIncremental, DbTotalizer,
+            Totalizer,
+        },
+        hamming,
+        pb::{self, GeneralizedTotalizer},
+        CnfDeduper, EncodeStats,
     },
-    instances::{BasicVarManager, ManageVars},
+    instances::{BasicVarManager, Cnf, ManageVars},
     lit,
     solvers::{
         Solve, SolveIncremental,
         SolverResult::{self, Sat, Unsat},
     },
-    types::Lit,
+    types::{constraints::CardConstraint, Assignment, Lit, TernaryVal},
     var,
 };
 
@@ -85,6 +92,10 @@ fn test_inc_both_card<CE: BoundBothIncremental + Extend<Lit> + Default>() {
 }
 
 fn test_inc_ub_card<CE: BoundUpperIncremental + Extend<Lit> + Default>() {
+    test_inc_ub_card_with(CE::default())
+}
+
+fn test_inc_ub_card_with<CE: BoundUpperIncremental + Extend<Lit>>(mut enc: CE) {
     // Set up instance
     let mut solver = rustsat_minisat::core::Minisat::default();
     solver.add_clause(clause![lit![0], lit![1]]).unwrap();
@@ -106,7 +117,6 @@ fn test_inc_ub_card<CE: BoundUpperIncremental + Extend<Lit> + Default>() {
     let res = solver.solve().unwrap();
     assert_eq!(res, SolverResult::Sat);
 
-    let mut enc = CE::default();
     enc.extend(vec![lit![0], lit![1], lit![2], lit![3], lit![4]]);
 
     enc.encode_ub(2..3, &mut solver, &mut var_manager).unwrap();
@@ -281,6 +291,14 @@ fn dbtot_inc_ub() {
 
 use rustsat_tools::{test_all, test_assignment};
 
+#[test]
+fn dyn_inc_ub() {
+    for name in card::DynUpperBounding::NAMES {
+        test_inc_ub_card_with(card::make(name).unwrap());
+    }
+    assert!(card::make("unknown").is_none());
+}
+
 fn test_ub_exhaustive<CE: BoundUpperIncremental + From<Vec<Lit>>>() {
     let mut solver = rustsat_minisat::core::Minisat::default();
     let mut enc = CE::from(vec![lit![0], lit![1], lit![2], lit![3]]);
@@ -553,3 +571,206 @@ fn invtot_both_exhaustive() {
 fn dbtot_ub_exhaustive() {
     test_ub_exhaustive::<DbTotalizer>()
 }
+
+fn test_implied_ub<CE: card::BoundUpper + FromIterator<Lit>>() {
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![5]);
+
+    let cond = lit![4];
+    card::encode_implied_ub::<CE, _, _>(
+        cond,
+        vec![lit![0], lit![1], lit![2], lit![3]],
+        1,
+        &mut solver,
+        &mut var_manager,
+    )
+    .unwrap();
+
+    test_all!(
+        solver,
+        vec![cond], //
+        Unsat,      // 1111
+        Unsat,      // 1110
+        Unsat,      // 1101
+        Unsat,      // 1100
+        Unsat,      // 1011
+        Unsat,      // 1010
+        Unsat,      // 1001
+        Sat,        // 1000
+        Unsat,      // 0111
+        Unsat,      // 0110
+        Unsat,      // 0101
+        Sat,        // 0100
+        Unsat,      // 0011
+        Sat,        // 0010
+        Sat,        // 0001
+        Sat         // 0000
+    );
+
+    test_all!(
+        solver,
+        vec![!cond], //
+        Sat,         // 1111
+        Sat,         // 1110
+        Sat,         // 1101
+        Sat,         // 1100
+        Sat,         // 1011
+        Sat,         // 1010
+        Sat,         // 1001
+        Sat,         // 1000
+        Sat,         // 0111
+        Sat,         // 0110
+        Sat,         // 0101
+        Sat,         // 0100
+        Sat,         // 0011
+        Sat,         // 0010
+        Sat,         // 0001
+        Sat          // 0000
+    );
+}
+
+#[test]
+fn tot_implied_ub() {
+    test_implied_ub::<Totalizer>()
+}
+
+#[test]
+fn dbtot_implied_ub() {
+    test_implied_ub::<DbTotalizer>()
+}
+
+#[test]
+fn implied_ub_trivial() {
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![4]);
+    let mut cnf = Cnf::new();
+    card::default_encode_implied_ub(
+        lit![3],
+        vec![lit![0], lit![1], lit![2]],
+        3,
+        &mut cnf,
+        &mut var_manager,
+    )
+    .unwrap();
+    assert!(cnf.is_empty());
+    assert_eq!(var_manager.n_used(), 4);
+}
+
+#[test]
+fn dedup_overlapping() {
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![3]);
+    let mut collector = CnfDeduper::new();
+    // Both constraints are encoded as the same clause
+    card::default_encode_cardinality_constraint(
+        CardConstraint::new_lb([lit![0], lit![1]], 1),
+        &mut collector,
+        &mut var_manager,
+    )
+    .unwrap();
+    card::default_encode_cardinality_constraint(
+        CardConstraint::new_lb([lit![1], lit![0]], 1),
+        &mut collector,
+        &mut var_manager,
+    )
+    .unwrap();
+    // Overlapping unit clauses
+    card::default_encode_cardinality_constraint(
+        CardConstraint::new_ub([lit![1], lit![2]], 0),
+        &mut collector,
+        &mut var_manager,
+    )
+    .unwrap();
+    card::default_encode_cardinality_constraint(
+        CardConstraint::new_ub([lit![2]], 0),
+        &mut collector,
+        &mut var_manager,
+    )
+    .unwrap();
+    assert_eq!(collector.n_duplicates(), 2);
+    let (cnf, n_duplicates) = collector.finish();
+    assert_eq!(n_duplicates, 2);
+    assert_eq!(cnf.len(), 3);
+}
+
+#[test]
+fn hamming_at_least() {
+    let reference: Assignment = [lit![0], !lit![1], lit![2], !lit![3]].into_iter().collect();
+    let vars = [var![0], var![1], var![2], var![3]];
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![4]);
+    let enc: Totalizer =
+        hamming::at_least(&reference, &vars, 2, &mut solver, &mut var_manager).unwrap();
+    let assumps = card::BoundLower::enforce_lb(&enc, 2).unwrap();
+    for assign in 0..16_u32 {
+        let mut all = assumps.clone();
+        let mut dist = 0;
+        for (idx, &var) in vars.iter().enumerate() {
+            let lit = if assign & (1 << idx) != 0 {
+                var.pos_lit()
+            } else {
+                var.neg_lit()
+            };
+            if reference.lit_value(lit) != TernaryVal::True {
+                dist += 1;
+            }
+            all.push(lit);
+        }
+        let expected = if dist >= 2 { Sat } else { Unsat };
+        assert_eq!(
+            solver.solve_assumps(&all).unwrap(),
+            expected,
+            "assignment: {assign:04b}"
+        );
+    }
+}
+
+#[test]
+fn tot_encode_ub_full() {
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    let lits: Vec<Lit> = (0..6).map(|idx| lit![idx]).collect();
+    let mut tot = Totalizer::from(lits.clone());
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![6]);
+    tot.encode_ub_full(&mut solver, &mut var_manager).unwrap();
+    // Enforce varying bounds like in a binary search without re-encoding
+    for k in [3, 0, 5, 1, 6, 2] {
+        let bound = tot.enforce_ub(k).unwrap();
+        for n_true in 0..=lits.len() {
+            let mut assumps = bound.clone();
+            assumps.extend(
+                lits.iter()
+                    .enumerate()
+                    .map(|(idx, &l)| if idx < n_true { l } else { !l }),
+            );
+            let expected = if n_true <= k { Sat } else { Unsat };
+            assert_eq!(
+                solver.solve_assumps(&assumps).unwrap(),
+                expected,
+                "k = {k}, n_true = {n_true}"
+            );
+        }
+    }
+}
+
+#[test]
+fn stats_trait_objects() {
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![6]);
+    let mut cnf = Cnf::new();
+    let mut tot = Totalizer::from((0..6).map(|idx| lit![idx]).collect::<Vec<_>>());
+    card::BoundUpper::encode_ub(&mut tot, 2..=3, &mut cnf, &mut var_manager).unwrap();
+    let mut gte = GeneralizedTotalizer::from_iter((0..6).map(|idx| (lit![idx], idx as usize + 1)));
+    pb::BoundUpper::encode_ub(&mut gte, 5..=8, &mut cnf, &mut var_manager).unwrap();
+    let encs: Vec<Box<dyn EncodeStats>> = vec![Box::new(tot), Box::new(gte)];
+    assert_eq!(
+        encs.iter().map(|enc| enc.n_clauses()).sum::<usize>(),
+        cnf.len()
+    );
+    assert_eq!(
+        encs.iter().map(|enc| enc.n_vars()).sum::<u32>(),
+        var_manager.n_used() - 6
+    );
+}
@@ -1,11 +1,12 @@
 use rustsat::{
-    instances::Cnf,
+    clause,
+    instances::{CheckError, Cnf},
     lit,
     solvers::{
         Solve, SolveIncremental,
         SolverResult::{Sat, Unsat},
     },
-    types::Lit,
+    types::{Assignment, Lit, TernaryVal},
 };
 use rustsat_tools::{test_all, test_assignment};
 
@@ -185,3 +186,130 @@ fn cnf_implications() {
         Sat                // 0000
     );
 }
+
+#[test]
+fn cnf_check_assignments() {
+    let mut cnf = Cnf::new();
+    cnf.add_lit_impl_lit(lit![0], lit![1]);
+    cnf.add_binary(lit![1], lit![2]);
+    let model = |lits: &[Lit]| lits.iter().copied().collect::<Assignment>();
+    let sat = [
+        model(&[lit![0], lit![1], lit![2]]),
+        model(&[!lit![0], !lit![1], lit![2]]),
+    ];
+    let unsat = [
+        model(&[lit![0], !lit![1], lit![2]]),
+        model(&[!lit![0], !lit![1], !lit![2]]),
+    ];
+    assert_eq!(cnf.check_assignments(&sat, &unsat), Ok(()));
+    assert_eq!(
+        cnf.check_assignments(&unsat, &[]),
+        Err(CheckError::ModelViolates {
+            assignment: 0,
+            clause: clause![!lit![0], lit![1]],
+        })
+    );
+    assert_eq!(
+        cnf.check_assignments(&[], &sat),
+        Err(CheckError::NonModelSatisfies { assignment: 0 })
+    );
+}
+
+#[test]
+fn cnf_display() {
+    let mut cnf = Cnf::new();
+    cnf.add_binary(lit![0], !lit![1]);
+    cnf.add_clause(clause![]);
+    cnf.add_unit(lit![2]);
+    assert_eq!(format!("{}", cnf), "1 -2 0\n0\n3 0\n");
+    assert_eq!(format!("{}", Cnf::new()), "");
+}
+
+#[test]
+fn cnf_write_dimacs_empty() {
+    let mut buf = vec![];
+    Cnf::new().write_dimacs(&mut buf, 0).unwrap();
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.ends_with("p cnf 0 0\n"));
+}
+
+#[test]
+fn cnf_iter_clauses() {
+    let mut cnf = Cnf::new();
+    cnf.add_cube_impl_lit(&[lit![0], lit![1]], lit![2]);
+    let clauses: Vec<&[Lit]> = cnf.iter().map(|cl| cl.lits()).collect();
+    assert_eq!(clauses, vec![&[!lit![0], !lit![1], lit![2]][..]]);
+    let clauses: Vec<Vec<Lit>> = cnf.into_iter().map(|cl| cl.into_iter().collect()).collect();
+    assert_eq!(clauses, vec![vec![!lit![0], !lit![1], lit![2]]]);
+}
+
+#[test]
+fn cnf_concat() {
+    let mut cnf1 = Cnf::new();
+    cnf1.add_binary(lit![0], lit![1]);
+    let mut cnf2 = Cnf::new();
+    cnf2.add_unit(lit![2]);
+    cnf2.add_ternary(lit![0], !lit![1], lit![3]);
+    let mut extended = cnf1.clone();
+    extended.extend_from_cnf(cnf2.clone());
+    assert_eq!(extended.len(), 3);
+    let joined = Cnf::concat([cnf1.clone(), Cnf::new(), cnf2.clone()]);
+    assert_eq!(joined.len(), cnf1.len() + cnf2.len());
+    assert_eq!(joined, extended);
+    assert_eq!(joined, cnf1.join(cnf2));
+    assert!(Cnf::concat([]).is_empty());
+}
+
+#[test]
+fn cnf_sanitize_in_place() {
+    let mut cnf = Cnf::new();
+    cnf.add_ternary(lit![0], !lit![1], lit![1]);
+    cnf.add_ternary(lit![2], lit![3], lit![2]);
+    cnf.add_unit(lit![4]);
+    cnf.add_clause(clause![]);
+    assert_eq!(cnf.sanitize_in_place(), 1);
+    let clauses: Vec<&[Lit]> = cnf.iter().map(|cl| cl.lits()).collect();
+    assert_eq!(
+        clauses,
+        vec![&[lit![2], lit![3]][..], &[lit![4]][..], &[][..]]
+    );
+    assert_eq!(cnf.sanitize_in_place(), 0);
+}
+
+#[test]
+fn cnf_evaluate_partial() {
+    let mut cnf = Cnf::new();
+    cnf.add_binary(lit![0], lit![1]);
+    cnf.add_binary(!lit![0], lit![2]);
+    // Second clause undecided
+    let assign = Assignment::from_iter([lit![0]]);
+    assert_eq!(cnf.evaluate(&assign), TernaryVal::DontCare);
+    // Second clause falsified
+    let assign = Assignment::from_iter([lit![0], !lit![2]]);
+    assert_eq!(cnf.evaluate(&assign), TernaryVal::False);
+    // False takes precedence over undecided clauses
+    let assign = Assignment::from_iter([!lit![0], !lit![1]]);
+    assert_eq!(cnf.evaluate(&assign), TernaryVal::False);
+    let assign = Assignment::from_iter([lit![0], lit![2]]);
+    assert_eq!(cnf.evaluate(&assign), TernaryVal::True);
+    assert_eq!(Cnf::new().evaluate(&assign), TernaryVal::True);
+}
+
+#[test]
+fn cnf_from_lit_vecs() {
+    let clauses = vec![
+        vec![lit![0], !lit![1]],
+        vec![lit![2]],
+        vec![],
+        vec![!lit![0], lit![1], lit![3]],
+    ];
+    let mut cnf: Cnf = clauses.clone().into_iter().collect();
+    assert_eq!(cnf.len(), 4);
+    let lits: Vec<&[Lit]> = cnf.iter().map(|cl| cl.lits()).collect();
+    let expected: Vec<&[Lit]> = clauses.iter().map(Vec::as_slice).collect();
+    assert_eq!(lits, expected);
+    cnf.extend(vec![vec![lit![4]], vec![!lit![2], lit![4]]]);
+    assert_eq!(cnf.len(), 6);
+    assert_eq!(cnf[4].lits(), &[lit![4]]);
+    assert_eq!(cnf[5].lits(), &[!lit![2], lit![4]]);
+}
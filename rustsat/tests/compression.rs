@@ -49,3 +49,14 @@ fn small_unsat_instance_bz2() {
     let res = solver.solve().unwrap();
     assert_eq!(res, SolverResult::Unsat);
 }
+
+#[test]
+fn compressed_matches_uncompressed() {
+    let plain: SatInstance<BasicVarManager> =
+        SatInstance::from_dimacs_path("./data/minisat-segfault.cnf").unwrap();
+    for ext in ["gz", "xz", "bz2"] {
+        let compressed: SatInstance<BasicVarManager> =
+            SatInstance::from_dimacs_path(format!("./data/minisat-segfault.cnf.{ext}")).unwrap();
+        assert_eq!(compressed.cnf(), plain.cnf(), "extension: {ext}");
+    }
+}
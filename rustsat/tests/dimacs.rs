@@ -0,0 +1,75 @@
+use std::io::Cursor;
+
+use rustsat::{
+    clause,
+    instances::{BasicVarManager, Cnf, OptInstance, SatInstance},
+    ipasir_lit,
+    types::Clause,
+};
+
+#[test]
+fn wcnf_pre22_hard_soft_split() {
+    let inst: OptInstance = OptInstance::from_dimacs_path("./data/small-pre22.wcnf").unwrap();
+    let mut true_hards = Cnf::new();
+    true_hards.add_clause(clause![ipasir_lit![1], ipasir_lit![2]]);
+    true_hards.add_clause(clause![ipasir_lit![2], ipasir_lit![3]]);
+    true_hards.add_clause(clause![ipasir_lit![3], ipasir_lit![4]]);
+    assert_eq!(inst.constraints_ref().cnf(), &true_hards);
+    let mut softs: Vec<(Clause, usize)> =
+        inst.objective_ref().iter_soft_cls().into_iter().collect();
+    softs.sort_unstable_by_key(|&(_, w)| w);
+    assert_eq!(
+        softs,
+        vec![
+            (clause![ipasir_lit![-1]], 2),
+            (clause![ipasir_lit![-3]], 3),
+            (clause![ipasir_lit![-2]], 4),
+            (clause![ipasir_lit![-4]], 15),
+        ]
+    );
+}
+
+#[test]
+fn wcnf_pre22_post22_equal() {
+    let pre22: OptInstance = OptInstance::from_dimacs_path("./data/small-pre22.wcnf").unwrap();
+    let post22: OptInstance = OptInstance::from_dimacs_path("./data/small.wcnf").unwrap();
+    assert_eq!(pre22, post22);
+}
+
+#[test]
+fn cnf_reader_matches_path() {
+    let data: &[u8] = b"c comment\np cnf 4 3\n1 -2 0\nc another comment\n2 3\n-4 0\n-1 4 0";
+    let from_reader = SatInstance::<BasicVarManager>::from_dimacs(Cursor::new(data)).unwrap();
+    let path = std::env::temp_dir().join(format!("rustsat-reader-{}.cnf", std::process::id()));
+    std::fs::write(&path, data).unwrap();
+    let from_path = SatInstance::<BasicVarManager>::from_dimacs_path(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(from_reader.cnf(), from_path.cnf());
+    let mut true_cnf = Cnf::new();
+    true_cnf.add_clause(clause![ipasir_lit![1], ipasir_lit![-2]]);
+    true_cnf.add_clause(clause![ipasir_lit![2], ipasir_lit![3], ipasir_lit![-4]]);
+    true_cnf.add_clause(clause![ipasir_lit![-1], ipasir_lit![4]]);
+    assert_eq!(from_reader.cnf(), &true_cnf);
+}
+
+#[test]
+fn cnf_write_path_round_trip() {
+    let inst =
+        SatInstance::<BasicVarManager>::from_dimacs_path("./data/minisat-segfault.cnf").unwrap();
+    let path = std::env::temp_dir().join(format!("rustsat-round-trip-{}.cnf", std::process::id()));
+    inst.write_dimacs_path(&path).unwrap();
+    let header = std::fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .find(|line| line.starts_with("p cnf"))
+        .map(String::from)
+        .unwrap();
+    let read_back = SatInstance::<BasicVarManager>::from_dimacs_path(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        header,
+        format!("p cnf {} {}", inst.n_vars(), inst.n_clauses())
+    );
+    assert_eq!(read_back.cnf(), inst.cnf());
+    assert_eq!(read_back.n_vars(), inst.n_vars());
+}
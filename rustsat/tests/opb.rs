@@ -81,3 +81,41 @@ fn opb_multi_opt() {
         MultiOptInstance::compose(true_constr, vec![true_obj_1, true_obj_2])
     );
 }
+
+#[test]
+fn opb_round_trip() {
+    let mut constr = SatInstance::new();
+    let mut lits = RsHashMap::default();
+    lits.insert(lit![0], 3);
+    lits.insert(lit![1], -2);
+    lits.insert(lit![2], 1);
+    constr.add_pb_constr(PBConstraint::new_ub(lits, 2));
+    let mut lits = RsHashMap::default();
+    lits.insert(lit![1], 4);
+    lits.insert(lit![3], -1);
+    constr.add_pb_constr(PBConstraint::new_lb(lits, -1));
+    let mut lits = RsHashMap::default();
+    lits.insert(lit![0], 1);
+    lits.insert(lit![3], 2);
+    constr.add_pb_constr(PBConstraint::new_eq(lits, 2));
+    let mut obj = Objective::new();
+    obj.increase_soft_lit_int(2, lit![0]);
+    obj.increase_soft_lit_int(-1, lit![2]);
+    let inst: OptInstance = OptInstance::compose(constr, obj);
+
+    let mut buf = vec![];
+    inst.write_opb(&mut buf, Options::default()).unwrap();
+    let parsed: OptInstance =
+        OptInstance::from_opb(std::io::Cursor::new(&buf), Options::default()).unwrap();
+    // Hint comment with the number of variables and constraints is ignored
+    assert!(String::from_utf8_lossy(&buf).starts_with("* #variable"));
+    let mut reparsed_buf = vec![];
+    parsed
+        .write_opb(&mut reparsed_buf, Options::default())
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        String::from_utf8(reparsed_buf).unwrap()
+    );
+    assert_eq!(parsed.constraints_ref().n_pbs(), 3);
+}
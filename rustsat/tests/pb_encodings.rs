@@ -1,20 +1,26 @@
+use std::cmp;
+
 use rustsat::{
     clause,
     encodings::{
+        absdiff,
         card::Totalizer,
+        graph::ConstraintGraph,
         pb::{
-            simulators::Card, BoundBoth, BoundBothIncremental, BoundLower, BoundUpper,
-            BoundUpperIncremental, DbGte, DoubleGeneralizedTotalizer, DynamicPolyWatchdog,
-            GeneralizedTotalizer, InvertedGeneralizedTotalizer,
+            self, simulators::Card, AdderPb, BoundBoth, BoundBothIncremental, BoundDir,
+            BoundDirected, BoundLower, BoundUpper, BoundUpperIncremental,
+            ChunkedGeneralizedTotalizer, DbGte, DoubleGeneralizedTotalizer, DynamicPolyWatchdog,
+            GeneralizedTotalizer, InvertedGeneralizedTotalizer, SequentialWeightCounter,
+            TreeStrategy,
         },
     },
-    instances::{BasicVarManager, ManageVars},
+    instances::{BasicVarManager, Cnf, ManageVars},
     lit,
     solvers::{
         Solve, SolveIncremental,
         SolverResult::{self, Sat, Unsat},
     },
-    types::{Lit, RsHashMap},
+    types::{Lit, RsHashMap, TernaryVal},
     var,
 };
 
@@ -262,6 +268,16 @@ fn gte_eq() {
     test_pb_eq::<DoubleGeneralizedTotalizer>()
 }
 
+#[test]
+fn gte_shared_lb() {
+    test_pb_lb::<GeneralizedTotalizer>()
+}
+
+#[test]
+fn gte_shared_eq() {
+    test_pb_eq::<GeneralizedTotalizer>()
+}
+
 #[test]
 fn tot_pb_sim_eq() {
     test_pb_eq::<Card<Totalizer>>()
@@ -282,8 +298,67 @@ fn dbgte_min_enc() {
     test_pb_ub_min_enc::<DbGte>()
 }
 
+#[test]
+fn swc_min_enc() {
+    test_pb_ub_min_enc::<SequentialWeightCounter>()
+}
+
 use rustsat_tools::{test_all, test_assignment};
 
+fn test_signed_ub<PBE: BoundUpper + Extend<(Lit, usize)> + Default>() {
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![3]);
+
+    // 2 x0 - 3 x1 + x2 <= 0
+    let mut enc = PBE::default();
+    let offset = pb::extend_signed(&mut enc, [(lit![0], 2), (lit![1], -3), (lit![2], 1)]);
+    assert_eq!(offset, 3);
+    enc.encode_ub(offset..=offset, &mut solver, &mut var_manager)
+        .unwrap();
+    let assumps = enc.enforce_ub(offset).unwrap();
+
+    test_all!(
+        solver, assumps, //
+        Sat,     // 111
+        Sat,     // 110
+        Unsat,   // 101
+        Unsat,   // 100
+        Sat,     // 011
+        Sat,     // 010
+        Unsat,   // 001
+        Sat      // 000
+    );
+
+    // Hand-normalized: 2 x0 + 3 !x1 + x2 <= 3
+    let mut enc = PBE::default();
+    enc.extend([(lit![0], 2), (!lit![1], 3), (lit![2], 1)]);
+    enc.encode_ub(3..=3, &mut solver, &mut var_manager).unwrap();
+    let assumps = enc.enforce_ub(3).unwrap();
+
+    test_all!(
+        solver, assumps, //
+        Sat,     // 111
+        Sat,     // 110
+        Unsat,   // 101
+        Unsat,   // 100
+        Sat,     // 011
+        Sat,     // 010
+        Unsat,   // 001
+        Sat      // 000
+    );
+}
+
+#[test]
+fn gte_signed_ub() {
+    test_signed_ub::<GeneralizedTotalizer>()
+}
+
+#[test]
+fn dbgte_signed_ub() {
+    test_signed_ub::<DbGte>()
+}
+
 fn test_ub_exhaustive<PBE: BoundUpperIncremental + From<RsHashMap<Lit, usize>>>(
     weights: [usize; 4],
     decreasing: bool,
@@ -341,6 +416,211 @@ fn test_ub_exhaustive<PBE: BoundUpperIncremental + From<RsHashMap<Lit, usize>>>(
     }
 }
 
+#[test]
+fn gte_enforce_window() {
+    let weights = [6, 5, 3, 1];
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    let mut enc =
+        GeneralizedTotalizer::from_iter((0..4).map(|idx| (lit![idx], weights[idx as usize])));
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![4]);
+    enc.encode_ub(.., &mut solver, &mut var_manager).unwrap();
+
+    let max_val = weights.iter().sum::<usize>();
+    let expected = |assign: usize, bound: usize| {
+        let sum = (0..4).fold(0, |sum, idx| sum + ((assign >> idx) & 1) * weights[3 - idx]);
+        if sum <= bound {
+            Sat
+        } else {
+            Unsat
+        }
+    };
+
+    for bound in 0..=max_val {
+        let single = enc.enforce_ub(bound).unwrap();
+        let assumps = enc.enforce_ub_window(bound, 3).unwrap();
+        assert!(single.iter().all(|l| assumps.contains(l)));
+
+        test_all!(
+            solver,
+            assumps, //
+            expected(0b1111, bound),
+            expected(0b1110, bound),
+            expected(0b1101, bound),
+            expected(0b1100, bound),
+            expected(0b1011, bound),
+            expected(0b1010, bound),
+            expected(0b1001, bound),
+            expected(0b1000, bound),
+            expected(0b0111, bound),
+            expected(0b0110, bound),
+            expected(0b0101, bound),
+            expected(0b0100, bound),
+            expected(0b0011, bound),
+            expected(0b0010, bound),
+            expected(0b0001, bound),
+            expected(0b0000, bound)
+        );
+    }
+}
+
+#[test]
+fn gte_enforce_minimal() {
+    let weights = [6, 5, 3, 1];
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    let mut enc =
+        GeneralizedTotalizer::from_iter((0..4).map(|idx| (lit![idx], weights[idx as usize])));
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![4]);
+    enc.encode_ub(.., &mut solver, &mut var_manager).unwrap();
+
+    let max_val = weights.iter().sum::<usize>();
+    let expected = |assign: usize, bound: usize| {
+        let sum = (0..4).fold(0, |sum, idx| sum + ((assign >> idx) & 1) * weights[3 - idx]);
+        if sum <= bound {
+            Sat
+        } else {
+            Unsat
+        }
+    };
+
+    for bound in 0..=max_val {
+        let standard = enc.enforce_ub(bound).unwrap();
+        let assumps = enc.enforce_ub_minimal(bound).unwrap();
+        assert!(assumps.iter().all(|l| standard.contains(l)));
+        if bound < 6 {
+            // The negation of the input with weight 6 is redundant
+            assert!(assumps.len() < standard.len());
+        }
+
+        test_all!(
+            solver,
+            assumps, //
+            expected(0b1111, bound),
+            expected(0b1110, bound),
+            expected(0b1101, bound),
+            expected(0b1100, bound),
+            expected(0b1011, bound),
+            expected(0b1010, bound),
+            expected(0b1001, bound),
+            expected(0b1000, bound),
+            expected(0b0111, bound),
+            expected(0b0110, bound),
+            expected(0b0101, bound),
+            expected(0b0100, bound),
+            expected(0b0011, bound),
+            expected(0b0010, bound),
+            expected(0b0001, bound),
+            expected(0b0000, bound)
+        );
+    }
+}
+
+#[test]
+fn gte_stable_outputs() {
+    let weights = [3, 2, 2, 1];
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    let mut enc = GeneralizedTotalizer::default().with_stable_outputs();
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![4]);
+
+    enc.extend([(lit![0], weights[0]), (lit![1], weights[1])]);
+    enc.encode_ub(.., &mut solver, &mut var_manager).unwrap();
+    let before = enc.enforce_ub(2).unwrap();
+    // Bound the first batch for good, as in a streaming setting
+    before.iter().try_for_each(|&l| solver.add_unit(l)).unwrap();
+
+    enc.extend([(lit![2], weights[2]), (lit![3], weights[3])]);
+    enc.encode_ub(.., &mut solver, &mut var_manager).unwrap();
+    let after = enc.enforce_ub(2).unwrap();
+    assert!(before.iter().all(|l| after.contains(l)));
+
+    // The units added for the first batch do not exclude solutions of the
+    // extended sum
+    let expected = |assign: usize| {
+        let sum = (0..4).fold(0, |sum, idx| sum + ((assign >> idx) & 1) * weights[3 - idx]);
+        if sum <= 2 {
+            Sat
+        } else {
+            Unsat
+        }
+    };
+    test_all!(
+        solver,
+        after,
+        expected(0b1111),
+        expected(0b1110),
+        expected(0b1101),
+        expected(0b1100),
+        expected(0b1011),
+        expected(0b1010),
+        expected(0b1001),
+        expected(0b1000),
+        expected(0b0111),
+        expected(0b0110),
+        expected(0b0101),
+        expected(0b0100),
+        expected(0b0011),
+        expected(0b0010),
+        expected(0b0001),
+        expected(0b0000)
+    );
+}
+
+/// Checks that a non-incremental encoding is equisatisfiable with the
+/// [`GeneralizedTotalizer`] for all bounds and input assignments
+fn test_ub_equisat_gte<PBE: BoundUpper + From<RsHashMap<Lit, usize>>>(weights: [usize; 4]) {
+    let lits: RsHashMap<Lit, usize> = weights
+        .iter()
+        .enumerate()
+        .map(|(idx, &w)| (lit![idx as u32], w))
+        .collect();
+    for bound in 0..=weights.iter().sum::<usize>() {
+        let mut enc_solver = rustsat_minisat::core::Minisat::default();
+        let mut enc = PBE::from(lits.clone());
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        enc.encode_ub(bound..bound + 1, &mut enc_solver, &mut var_manager)
+            .unwrap();
+        let enc_assumps = enc.enforce_ub(bound).unwrap();
+
+        let mut gte_solver = rustsat_minisat::core::Minisat::default();
+        let mut gte = GeneralizedTotalizer::from(lits.clone());
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![4]);
+        gte.encode_ub(bound..bound + 1, &mut gte_solver, &mut var_manager)
+            .unwrap();
+        let gte_assumps = gte.enforce_ub(bound).unwrap();
+
+        for assign in 0..16_u32 {
+            let inputs: Vec<Lit> = (0..4)
+                .map(|idx| {
+                    if assign & (1 << idx) != 0 {
+                        lit![idx]
+                    } else {
+                        !lit![idx]
+                    }
+                })
+                .collect();
+            let mut assumps = enc_assumps.clone();
+            assumps.extend(&inputs);
+            let enc_res = enc_solver.solve_assumps(&assumps).unwrap();
+            let mut assumps = gte_assumps.clone();
+            assumps.extend(&inputs);
+            let gte_res = gte_solver.solve_assumps(&assumps).unwrap();
+            assert_eq!(enc_res, gte_res, "bound: {bound}, assignment: {assign:04b}");
+        }
+    }
+}
+
+#[test]
+fn swc_equisat_gte() {
+    test_ub_equisat_gte::<SequentialWeightCounter>([1, 1, 1, 1]);
+    test_ub_equisat_gte::<SequentialWeightCounter>([5, 5, 3, 3]);
+    test_ub_equisat_gte::<SequentialWeightCounter>([2, 1, 7, 3]);
+    test_ub_equisat_gte::<SequentialWeightCounter>([8, 9, 1, 8]);
+}
+
 macro_rules! generate_exhaustive {
     ($mod:ident, $enc:ty) => {
         mod $mod {
@@ -598,3 +878,635 @@ mod dpw_inc_prec {
         );
     }
 }
+
+#[test]
+fn gte_reified_card_input() {
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![5]);
+    let mut graph = ConstraintGraph::default();
+    // First layer: `c` is implied if at least two of x0, x1, x2 are true
+    let card_lits = vec![lit![0], lit![1], lit![2]];
+    let mut tot = Totalizer::from(card_lits.clone());
+    let (card_node, res) = graph.encode(card_lits, &mut var_manager, |vm| {
+        rustsat::encodings::card::BoundUpper::encode_ub(&mut tot, 1..2, &mut solver, vm)
+    });
+    res.unwrap();
+    let c = !rustsat::encodings::card::BoundUpper::enforce_ub(&tot, 1).unwrap()[0];
+    // Second layer: c has weight 3 in the GTE
+    let pb_lits = [(c, 3), (lit![3], 2), (lit![4], 2)];
+    let mut gte = GeneralizedTotalizer::from_iter(pb_lits);
+    let (gte_node, res) = graph.encode(pb_lits.map(|(l, _)| l), &mut var_manager, |vm| {
+        gte.encode_ub(3..4, &mut solver, vm)
+    });
+    res.unwrap();
+    assert_eq!(graph.validate(), Ok(()));
+    assert_eq!(graph.dependencies(gte_node), vec![card_node]);
+    assert!(graph.dependencies(card_node).is_empty());
+
+    let assumps = gte.enforce_ub(3).unwrap();
+    let mut check = |inputs: &[Lit], expected: SolverResult| {
+        let mut all = assumps.clone();
+        all.extend(inputs);
+        assert_eq!(solver.solve_assumps(&all).unwrap(), expected);
+    };
+    check(&[lit![0], lit![1]], Sat);
+    check(&[lit![0], lit![1], lit![3]], Unsat);
+    check(&[lit![0], lit![3]], Sat);
+    check(&[lit![0], lit![3], lit![4]], Unsat);
+}
+
+/// Checks that two fully encoded GTEs over the inputs `0..n_inputs` are
+/// equisatisfiable for all bounds and input assignments
+fn assert_gte_equisat(
+    gte_a: &GeneralizedTotalizer,
+    cnf_a: Cnf,
+    gte_b: &GeneralizedTotalizer,
+    cnf_b: Cnf,
+    n_inputs: u32,
+) {
+    let mut solver_a = rustsat_minisat::core::Minisat::default();
+    solver_a.add_cnf(cnf_a).unwrap();
+    let mut solver_b = rustsat_minisat::core::Minisat::default();
+    solver_b.add_cnf(cnf_b).unwrap();
+    for ub in 0..=pb::Encode::weight_sum(gte_a) {
+        let assumps_a = gte_a.enforce_ub(ub).unwrap();
+        let assumps_b = gte_b.enforce_ub(ub).unwrap();
+        for assign in 0..1_u32 << n_inputs {
+            let inputs: Vec<Lit> = (0..n_inputs)
+                .map(|idx| {
+                    if assign & (1 << idx) != 0 {
+                        lit![idx]
+                    } else {
+                        !lit![idx]
+                    }
+                })
+                .collect();
+            let mut assumps = assumps_a.clone();
+            assumps.extend(&inputs);
+            let res_a = solver_a.solve_assumps(&assumps).unwrap();
+            let mut assumps = assumps_b.clone();
+            assumps.extend(&inputs);
+            let res_b = solver_b.solve_assumps(&assumps).unwrap();
+            assert_eq!(res_a, res_b, "bound: {ub}, assignment: {assign:b}");
+        }
+    }
+}
+
+#[test]
+fn gte_seeded_tie_breaking() {
+    let lits: Vec<(Lit, usize)> = (0..6)
+        .map(|idx| (lit![idx], idx as usize / 3 + 1))
+        .collect();
+    let encode = |seed: u64| {
+        let mut gte = GeneralizedTotalizer::from_iter(lits.clone()).with_seed(seed);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![6]);
+        let mut cnf = Cnf::new();
+        gte.encode_ub(.., &mut cnf, &mut var_manager).unwrap();
+        (gte, cnf)
+    };
+    let (gte_a, cnf_a) = encode(1);
+    let (_, cnf_a_again) = encode(1);
+    let (gte_b, cnf_b) = encode(2);
+    assert_eq!(cnf_a, cnf_a_again);
+    assert_ne!(cnf_a, cnf_b);
+    assert_gte_equisat(&gte_a, cnf_a, &gte_b, cnf_b, 6);
+}
+
+#[test]
+fn gte_compressed_equisat() {
+    let terms: Vec<(Lit, usize)> = (0..7)
+        .map(|idx| (lit![idx], [2, 3, 5][idx as usize % 3]))
+        .collect();
+    let encode = |mut gte: GeneralizedTotalizer| {
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![7]);
+        let mut cnf = Cnf::new();
+        gte.encode_ub(.., &mut cnf, &mut var_manager).unwrap();
+        (gte, cnf)
+    };
+    let (plain, plain_cnf) = encode(GeneralizedTotalizer::from_iter(terms.clone()));
+    let (compressed, compressed_cnf) = encode(GeneralizedTotalizer::from_compressed(&terms));
+    assert_gte_equisat(&plain, plain_cnf, &compressed, compressed_cnf, 7);
+}
+
+#[test]
+fn gte_huffman_equisat() {
+    // Many light and few heavy literals
+    let weights = [1, 1, 1, 1, 2, 2, 40, 100];
+    let terms: Vec<(Lit, usize)> = weights
+        .iter()
+        .enumerate()
+        .map(|(idx, &w)| (lit![idx as u32], w))
+        .collect();
+    let encode = |strategy: TreeStrategy| {
+        let mut gte = GeneralizedTotalizer::from_iter(terms.clone()).with_tree_strategy(strategy);
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![8]);
+        let mut cnf = Cnf::new();
+        gte.encode_ub(.., &mut cnf, &mut var_manager).unwrap();
+        (gte, cnf)
+    };
+    let (balanced, balanced_cnf) = encode(TreeStrategy::Balanced);
+    let (huffman, huffman_cnf) = encode(TreeStrategy::WeightHuffman);
+    assert!(huffman_cnf.len() <= balanced_cnf.len());
+    assert_gte_equisat(&balanced, balanced_cnf, &huffman, huffman_cnf, 8);
+}
+
+#[test]
+fn abs_diff_objective() {
+    let a = [(lit![0], 3), (lit![1], 1), (lit![2], 2)];
+    let b = [(lit![3], 2), (lit![4], 4), (lit![5], 1)];
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![6]);
+    let mut cnf = Cnf::new();
+    let terms = absdiff::default_abs_diff(&a, &b, &mut cnf, &mut var_manager).unwrap();
+    let mut obj = GeneralizedTotalizer::from_iter(terms);
+    obj.encode_ub(.., &mut cnf, &mut var_manager).unwrap();
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    solver.add_cnf(cnf).unwrap();
+    let weighted_sum = |terms: &[(Lit, usize)], bits: u32| -> usize {
+        terms
+            .iter()
+            .filter(|(l, _)| bits & (1 << l.vidx()) != 0)
+            .map(|(_, w)| w)
+            .sum()
+    };
+    for bits in 0..1_u32 << 6 {
+        let inputs: Vec<Lit> = (0..6)
+            .map(|idx| {
+                if bits & (1 << idx) != 0 {
+                    lit![idx]
+                } else {
+                    !lit![idx]
+                }
+            })
+            .collect();
+        let diff = weighted_sum(&a, bits).abs_diff(weighted_sum(&b, bits));
+        // Minimum objective value under the input assignment is the difference
+        let mut assumps = inputs.clone();
+        assumps.extend(obj.enforce_ub(diff).unwrap());
+        assert_eq!(solver.solve_assumps(&assumps).unwrap(), Sat);
+        if let Some(lower) = diff.checked_sub(1) {
+            let mut assumps = inputs;
+            assumps.extend(obj.enforce_ub(lower).unwrap());
+            assert_eq!(solver.solve_assumps(&assumps).unwrap(), Unsat);
+        }
+    }
+}
+
+#[test]
+fn pb_disjunction() {
+    // 3 x0 + 2 x1 + 2 x2 <= 2 OR 1 x1 + 4 x3 + 1 x4 <= 1
+    let terms1 = [(lit![0], 3), (lit![1], 2), (lit![2], 2)];
+    let terms2 = [(lit![1], 1), (lit![3], 4), (lit![4], 1)];
+    let mut enc1 = GeneralizedTotalizer::from_iter(terms1);
+    let mut enc2 = GeneralizedTotalizer::from_iter(terms2);
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![5]);
+    let mut cnf = Cnf::new();
+    let selectors =
+        pb::disjunction([(&mut enc1, 2), (&mut enc2, 1)], &mut cnf, &mut var_manager).unwrap();
+    assert_eq!(selectors.len(), 2);
+    cnf.add_clause(selectors);
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    solver.add_cnf(cnf).unwrap();
+    let weighted_sum = |terms: &[(Lit, usize)], bits: u32| -> usize {
+        terms
+            .iter()
+            .filter(|(l, _)| bits & (1 << l.vidx()) != 0)
+            .map(|(_, w)| w)
+            .sum()
+    };
+    for bits in 0..1_u32 << 5 {
+        let inputs: Vec<Lit> = (0..5)
+            .map(|idx| {
+                if bits & (1 << idx) != 0 {
+                    lit![idx]
+                } else {
+                    !lit![idx]
+                }
+            })
+            .collect();
+        let holds = weighted_sum(&terms1, bits) <= 2 || weighted_sum(&terms2, bits) <= 1;
+        assert_eq!(
+            solver.solve_assumps(&inputs).unwrap(),
+            if holds { Sat } else { Unsat },
+            "bits = {bits:05b}"
+        );
+    }
+}
+
+#[test]
+fn gte_chunked_equisat() {
+    const N: u32 = 40;
+    // Deterministic pseudo-random weights and assignments
+    let mut state: u64 = 42;
+    let mut next = move || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (state >> 33) as usize
+    };
+    let weights: Vec<usize> = (0..N).map(|_| 1 + next() % 12).collect();
+    let weight_sum: usize = weights.iter().sum();
+    let inputs = || (0..N).map(|idx| (lit![idx], weights[idx as usize]));
+
+    let mut mono_solver = rustsat_minisat::core::Minisat::default();
+    let mut mono = GeneralizedTotalizer::from_iter(inputs());
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![N]);
+    mono.encode_ub(.., &mut mono_solver, &mut var_manager)
+        .unwrap();
+
+    let mut chunked_solver = rustsat_minisat::core::Minisat::default();
+    let mut chunked = ChunkedGeneralizedTotalizer::new(30);
+    chunked.extend(inputs());
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![N]);
+    chunked
+        .encode_ub(.., &mut chunked_solver, &mut var_manager)
+        .unwrap();
+    assert!(chunked.n_chunks() > 1);
+
+    for _ in 0..30 {
+        let bound = next() % (weight_sum + 1);
+        // Fix a random subset of the inputs
+        let mut fixed: Vec<Lit> = vec![];
+        for idx in 0..N {
+            match next() % 3 {
+                0 => (),
+                1 => fixed.push(lit![idx]),
+                _ => fixed.push(!lit![idx]),
+            }
+        }
+        let mut mono_assumps = mono.enforce_ub(bound).unwrap();
+        mono_assumps.extend(&fixed);
+        let mut chunked_assumps = chunked.enforce_ub(bound).unwrap();
+        chunked_assumps.extend(&fixed);
+        let fixed_sum: usize = fixed
+            .iter()
+            .filter(|l| l.is_pos())
+            .map(|l| weights[l.vidx()])
+            .sum();
+        let expected = if fixed_sum <= bound { Sat } else { Unsat };
+        assert_eq!(mono_solver.solve_assumps(&mono_assumps).unwrap(), expected);
+        assert_eq!(
+            chunked_solver.solve_assumps(&chunked_assumps).unwrap(),
+            expected,
+            "bound = {bound}"
+        );
+    }
+}
+
+#[test]
+fn adder_equisat_gte() {
+    const N: u32 = 6;
+    let mut state: u64 = 7;
+    let mut next = move || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (state >> 33) as usize
+    };
+    for _ in 0..5 {
+        let weights: Vec<usize> = (0..N).map(|_| 1 + next() % 9).collect();
+        let weight_sum: usize = weights.iter().sum();
+        let inputs = || (0..N).map(|idx| (lit![idx], weights[idx as usize]));
+
+        let mut adder_solver = rustsat_minisat::core::Minisat::default();
+        let mut adder = AdderPb::from_iter(inputs());
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![N]);
+        adder
+            .encode_ub(.., &mut adder_solver, &mut var_manager)
+            .unwrap();
+
+        let mut gte_solver = rustsat_minisat::core::Minisat::default();
+        let mut gte = GeneralizedTotalizer::from_iter(inputs());
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![N]);
+        gte.encode_ub(.., &mut gte_solver, &mut var_manager)
+            .unwrap();
+
+        for _ in 0..4 {
+            let bound = next() % (weight_sum + 1);
+            for bits in 0..1_u32 << N {
+                let fixed: Vec<Lit> = (0..N)
+                    .map(|idx| {
+                        if bits & (1 << idx) != 0 {
+                            lit![idx]
+                        } else {
+                            !lit![idx]
+                        }
+                    })
+                    .collect();
+                let mut assumps = adder.enforce_ub(bound).unwrap();
+                assumps.extend(&fixed);
+                let adder_res = adder_solver.solve_assumps(&assumps).unwrap();
+                let mut assumps = gte.enforce_ub(bound).unwrap();
+                assumps.extend(&fixed);
+                let gte_res = gte_solver.solve_assumps(&assumps).unwrap();
+                assert_eq!(
+                    adder_res, gte_res,
+                    "weights = {weights:?}, bound = {bound}, bits = {bits:06b}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn double_gte_channel_onehot() {
+    let weights = [3, 1, 2, 2];
+    let weight_sum: usize = weights.iter().sum();
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    let mut enc =
+        DoubleGeneralizedTotalizer::from_iter((0..4).map(|idx| (lit![idx], weights[idx as usize])));
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![4]);
+    enc.encode_both(.., &mut solver, &mut var_manager).unwrap();
+    let onehot: Vec<Lit> = (0..=weight_sum).map(|_| var_manager.new_lit()).collect();
+    solver
+        .add_cnf(enc.channel_onehot(&onehot).unwrap())
+        .unwrap();
+
+    for bits in 0..1_usize << 4 {
+        let assumps: Vec<Lit> = (0..4)
+            .map(|idx| {
+                if bits & (1 << idx) != 0 {
+                    lit![idx as u32]
+                } else {
+                    !lit![idx as u32]
+                }
+            })
+            .collect();
+        let sum: usize = (0..4)
+            .filter(|idx| bits & (1 << idx) != 0)
+            .map(|idx| weights[idx])
+            .sum();
+        assert_eq!(solver.solve_assumps(&assumps).unwrap(), Sat);
+        for (val, &hot) in onehot.iter().enumerate() {
+            assert_eq!(
+                solver.lit_val(hot).unwrap(),
+                TernaryVal::from(val == sum),
+                "bits = {bits:04b}, val = {val}"
+            );
+        }
+    }
+    // Fixing the one-hot value fixes the sum
+    for (val, &hot) in onehot.iter().enumerate() {
+        assert_eq!(solver.solve_assumps(&[hot]).unwrap(), Sat);
+        let sum: usize = (0..4)
+            .filter(|&idx| solver.lit_val(lit![idx as u32]).unwrap() == TernaryVal::True)
+            .map(|idx| weights[idx])
+            .sum();
+        assert_eq!(sum, val);
+    }
+}
+
+/// Solution improving search that is generic over the bound direction
+fn descend<PBE>(dir: BoundDir) -> usize
+where
+    PBE: BoundDirected + FromIterator<(Lit, usize)>,
+{
+    let weights = [3, 1, 2, 2];
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    solver.add_clause(clause![lit![0], lit![1]]).unwrap();
+    solver.add_clause(clause![lit![2], lit![3]]).unwrap();
+    solver.add_clause(clause![!lit![0], !lit![2]]).unwrap();
+    let mut enc = PBE::from_iter((0..4).map(|idx| (lit![idx], weights[idx as usize])));
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![4]);
+    enc.encode_bound(dir, .., &mut solver, &mut var_manager)
+        .unwrap();
+    let mut assumps = vec![];
+    let mut best = None;
+    while solver.solve_assumps(&assumps).unwrap() == Sat {
+        let val: usize = (0..4)
+            .filter(|&idx| solver.lit_val(lit![idx]).unwrap() == TernaryVal::True)
+            .map(|idx| weights[idx as usize])
+            .sum();
+        best = Some(val);
+        let next = match dir {
+            BoundDir::Upper if val > 0 => val - 1,
+            BoundDir::Lower if val < enc.weight_sum() => val + 1,
+            _ => break,
+        };
+        assumps = enc.enforce_bound(dir, next).unwrap();
+    }
+    best.unwrap()
+}
+
+#[test]
+fn directed_descent() {
+    assert_eq!(descend::<DoubleGeneralizedTotalizer>(BoundDir::Upper), 3);
+    assert_eq!(descend::<DoubleGeneralizedTotalizer>(BoundDir::Lower), 6);
+}
+
+/// Checks an upper bounding encoding against a brute-force oracle by
+/// enumerating all assignments of the input literals
+fn check_pb_encoding_exhaustive<PBE>(lits: &[(Lit, usize)], bound: usize)
+where
+    PBE: BoundUpper + FromIterator<(Lit, usize)>,
+{
+    let n_vars = lits.iter().map(|(l, _)| l.vidx32() + 1).max().unwrap_or(0);
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    let mut enc = PBE::from_iter(lits.iter().copied());
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![n_vars]);
+    enc.encode_ub(bound..=bound, &mut solver, &mut var_manager)
+        .unwrap();
+    let bound_assumps = enc.enforce_ub(bound).unwrap();
+    for bits in 0..1_u32 << n_vars {
+        let mut assumps = bound_assumps.clone();
+        assumps.extend((0..n_vars).map(|idx| {
+            if bits & (1 << idx) != 0 {
+                lit![idx]
+            } else {
+                !lit![idx]
+            }
+        }));
+        let sum: usize = lits
+            .iter()
+            .filter(|(l, _)| (bits & (1 << l.vidx32()) != 0) == l.is_pos())
+            .map(|(_, w)| w)
+            .sum();
+        let expected = if sum <= bound { Sat } else { Unsat };
+        assert_eq!(
+            solver.solve_assumps(&assumps).unwrap(),
+            expected,
+            "lits = {lits:?}, bound = {bound}, bits = {bits:b}"
+        );
+    }
+}
+
+/// Checks an upper bounding encoding exhaustively on small pseudo-random
+/// instances
+fn check_pb_encoding_random<PBE>()
+where
+    PBE: BoundUpper + FromIterator<(Lit, usize)>,
+{
+    let mut state: u64 = 1795;
+    let mut next = move |modulus: usize| {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (state >> 33) as usize % modulus
+    };
+    for _ in 0..20 {
+        let n_lits = 1 + next(6) as u32;
+        let lits: Vec<(Lit, usize)> = (0..n_lits)
+            .map(|idx| {
+                let lit = if next(2) == 0 { lit![idx] } else { !lit![idx] };
+                (lit, 1 + next(10))
+            })
+            .collect();
+        let weight_sum: usize = lits.iter().map(|(_, w)| w).sum();
+        let bound = next(weight_sum + 2);
+        check_pb_encoding_exhaustive::<PBE>(&lits, bound);
+    }
+}
+
+#[test]
+fn gte_random_exhaustive() {
+    check_pb_encoding_random::<GeneralizedTotalizer>();
+}
+
+#[test]
+fn gte_oneshot_random_exhaustive() {
+    let mut state: u64 = 1761;
+    let mut next = move |modulus: usize| {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (state >> 33) as usize % modulus
+    };
+    for _ in 0..30 {
+        let n_lits = 2 + next(5) as u32;
+        let lits: Vec<(Lit, usize)> = (0..n_lits).map(|idx| (lit![idx], 1 + next(10))).collect();
+        let weight_sum: usize = lits.iter().map(|(_, w)| w).sum();
+        let min_ub = next(weight_sum);
+        let max_ub = min_ub + next(weight_sum - min_ub);
+        let mut solver = rustsat_minisat::core::Minisat::default();
+        let mut enc = GeneralizedTotalizer::from_iter(lits.iter().copied());
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![n_lits]);
+        enc.encode_ub_oneshot(min_ub..=max_ub, &mut solver, &mut var_manager)
+            .unwrap();
+        // Check every bound that can be enforced, not only the requested ones
+        let bounds = enc.encoded_range().map_or(min_ub..max_ub + 1, |range| {
+            cmp::min(min_ub, range.start)..cmp::max(max_ub + 1, range.end)
+        });
+        for bound in bounds {
+            let bound_assumps = enc.enforce_ub(bound).unwrap();
+            for bits in 0..1_u32 << n_lits {
+                let mut assumps = bound_assumps.clone();
+                assumps.extend((0..n_lits).map(|idx| {
+                    if bits & (1 << idx) != 0 {
+                        lit![idx]
+                    } else {
+                        !lit![idx]
+                    }
+                }));
+                let sum: usize = lits
+                    .iter()
+                    .filter(|(l, _)| bits & (1 << l.vidx32()) != 0)
+                    .map(|(_, w)| w)
+                    .sum();
+                let expected = if sum <= bound { Sat } else { Unsat };
+                assert_eq!(
+                    solver.solve_assumps(&assumps).unwrap(),
+                    expected,
+                    "lits = {lits:?}, range = {min_ub}..={max_ub}, bound = {bound}, bits = {bits:b}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn swc_random_exhaustive() {
+    check_pb_encoding_random::<SequentialWeightCounter>();
+}
+
+#[test]
+fn adder_random_exhaustive() {
+    check_pb_encoding_random::<AdderPb>();
+}
+
+#[test]
+fn gte_tighten_ub_descent() {
+    let weights = [4, 3, 2, 5, 1];
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    solver.add_clause(clause![lit![0], lit![1]]).unwrap();
+    solver
+        .add_clause(clause![lit![1], lit![2], lit![3]])
+        .unwrap();
+    solver.add_clause(clause![lit![3], lit![4]]).unwrap();
+    solver.add_clause(clause![lit![0], lit![4]]).unwrap();
+    let cost = |solver: &rustsat_minisat::core::Minisat| -> usize {
+        (0..5)
+            .filter(|&idx| solver.lit_val(lit![idx]).unwrap() == TernaryVal::True)
+            .map(|idx| weights[idx as usize])
+            .sum()
+    };
+    let mut enc =
+        GeneralizedTotalizer::from_iter((0..5).map(|idx| (lit![idx], weights[idx as usize])));
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![5]);
+
+    assert_eq!(solver.solve().unwrap(), Sat);
+    let mut best = cost(&solver);
+    while best > 0 {
+        let (cnf, assumps) = enc.tighten_ub(best - 1, &mut var_manager).unwrap();
+        solver.add_cnf(cnf).unwrap();
+        if solver.solve_assumps(&assumps).unwrap() == Unsat {
+            break;
+        }
+        let next = cost(&solver);
+        assert!(next < best);
+        best = next;
+    }
+    // Optimum is `lit![1]` and `lit![4]`
+    assert_eq!(best, 4);
+}
+
+#[test]
+fn gte_enforce_ge() {
+    let weights = [5, 3, 2, 2, 1];
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    let mut enc =
+        GeneralizedTotalizer::from_iter((0..5).map(|idx| (lit![idx], weights[idx as usize])));
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![5]);
+    assert_eq!(
+        enc.enforce_ge(1),
+        Err(rustsat::encodings::Error::NotEncoded)
+    );
+    let mut cnf = Cnf::new();
+    enc.encode_ge(&mut cnf, &mut var_manager).unwrap();
+    solver.add_cnf(cnf).unwrap();
+    assert_eq!(enc.enforce_ge(14), Err(rustsat::encodings::Error::Unsat));
+    for inputs in 0..32_u32 {
+        let sum: usize = (0..5)
+            .filter(|idx| inputs >> idx & 1 == 1)
+            .map(|idx| weights[idx])
+            .sum();
+        let input_assumps: Vec<Lit> = (0..5)
+            .map(|idx| Lit::new(idx, inputs >> idx & 1 == 0))
+            .collect();
+        for lb in 0..=13 {
+            let mut assumps = input_assumps.clone();
+            assumps.extend(enc.enforce_ge(lb).unwrap());
+            let res = solver.solve_assumps(&assumps).unwrap();
+            assert_eq!(res == Sat, sum >= lb, "sum {} lb {}", sum, lb);
+            // Upper and lower bounds can be enforced on the same tree
+            let ub = lb + 2;
+            assumps.extend(enc.enforce_ub(ub).unwrap());
+            let res = solver.solve_assumps(&assumps).unwrap();
+            assert_eq!(res == Sat, sum >= lb && sum <= ub);
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use rustsat::{
+    clause,
+    encodings::pb::GeneralizedTotalizer,
+    instances::{BasicVarManager, Cnf, ManageVars, Objective},
+    lit,
+    solvers::{
+        optimize::{greedy_ub, linear_sat_unsat, unsat_sat},
+        Solve, SolveIncremental, SolveStats, SolverResult,
+    },
+    types::TernaryVal,
+    var,
+};
+
+#[test]
+fn solve_all_lazy() {
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    // Six variables in two disjoint clauses, 49 models
+    solver.add_ternary(lit![0], lit![1], lit![2]).unwrap();
+    solver.add_ternary(lit![3], lit![4], lit![5]).unwrap();
+    let models: Vec<_> = solver
+        .solve_all(var![5])
+        .take(2)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(models.len(), 2);
+    assert_ne!(models[0], models[1]);
+    // Only as many solver calls as models taken
+    assert_eq!(solver.stats().n_sat, 2);
+    assert_eq!(solver.stats().n_unsat, 0);
+    // Solver is still usable and the taken models are blocked
+    assert_eq!(solver.solve().unwrap(), SolverResult::Sat);
+    assert_eq!(solver.solve_all(var![5]).count(), 47);
+    assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+}
+
+#[test]
+fn add_clause_if_useful() {
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    solver.add_binary(lit![0], lit![1]).unwrap();
+    solver.add_unit(!lit![1]).unwrap();
+    let n_clauses = solver.stats().n_clauses;
+    // Entailed by unit propagation
+    assert!(!solver
+        .add_clause_if_useful(clause![lit![0], lit![2]])
+        .unwrap());
+    assert_eq!(solver.stats().n_clauses, n_clauses);
+    assert!(solver
+        .add_clause_if_useful(clause![lit![2], lit![3]])
+        .unwrap());
+    assert_eq!(solver.stats().n_clauses, n_clauses + 1);
+    assert_eq!(
+        solver.solve_assumps(&[!lit![2], !lit![3]]).unwrap(),
+        SolverResult::Unsat
+    );
+    // Negation propagates to a conflict
+    solver.add_binary(lit![4], lit![5]).unwrap();
+    solver.add_binary(lit![4], !lit![5]).unwrap();
+    let n_clauses = solver.stats().n_clauses;
+    assert!(!solver
+        .add_clause_if_useful(clause![lit![4], lit![6]])
+        .unwrap());
+    assert_eq!(solver.stats().n_clauses, n_clauses);
+}
+
+#[test]
+fn add_clause_if_useful_bounded() {
+    // Pigeon hole principle with 5 pigeons and 4 holes, only refuted by search
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    let (n_pigeons, n_holes) = (5, 4);
+    let hole = |pigeon: u32, hole: u32| lit![2 + pigeon * n_holes + hole];
+    for pigeon in 0..n_pigeons {
+        solver
+            .add_clause((0..n_holes).map(|h| hole(pigeon, h)).collect())
+            .unwrap();
+    }
+    for h in 0..n_holes {
+        for p1 in 0..n_pigeons {
+            for p2 in p1 + 1..n_pigeons {
+                solver.add_binary(!hole(p1, h), !hole(p2, h)).unwrap();
+            }
+        }
+    }
+    let n_clauses = solver.stats().n_clauses;
+    // Entailed, but the check gives up and adds the clause
+    assert!(solver
+        .add_clause_if_useful(clause![lit![0], lit![1]])
+        .unwrap());
+    assert_eq!(solver.stats().n_clauses, n_clauses + 1);
+    // The limit is removed afterwards
+    assert_eq!(solver.solve().unwrap(), SolverResult::Unsat);
+}
+
+#[test]
+fn greedy_ub_valid() {
+    let mut hard = Cnf::new();
+    hard.add_binary(lit![0], lit![1]);
+    hard.add_binary(lit![1], lit![2]);
+    let obj = Objective::from_iter([(lit![0], 1_usize), (lit![1], 3), (lit![2], 1)]);
+    // Optimum is setting `lit![0]` and `lit![2]` with cost 2
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    let (ub, sol) = greedy_ub(&hard, &obj, &mut solver).unwrap().unwrap();
+    assert!(ub >= 2);
+    assert_eq!(ub, obj.evaluate_no_offset(&sol));
+    assert!(hard.iter().all(|cl| cl.is_sat(&sol)));
+
+    hard.add_unit(!lit![1]);
+    hard.add_unit(!lit![0]);
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    assert!(greedy_ub(&hard, &obj, &mut solver).unwrap().is_none());
+}
+
+#[test]
+fn linear_sat_unsat_optimum() {
+    let mut solver = rustsat_minisat::core::Minisat::default();
+    solver.add_binary(lit![0], lit![1]).unwrap();
+    solver.add_ternary(lit![1], lit![2], lit![3]).unwrap();
+    solver.add_binary(lit![3], lit![4]).unwrap();
+    solver.add_binary(lit![0], lit![4]).unwrap();
+    let weights = [4, 3, 2, 5, 1];
+    let mut obj =
+        GeneralizedTotalizer::from_iter((0..5).map(|idx| (lit![idx], weights[idx as usize])));
+    let mut var_manager = BasicVarManager::default();
+    var_manager.increase_next_free(var![5]);
+    let (cost, sol) = linear_sat_unsat(&mut solver, &mut obj, &mut var_manager)
+        .unwrap()
+        .unwrap();
+    // Optimum is setting `lit![1]` and `lit![4]`
+    assert_eq!(cost, 4);
+    assert_eq!(sol.lit_value(lit![1]), TernaryVal::True);
+    assert_eq!(sol.lit_value(lit![4]), TernaryVal::True);
+
+    solver.add_unit(!lit![0]).unwrap();
+    solver.add_unit(!lit![1]).unwrap();
+    assert!(linear_sat_unsat(&mut solver, &mut obj, &mut var_manager)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn optimization_drivers_agree() {
+    let mut state: u64 = 1815;
+    let mut next = move |modulus: u32| {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (state >> 33) as u32 % modulus
+    };
+    for _ in 0..30 {
+        let n_vars = 3 + next(5);
+        let mut hard = Cnf::new();
+        for _ in 0..1 + next(2 * n_vars) {
+            hard.add_clause(
+                (0..1 + next(3))
+                    .map(|_| {
+                        let lit = lit![next(n_vars)];
+                        if next(2) == 0 {
+                            lit
+                        } else {
+                            !lit
+                        }
+                    })
+                    .collect(),
+            );
+        }
+        let obj: Vec<_> = (0..n_vars)
+            .map(|idx| (lit![idx], 1 + next(6) as usize))
+            .collect();
+
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![n_vars]);
+        let mut solver = rustsat_minisat::core::Minisat::default();
+        solver.add_cnf_ref(&hard).unwrap();
+        let mut gte = GeneralizedTotalizer::from_iter(obj.iter().copied());
+        let linear = linear_sat_unsat(&mut solver, &mut gte, &mut var_manager).unwrap();
+
+        let mut var_manager = BasicVarManager::default();
+        var_manager.increase_next_free(var![n_vars]);
+        let mut solver = rustsat_minisat::core::Minisat::default();
+        solver.add_cnf_ref(&hard).unwrap();
+        let core_guided = unsat_sat(&mut solver, obj.iter().copied(), &mut var_manager).unwrap();
+
+        match (linear, core_guided) {
+            (None, None) => (),
+            (Some((lin_cost, _)), Some((cg_cost, cg_sol))) => {
+                assert_eq!(lin_cost, cg_cost, "hard = {hard:?}, obj = {obj:?}");
+                assert!(hard.iter().all(|cl| cl.is_sat(&cg_sol)));
+            }
+            (linear, core_guided) => panic!(
+                "drivers disagree on satisfiability: {:?} vs {:?}",
+                linear.map(|(c, _)| c),
+                core_guided.map(|(c, _)| c)
+            ),
+        }
+    }
+}
@@ -7,8 +7,7 @@
 use anyhow::Context;
 use rustsat::{
     instances::{ManageVars, SatInstance},
-    solvers::{self, Solve, SolveIncremental},
-    types::{Assignment, Var},
+    solvers::Solve,
 };
 
 macro_rules! print_usage {
@@ -18,34 +17,6 @@ macro_rules! print_usage {
     }};
 }
 
-struct Enumerator<S: SolveIncremental> {
-    solver: S,
-    max_var: Var,
-}
-
-impl<S: SolveIncremental> Iterator for Enumerator<S> {
-    type Item = Assignment;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.solver.solve().expect("error while solving") {
-            solvers::SolverResult::Sat => {
-                let sol = self
-                    .solver
-                    .solution(self.max_var)
-                    .expect("could not get solution from solver");
-                // Add blocking clause to solver
-                let bl_cl = sol.clone().into_iter().map(|l| !l).collect();
-                self.solver
-                    .add_clause(bl_cl)
-                    .expect("error adding blocking clause to solver");
-                Some(sol)
-            }
-            solvers::SolverResult::Unsat => None,
-            solvers::SolverResult::Interrupted => panic!("solver interrupted without limits"),
-        }
-    }
-}
-
 fn main() -> anyhow::Result<()> {
     let in_path = std::env::args().nth(1).unwrap_or_else(|| print_usage!());
 
@@ -59,11 +30,11 @@ fn main() -> anyhow::Result<()> {
         .context("error reserving memory in solver")?;
     solver.add_cnf(cnf).expect("error adding cnf to solver");
 
-    let enumerator = Enumerator {
-        solver,
-        max_var: vm.max_var().unwrap(),
-    };
-
-    enumerator.for_each(|sol| println!("s {}", sol));
+    solver
+        .solve_all(vm.max_var().unwrap())
+        .try_for_each(|sol| {
+            println!("s {}", sol?);
+            anyhow::Ok(())
+        })?;
     Ok(())
 }